@@ -62,12 +62,15 @@ use jj_lib::git;
 use jj_lib::git_backend::GitBackend;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
+use jj_lib::hex_util::to_forward_hex;
 use jj_lib::hex_util::to_reverse_hex;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::object_id::HexPrefix;
 use jj_lib::object_id::ObjectId;
+use jj_lib::object_id::PrefixResolution;
 use jj_lib::op_heads_store;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_store::OperationId;
@@ -107,6 +110,7 @@ use jj_lib::signing::SignInitError;
 use jj_lib::str_util::StringPattern;
 use jj_lib::transaction::Transaction;
 use jj_lib::view::View;
+use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::LockedWorkingCopy;
 use jj_lib::working_copy::SnapshotOptions;
@@ -786,6 +790,13 @@ impl WorkspaceCommandHelper {
         Ok((locked_ws, wc_commit))
     }
 
+    /// Options for checking out commits in the working copy.
+    pub fn checkout_options(&self) -> Result<CheckoutOptions, CommandError> {
+        Ok(CheckoutOptions {
+            conflict_marker_style: self.settings.conflict_marker_style()?,
+        })
+    }
+
     pub fn start_working_copy_mutation(
         &mut self,
     ) -> Result<(LockedWorkspace, Commit), CommandError> {
@@ -992,6 +1003,34 @@ impl WorkspaceCommandHelper {
         )
     }
 
+    /// Resolves a change ID (or a unique prefix of it) to the visible commit
+    /// of the change. Returns an error if the change is divergent.
+    pub fn resolve_change_id(&self, change_str: &str) -> Result<Commit, CommandError> {
+        let Some(prefix) = to_forward_hex(change_str)
+            .as_deref()
+            .and_then(HexPrefix::new)
+        else {
+            return Err(user_error(format!("Invalid change ID: {change_str}")));
+        };
+        let repo = self.repo().as_ref();
+        match self
+            .id_prefix_context()?
+            .resolve_change_prefix(repo, &prefix)
+        {
+            PrefixResolution::NoMatch => Err(user_error(format!("No such change: {change_str}"))),
+            PrefixResolution::AmbiguousMatch => Err(user_error(format!(
+                "Change ID prefix \"{change_str}\" is ambiguous"
+            ))),
+            PrefixResolution::SingleMatch(ids) => match &ids[..] {
+                [id] => Ok(repo.store().get_commit(id)?),
+                _ => Err(user_error_with_hint(
+                    format!("Change {change_str} is divergent"),
+                    format!("Use `jj log -r {change_str}` to see the divergent commits."),
+                )),
+            },
+        }
+    }
+
     /// Evaluates revset expressions to non-empty set of commits. The returned
     /// set preserves the order of the input expressions.
     ///
@@ -1099,8 +1138,9 @@ impl WorkspaceCommandHelper {
         };
         RevsetParseContext::new(
             &self.revset_aliases_map,
-            self.settings.user_email(),
+            self.settings.user_emails(),
             now.into(),
+            self.settings.diff_max_scan_bytes(),
             &self.revset_extensions,
             Some(workspace_context),
         )
@@ -1342,6 +1382,7 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
             fsmonitor_settings: self.settings.fsmonitor_settings()?,
             progress: progress.as_ref().map(|x| x as _),
             max_new_file_size: self.settings.max_new_file_size()?,
+            conflict_marker_style: self.settings.conflict_marker_style()?,
         })?;
         drop(progress);
         if new_tree_id != *wc_commit.tree_id() {
@@ -1382,11 +1423,13 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
         new_commit: &Commit,
     ) -> Result<(), CommandError> {
         assert!(self.may_update_working_copy);
+        let checkout_options = self.checkout_options()?;
         let stats = update_working_copy(
             &self.user_repo.repo,
             &mut self.workspace,
             maybe_old_commit,
             new_commit,
+            &checkout_options,
         )?;
         if Some(new_commit) != maybe_old_commit {
             if let Some(mut formatter) = ui.status_formatter() {
@@ -1892,32 +1935,35 @@ pub fn start_repo_transaction(
     let mut tx = repo.start_transaction(settings);
     // TODO: Either do better shell-escaping here or store the values in some list
     // type (which we currently don't have).
-    let shell_escape = |arg: &String| {
-        if arg.as_bytes().iter().all(|b| {
-            matches!(b,
-                b'A'..=b'Z'
-                | b'a'..=b'z'
-                | b'0'..=b'9'
-                | b','
-                | b'-'
-                | b'.'
-                | b'/'
-                | b':'
-                | b'@'
-                | b'_'
-            )
-        }) {
-            arg.clone()
-        } else {
-            format!("'{}'", arg.replace('\'', "\\'"))
-        }
-    };
     let mut quoted_strings = vec!["jj".to_string()];
-    quoted_strings.extend(string_args.iter().skip(1).map(shell_escape));
+    quoted_strings.extend(string_args.iter().skip(1).map(|arg| shell_escape(arg)));
     tx.set_tag("args".to_string(), quoted_strings.join(" "));
     tx
 }
 
+/// Quotes the command-line argument if it contains characters other than the
+/// ones known to be safe in shells.
+pub fn shell_escape(arg: &str) -> String {
+    if arg.as_bytes().iter().all(|b| {
+        matches!(b,
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b','
+            | b'-'
+            | b'.'
+            | b'/'
+            | b':'
+            | b'@'
+            | b'_'
+        )
+    }) {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', "\\'"))
+    }
+}
+
 /// Whether the working copy is stale or not.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum WorkingCopyFreshness {
@@ -2164,13 +2210,19 @@ pub fn update_working_copy(
     workspace: &mut Workspace,
     old_commit: Option<&Commit>,
     new_commit: &Commit,
+    options: &CheckoutOptions,
 ) -> Result<Option<CheckoutStats>, CommandError> {
     let old_tree_id = old_commit.map(|commit| commit.tree_id().clone());
     let stats = if Some(new_commit.tree_id()) != old_tree_id.as_ref() {
         // TODO: CheckoutError::ConcurrentCheckout should probably just result in a
         // warning for most commands (but be an error for the checkout command)
         let stats = workspace
-            .check_out(repo.op_id().clone(), old_tree_id.as_ref(), new_commit)
+            .check_out(
+                repo.op_id().clone(),
+                old_tree_id.as_ref(),
+                new_commit,
+                options,
+            )
             .map_err(|err| {
                 internal_error_with_message(
                     format!("Failed to check out commit {}", new_commit.id().hex()),
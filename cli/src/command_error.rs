@@ -46,6 +46,7 @@ use jj_lib::revset::RevsetParseErrorKind;
 use jj_lib::revset::RevsetResolutionError;
 use jj_lib::signing::SignInitError;
 use jj_lib::str_util::StringPatternParseError;
+use jj_lib::time_util::DatePatternParseError;
 use jj_lib::working_copy::ResetError;
 use jj_lib::working_copy::SnapshotError;
 use jj_lib::working_copy::WorkingCopyStateError;
@@ -60,6 +61,7 @@ use crate::formatter::Formatter;
 use crate::merge_tools::ConflictResolveError;
 use crate::merge_tools::DiffEditError;
 use crate::merge_tools::MergeToolConfigError;
+use crate::rebase_todo::ParseRebaseTodoError;
 use crate::revset_util::UserRevsetEvaluationError;
 use crate::template_parser::TemplateParseError;
 use crate::template_parser::TemplateParseErrorKind;
@@ -568,6 +570,12 @@ impl From<ParseBulkEditMessageError> for CommandError {
     }
 }
 
+impl From<ParseRebaseTodoError> for CommandError {
+    fn from(err: ParseRebaseTodoError) -> Self {
+        user_error(err)
+    }
+}
+
 fn find_source_parse_error_hint(err: &dyn error::Error) -> Option<String> {
     let source = err.source()?;
     if let Some(source) = source.downcast_ref() {
@@ -582,6 +590,8 @@ fn find_source_parse_error_hint(err: &dyn error::Error) -> Option<String> {
         revset_resolution_error_hint(source)
     } else if let Some(source) = source.downcast_ref() {
         string_pattern_parse_error_hint(source)
+    } else if let Some(source) = source.downcast_ref() {
+        date_pattern_parse_error_hint(source)
     } else if let Some(source) = source.downcast_ref() {
         template_parse_error_hint(source)
     } else {
@@ -685,6 +695,19 @@ fn string_pattern_parse_error_hint(err: &StringPatternParseError) -> Option<Stri
     }
 }
 
+fn date_pattern_parse_error_hint(err: &DatePatternParseError) -> Option<String> {
+    match err {
+        DatePatternParseError::InvalidKind(_) => {
+            Some("Try prefixing with one of `after:` or `before:`".into())
+        }
+        DatePatternParseError::ParseError { .. } => Some(
+            "Dates can be specified as `2024-02-01`, `2024-02-01T12:00:00`, \
+             `2024-02-01T12:00:00-08:00`, `2 days ago`, `yesterday 5pm`, etc."
+                .into(),
+        ),
+    }
+}
+
 fn template_parse_error_hint(err: &TemplateParseError) -> Option<String> {
     // Only for the bottom error, which is usually the root cause
     let bottom_err = iter::successors(Some(err), |e| e.origin()).last().unwrap();
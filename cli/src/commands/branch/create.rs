@@ -12,13 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
 use clap::builder::NonEmptyStringValueParser;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
+use jj_lib::view::View;
 
 use super::has_tracked_remote_branches;
+use super::is_fast_forward;
+use crate::cli_util::short_change_hash;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -34,8 +46,52 @@ pub struct BranchCreateArgs {
     revision: Option<RevisionArg>,
 
     /// The branches to create
-    #[arg(required = true, value_parser = NonEmptyStringValueParser::new())]
+    #[arg(
+        required_unless_present_any = ["at_heads", "from_description"],
+        value_parser = NonEmptyStringValueParser::new()
+    )]
     names: Vec<String>,
+
+    /// Name the branch after the first line of the target's description
+    ///
+    /// The line is lowercased and every run of other characters than ASCII
+    /// letters and digits is replaced by `-` (e.g. "Fix login bug" becomes
+    /// `fix-login-bug`). If a branch of that name already exists, a numeric
+    /// suffix like `-2` is appended.
+    #[arg(long, conflicts_with_all = ["names", "at_heads", "force"])]
+    from_description: bool,
+
+    /// Create a branch at each visible head instead
+    ///
+    /// Each branch is named by the `--prefix` followed by the short change ID
+    /// of the head, so the names stay the same when the heads are rewritten.
+    #[arg(long, requires = "prefix", conflicts_with_all = ["revision", "names"])]
+    at_heads: bool,
+
+    /// Print the full commit ID the branches now point to
+    ///
+    /// The commit ID is printed to stdout, so it can be captured by scripts.
+    #[arg(long, conflicts_with = "at_heads")]
+    print_target: bool,
+
+    /// Move the branches to the target revision if they already exist
+    ///
+    /// Existing branches are only moved forward unless `--allow-backwards` is
+    /// also given, as with `jj branch set`.
+    #[arg(long, conflicts_with = "at_heads")]
+    force: bool,
+
+    /// Allow moving existing branches backwards or sideways
+    #[arg(long, short = 'B', requires = "force")]
+    allow_backwards: bool,
+
+    /// Prefix of the branch names created by `--at-heads`
+    #[arg(
+        long,
+        requires = "at_heads",
+        value_parser = NonEmptyStringValueParser::new()
+    )]
+    prefix: Option<String>,
 }
 
 pub fn cmd_branch_create(
@@ -44,42 +100,69 @@ pub fn cmd_branch_create(
     args: &BranchCreateArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    if args.at_heads {
+        return create_branches_at_heads(ui, &mut workspace_command, args.prefix.as_ref().unwrap());
+    }
     let target_commit =
         workspace_command.resolve_single_rev(args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
-    let view = workspace_command.repo().view();
-    let branch_names = &args.names;
-    for name in branch_names {
-        if view.get_local_branch(name).is_present() {
-            return Err(user_error_with_hint(
-                format!("Branch already exists: {name}"),
-                "Use `jj branch set` to update it.",
-            ));
+    let repo = workspace_command.repo().as_ref();
+    let branch_names = if args.from_description {
+        vec![branch_name_from_description(repo.view(), &target_commit)?]
+    } else {
+        args.names.clone()
+    };
+    let mut new_branch_count = 0;
+    let mut moved_branch_count = 0;
+    let mut unchanged_branch_names = vec![];
+    for name in &branch_names {
+        let old_target = repo.view().get_local_branch(name);
+        if !args.force {
+            check_branch_can_be_created(repo.view(), name)?;
+            new_branch_count += 1;
+            continue;
+        }
+        // A local branch resurrected next to its tracked remote branches is
+        // created, not moved.
+        if old_target.is_absent() {
+            new_branch_count += 1;
+        } else if old_target.as_normal() == Some(target_commit.id()) {
+            unchanged_branch_names.push(name);
+        } else {
+            moved_branch_count += 1;
         }
-        if has_tracked_remote_branches(view, name) {
+        if !args.allow_backwards && !is_fast_forward(repo, old_target, target_commit.id()) {
             return Err(user_error_with_hint(
-                format!("Tracked remote branches exist for deleted branch: {name}"),
-                format!(
-                    "Use `jj branch set` to recreate the local branch. Run `jj branch untrack \
-                     'glob:{name}@*'` to disassociate them."
-                ),
+                format!("Refusing to move branch backwards or sideways: {name}"),
+                "Use --allow-backwards to allow it.",
             ));
         }
     }
 
     let mut tx = workspace_command.start_transaction();
-    for branch_name in branch_names {
+    for branch_name in &branch_names {
         tx.mut_repo()
             .set_local_branch_target(branch_name, RefTarget::normal(target_commit.id().clone()));
     }
 
     if let Some(mut formatter) = ui.status_formatter() {
-        write!(
-            formatter,
-            "Created {} branches pointing to ",
-            branch_names.len()
-        )?;
-        tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
-        writeln!(formatter)?;
+        if new_branch_count > 0 {
+            write!(
+                formatter,
+                "Created {new_branch_count} branches pointing to "
+            )?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+        if moved_branch_count > 0 {
+            write!(formatter, "Moved {moved_branch_count} branches to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+        for name in unchanged_branch_names {
+            write!(formatter, "Branch {name} already points to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
     }
     if branch_names.len() > 1 && args.revision.is_none() {
         writeln!(ui.hint_default(), "Use -r to specify the target revision.")?;
@@ -93,5 +176,105 @@ pub fn cmd_branch_create(
             id = target_commit.id().hex()
         ),
     )?;
+    if args.print_target {
+        writeln!(ui.stdout(), "{}", target_commit.id().hex())?;
+    }
+    Ok(())
+}
+
+fn create_branches_at_heads(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    prefix: &str,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo().clone();
+    let heads: Vec<Commit> = RevsetExpression::visible_heads()
+        .minus(&RevsetExpression::root())
+        .evaluate_programmatic(repo.as_ref())?
+        .iter()
+        .commits(repo.store())
+        .try_collect()?;
+    let mut branches: Vec<(String, Commit)> = vec![];
+    for commit in heads {
+        let name = format!("{prefix}{}", short_change_hash(commit.change_id()));
+        check_branch_can_be_created(repo.view(), &name)?;
+        if branches.iter().any(|(other_name, _)| *other_name == name) {
+            return Err(user_error_with_hint(
+                format!("Multiple heads would be given the branch name {name}"),
+                "Resolve the divergent change before creating the branches.",
+            ));
+        }
+        branches.push((name, commit));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for (name, commit) in &branches {
+        tx.mut_repo()
+            .set_local_branch_target(name, RefTarget::normal(commit.id().clone()));
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        for (name, commit) in &branches {
+            write!(formatter, "Created branch {name} pointing to ")?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    tx.finish(
+        ui,
+        format!(
+            "create branch {names} at visible heads",
+            names = branches.iter().map(|(name, _)| name).join(", ")
+        ),
+    )?;
+    Ok(())
+}
+
+/// Derives a branch name from the first line of the `commit` description,
+/// appending a numeric suffix if the name is already taken.
+fn branch_name_from_description(view: &View, commit: &Commit) -> Result<String, CommandError> {
+    let first_line = commit.description().lines().next().unwrap_or("");
+    let slug = first_line
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .join("-");
+    if slug.is_empty() {
+        return Err(user_error_with_hint(
+            format!(
+                "Cannot derive a branch name from the description of commit {}",
+                short_commit_hash(commit.id())
+            ),
+            "Describe the commit first, or specify the branch name.",
+        ));
+    }
+    let is_taken = |name: &str| {
+        view.get_local_branch(name).is_present() || has_tracked_remote_branches(view, name)
+    };
+    let name = (1..)
+        .map(|n| match n {
+            1 => slug.clone(),
+            _ => format!("{slug}-{n}"),
+        })
+        .find(|name| !is_taken(name))
+        .unwrap();
+    Ok(name)
+}
+
+fn check_branch_can_be_created(view: &View, name: &str) -> Result<(), CommandError> {
+    if view.get_local_branch(name).is_present() {
+        return Err(user_error_with_hint(
+            format!("Branch already exists: {name}"),
+            "Use `jj branch set` to update it.",
+        ));
+    }
+    if has_tracked_remote_branches(view, name) {
+        return Err(user_error_with_hint(
+            format!("Tracked remote branches exist for deleted branch: {name}"),
+            format!(
+                "Use `jj branch set` to recreate the local branch. Run `jj branch untrack \
+                 'glob:{name}@*'` to disassociate them."
+            ),
+        ));
+    }
     Ok(())
 }
@@ -14,7 +14,7 @@
 
 use itertools::Itertools as _;
 use jj_lib::op_store::RefTarget;
-use jj_lib::str_util::StringPattern;
+use jj_lib::str_util::NegatableStringPattern;
 
 use super::find_local_branches;
 use crate::cli_util::CommandHelper;
@@ -28,10 +28,11 @@ pub struct BranchDeleteArgs {
     /// The branches to delete
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// select branches by wildcard pattern. Prefix a pattern with `~` to
+    /// exclude the matching branches. For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
-    #[arg(required = true, value_parser = StringPattern::parse)]
-    names: Vec<StringPattern>,
+    #[arg(required = true, value_parser = NegatableStringPattern::parse)]
+    names: Vec<NegatableStringPattern>,
 }
 
 pub fn cmd_branch_delete(
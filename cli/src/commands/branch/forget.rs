@@ -13,14 +13,16 @@
 // limitations under the License.
 
 use itertools::Itertools as _;
+use jj_lib::git;
 use jj_lib::op_store::BranchTarget;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
-use jj_lib::str_util::StringPattern;
+use jj_lib::str_util::NegatableStringPattern;
 use jj_lib::view::View;
 
 use super::find_branches_with;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RemoteBranchName;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -29,15 +31,29 @@ use crate::ui::Ui;
 ///
 /// A forgotten branch will not impact remotes on future pushes. It will be
 /// recreated on future pulls if it still exists in the remote.
+///
+/// By default, only the local branch is forgotten, and the remote branches
+/// are untracked so the branch won't be recreated on future pulls.
 #[derive(clap::Args, Clone, Debug)]
 pub struct BranchForgetArgs {
+    /// Also forget the corresponding remote branches, except the Git-tracking
+    /// ones
+    #[arg(long)]
+    include_remotes: bool,
+    /// Also forget the corresponding Git-tracking branches
+    ///
+    /// Otherwise, the Git-tracking branches are kept, so the branches will be
+    /// deleted from the underlying Git repo on the next `jj git export`.
+    #[arg(long, requires = "include_remotes")]
+    include_git: bool,
     /// The branches to forget
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// select branches by wildcard pattern. Prefix a pattern with `~` to
+    /// exclude the matching branches. For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
-    #[arg(required = true, value_parser = StringPattern::parse)]
-    names: Vec<StringPattern>,
+    #[arg(required = true, value_parser = NegatableStringPattern::parse)]
+    names: Vec<NegatableStringPattern>,
 }
 
 pub fn cmd_branch_forget(
@@ -49,15 +65,45 @@ pub fn cmd_branch_forget(
     let repo = workspace_command.repo().clone();
     let matched_branches = find_forgettable_branches(repo.view(), &args.names)?;
     let mut tx = workspace_command.start_transaction();
+    let mut forgotten_remote_names = vec![];
+    let mut untracked_remote_names = vec![];
     for (name, branch_target) in &matched_branches {
         tx.mut_repo()
             .set_local_branch_target(name, RefTarget::absent());
-        for (remote_name, _) in &branch_target.remote_refs {
-            tx.mut_repo()
-                .set_remote_branch(name, remote_name, RemoteRef::absent());
+        for &(remote_name, remote_ref) in &branch_target.remote_refs {
+            let remote_branch_name = RemoteBranchName {
+                branch: name.to_string(),
+                remote: remote_name.to_owned(),
+            };
+            if remote_name == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+                if args.include_git {
+                    tx.mut_repo()
+                        .set_remote_branch(name, remote_name, RemoteRef::absent());
+                    forgotten_remote_names.push(remote_branch_name);
+                }
+            } else if args.include_remotes {
+                tx.mut_repo()
+                    .set_remote_branch(name, remote_name, RemoteRef::absent());
+                forgotten_remote_names.push(remote_branch_name);
+            } else if remote_ref.is_tracking() {
+                tx.mut_repo().untrack_remote_branch(name, remote_name);
+                untracked_remote_names.push(remote_branch_name);
+            }
+        }
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        for (name, branch_target) in &matched_branches {
+            if branch_target.local_target.is_present() {
+                writeln!(formatter, "Forgot local branch {name}")?;
+            }
+        }
+        for name in &forgotten_remote_names {
+            writeln!(formatter, "Forgot remote branch {name}")?;
+        }
+        for name in &untracked_remote_names {
+            writeln!(formatter, "Stopped tracking remote branch {name}")?;
         }
     }
-    writeln!(ui.status(), "Forgot {} branches.", matched_branches.len())?;
     tx.finish(
         ui,
         format!(
@@ -70,7 +116,7 @@ pub fn cmd_branch_forget(
 
 fn find_forgettable_branches<'a>(
     view: &'a View,
-    name_patterns: &[StringPattern],
+    name_patterns: &[NegatableStringPattern],
 ) -> Result<Vec<(&'a str, BranchTarget<'a>)>, CommandError> {
     find_branches_with(name_patterns, |pattern| {
         view.branches().filter(|(name, _)| pattern.matches(name))
@@ -12,17 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::collections::HashSet;
+use std::io;
+use std::iter;
+use std::rc::Rc;
 
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::git;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
+use jj_lib::str_util::NegatableStringPattern;
 use jj_lib::str_util::StringPattern;
+use unicode_width::UnicodeWidthStr as _;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::commit_templater::RefName;
+use crate::formatter::Formatter;
 use crate::ui::Ui;
 
 /// List branches and their targets
@@ -51,13 +63,34 @@ pub struct BranchListArgs {
     #[arg(long, short, conflicts_with_all = ["all_remotes"])]
     conflicted: bool,
 
+    /// Print local branches and the branches of each remote in separate
+    /// sections
+    #[arg(long)]
+    group_by_remote: bool,
+
+    /// Render remote branches as `name@remote`, including the ones tracked by
+    /// a local branch
+    ///
+    /// By default, a tracking remote branch is rendered as `@remote` below the
+    /// local branch. The `@git` pseudo remote is rendered the same way.
+    #[arg(long)]
+    fully_qualified: bool,
+
+    /// Sort branches by the given key
+    ///
+    /// Date keys look up the commit the branch points to (or, for a deleted
+    /// branch, the first remote target). Ties are broken by branch name.
+    #[arg(long, value_enum, default_value_t = BranchSortKey::Name)]
+    sort: BranchSortKey,
+
     /// Show branches whose local name matches
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// select branches by wildcard pattern. Prefix a pattern with `~` to
+    /// exclude the matching branches. For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
-    #[arg(value_parser = StringPattern::parse)]
-    names: Vec<StringPattern>,
+    #[arg(value_parser = NegatableStringPattern::parse)]
+    names: Vec<NegatableStringPattern>,
 
     /// Show branches whose local targets are in the given revisions
     ///
@@ -66,6 +99,18 @@ pub struct BranchListArgs {
     #[arg(long, short)]
     revisions: Vec<RevisionArg>,
 
+    /// Show branches that exist on the given remotes only
+    ///
+    /// Remote branches of the other remotes are omitted. Non-tracking remote
+    /// branches of the given remotes are listed too, and tracked branches are
+    /// still listed with their local targets.
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select remotes by wildcard pattern. For details, see
+    /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
+    #[arg(long = "remote", value_name = "REMOTE", value_parser = StringPattern::parse)]
+    remotes: Vec<StringPattern>,
+
     /// Render each branch using the given template
     ///
     /// All 0-argument methods of the `RefName` type are available as keywords.
@@ -73,6 +118,74 @@ pub struct BranchListArgs {
     /// For the syntax, see https://github.com/martinvonz/jj/blob/main/docs/templates.md
     #[arg(long, short = 'T')]
     template: Option<String>,
+
+    /// Output format
+    ///
+    /// With `json`, the listed branches are printed as an array of objects
+    /// with the `name`, the local `target` commit IDs, whether the local
+    /// target is a `conflict`, and the listed `remotes`. Each remote entry
+    /// has the `remote` name, its `target`, `conflict`, and `tracking` state.
+    ///
+    /// With `table`, each branch is printed as a row with a column per remote.
+    /// A cell shows `=` if the remote branch is at the local target, or how
+    /// many commits the local branch is ahead (`+N`) and behind (`-N`). Use
+    /// with `--all-remotes` to fill in the remote branches that are in sync.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = BranchListFormat::Text,
+        conflicts_with_all = ["template", "group_by_remote"]
+    )]
+    format: BranchListFormat,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum BranchListFormat {
+    /// Render each branch using the `templates.branch_list` template
+    Text,
+    /// JSON array of branches, for use by other tools
+    Json,
+    /// Table of the local branches and their remote branches
+    Table,
+}
+
+#[derive(serde::Serialize)]
+struct BranchJson<'a> {
+    name: &'a str,
+    target: Vec<String>,
+    conflict: bool,
+    remotes: Vec<RemoteBranchJson<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct RemoteBranchJson<'a> {
+    remote: &'a str,
+    target: Vec<String>,
+    conflict: bool,
+    tracking: bool,
+}
+
+fn target_to_json(target: &RefTarget) -> Vec<String> {
+    target.added_ids().map(|id| id.hex()).collect()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum BranchSortKey {
+    /// Branch name in ascending order
+    Name,
+    /// Branch name in descending order
+    #[value(name = "name-")]
+    NameDesc,
+    /// Author date of the target commit, oldest first
+    AuthorDate,
+    /// Author date of the target commit, newest first
+    #[value(name = "author-date-")]
+    AuthorDateDesc,
+    /// Committer date of the target commit, oldest first
+    CommitterDate,
+    /// Committer date of the target commit, newest first
+    #[value(name = "committer-date-")]
+    CommitterDateDesc,
 }
 
 pub fn cmd_branch_list(
@@ -90,7 +203,7 @@ pub fn cmd_branch_list(
         if !args.names.is_empty() {
             branch_names.extend(
                 view.branches()
-                    .filter(|&(name, _)| args.names.iter().any(|pattern| pattern.matches(name)))
+                    .filter(|&(name, _)| NegatableStringPattern::select(&args.names, name))
                     .map(|(name, _)| name),
             );
         }
@@ -124,24 +237,96 @@ pub fn cmd_branch_list(
             .labeled("branch_list")
     };
 
-    ui.request_pager();
-    let mut formatter = ui.stdout_formatter();
+    let is_listed_remote = |remote: &str| {
+        args.remotes.is_empty() || args.remotes.iter().any(|pattern| pattern.matches(remote))
+    };
 
+    // Branches to print, paired with the remote name if any
+    let mut listed_refs: Vec<(Option<&str>, Rc<RefName>)> = vec![];
+    let mut listed_json_branches: Vec<BranchJson> = vec![];
+    let mut table_rows: Vec<(&str, Vec<(&str, String)>)> = vec![];
     let mut found_deleted_local_branch = false;
     let mut found_deleted_tracking_local_branch = false;
-    let branches_to_list = view.branches().filter(|(name, target)| {
-        branch_names_to_list
-            .as_ref()
-            .map_or(true, |branch_names| branch_names.contains(name))
-            && (!args.conflicted || target.local_target.has_conflict())
-    });
+    let mut branches_to_list = view
+        .branches()
+        .filter(|(name, target)| {
+            branch_names_to_list
+                .as_ref()
+                .map_or(true, |branch_names| branch_names.contains(name))
+                && (!args.conflicted || target.local_target.has_conflict())
+                && (args.remotes.is_empty()
+                    || target
+                        .remote_refs
+                        .iter()
+                        .any(|&(remote, _)| is_listed_remote(remote)))
+        })
+        .collect_vec();
+    // view.branches() is ordered by name, so a stable sort keeps ties sorted
+    // by name.
+    match args.sort {
+        BranchSortKey::Name => {}
+        BranchSortKey::NameDesc => branches_to_list.reverse(),
+        BranchSortKey::AuthorDate
+        | BranchSortKey::AuthorDateDesc
+        | BranchSortKey::CommitterDate
+        | BranchSortKey::CommitterDateDesc => {
+            let mut keyed_branches = Vec::with_capacity(branches_to_list.len());
+            for (name, branch_target) in branches_to_list {
+                let target_id = branch_target
+                    .local_target
+                    .added_ids()
+                    .chain(
+                        branch_target
+                            .remote_refs
+                            .iter()
+                            .flat_map(|(_, remote_ref)| remote_ref.target.added_ids()),
+                    )
+                    .next();
+                let timestamp = match target_id {
+                    Some(id) => {
+                        let commit = repo.store().get_commit(id)?;
+                        let signature = match args.sort {
+                            BranchSortKey::AuthorDate | BranchSortKey::AuthorDateDesc => {
+                                commit.author()
+                            }
+                            _ => commit.committer(),
+                        };
+                        Some(signature.timestamp.timestamp)
+                    }
+                    None => None,
+                };
+                keyed_branches.push((timestamp, (name, branch_target)));
+            }
+            if matches!(
+                args.sort,
+                BranchSortKey::AuthorDateDesc | BranchSortKey::CommitterDateDesc
+            ) {
+                keyed_branches.sort_by(|(a, _), (b, _)| b.cmp(a));
+            } else {
+                keyed_branches.sort_by_key(|(timestamp, _)| *timestamp);
+            }
+            branches_to_list = keyed_branches
+                .into_iter()
+                .map(|(_, branch)| branch)
+                .collect();
+        }
+    }
     for (name, branch_target) in branches_to_list {
         let local_target = branch_target.local_target;
         let remote_refs = branch_target.remote_refs;
-        let (mut tracking_remote_refs, untracked_remote_refs) = remote_refs
+        let (mut tracking_remote_refs, mut untracked_remote_refs) = remote_refs
             .iter()
             .copied()
+            .filter(|&(remote, _)| is_listed_remote(remote))
             .partition::<Vec<_>, _>(|&(_, remote_ref)| remote_ref.is_tracking());
+        // Non-tracking remote branches are listed if the remotes are specified
+        // explicitly. Otherwise a remote-only branch would be selected but not
+        // shown.
+        let list_untracked_remote_refs =
+            args.all_remotes || !args.remotes.is_empty() && !args.tracked;
+        if !list_untracked_remote_refs {
+            untracked_remote_refs.clear();
+        }
 
         if args.tracked {
             tracking_remote_refs
@@ -150,18 +335,60 @@ pub fn cmd_branch_list(
             tracking_remote_refs.retain(|&(_, remote_ref)| remote_ref.target != *local_target);
         }
 
+        if args.format != BranchListFormat::Text {
+            let listed_remote_refs = tracking_remote_refs
+                .iter()
+                .chain(&untracked_remote_refs)
+                .sorted_by_key(|&&(remote, _)| remote)
+                .collect_vec();
+            if !args.tracked && local_target.is_present() || !listed_remote_refs.is_empty() {
+                if args.format == BranchListFormat::Json {
+                    listed_json_branches.push(BranchJson {
+                        name,
+                        target: target_to_json(local_target),
+                        conflict: local_target.has_conflict(),
+                        remotes: listed_remote_refs
+                            .into_iter()
+                            .map(|&(remote, remote_ref)| RemoteBranchJson {
+                                remote,
+                                target: target_to_json(&remote_ref.target),
+                                conflict: remote_ref.target.has_conflict(),
+                                tracking: remote_ref.is_tracking(),
+                            })
+                            .collect(),
+                    });
+                } else {
+                    let cells = listed_remote_refs
+                        .into_iter()
+                        .map(|&(remote, remote_ref)| {
+                            let cell =
+                                format_table_cell(repo.as_ref(), local_target, &remote_ref.target)?;
+                            Ok((remote, cell))
+                        })
+                        .try_collect::<_, _, CommandError>()?;
+                    table_rows.push((name, cells));
+                }
+            }
+        }
+
         if !args.tracked && local_target.is_present() || !tracking_remote_refs.is_empty() {
             let ref_name = RefName::local(
                 name,
                 local_target.clone(),
                 remote_refs.iter().map(|&(_, remote_ref)| remote_ref),
             );
-            template.format(&ref_name, formatter.as_mut())?;
+            listed_refs.push((None, ref_name));
         }
 
         for &(remote, remote_ref) in &tracking_remote_refs {
-            let ref_name = RefName::remote(name, remote, remote_ref.clone(), local_target);
-            template.format(&ref_name, formatter.as_mut())?;
+            // Tracking remote branches are rendered relative to the preceding
+            // local branch, which isn't there if grouped by remote.
+            let ref_name = if args.group_by_remote || args.fully_qualified {
+                RefName::remote_only(name, remote, remote_ref.target.clone())
+            } else {
+                RefName::remote(name, remote, remote_ref.clone(), local_target)
+            };
+            listed_refs.push((Some(remote), ref_name));
         }
 
         if local_target.is_absent() && !tracking_remote_refs.is_empty() {
@@ -171,14 +398,50 @@ pub fn cmd_branch_list(
                 .any(|&(remote, _)| remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO);
         }
 
-        if args.all_remotes {
-            for &(remote, remote_ref) in &untracked_remote_refs {
-                let ref_name = RefName::remote_only(name, remote, remote_ref.target.clone());
-                template.format(&ref_name, formatter.as_mut())?;
-            }
+        for &(remote, remote_ref) in &untracked_remote_refs {
+            let ref_name = RefName::remote_only(name, remote, remote_ref.target.clone());
+            listed_refs.push((Some(remote), ref_name));
         }
     }
 
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    if args.format == BranchListFormat::Json {
+        let json = serde_json::to_string_pretty(&listed_json_branches).unwrap();
+        writeln!(formatter, "{json}")?;
+    } else if args.format == BranchListFormat::Table {
+        write_branch_table(formatter.as_mut(), &table_rows)?;
+    } else if args.group_by_remote {
+        let mut local_refs = vec![];
+        let mut refs_by_remote: BTreeMap<&str, Vec<Rc<RefName>>> = BTreeMap::new();
+        for (remote, ref_name) in listed_refs {
+            match remote {
+                Some(remote) => refs_by_remote.entry(remote).or_default().push(ref_name),
+                None => local_refs.push(ref_name),
+            }
+        }
+        let sections = (!local_refs.is_empty())
+            .then(|| ("Local branches:".to_owned(), local_refs))
+            .into_iter()
+            .chain(
+                refs_by_remote
+                    .into_iter()
+                    .map(|(remote, refs)| (format!("Remote branches of {remote}:"), refs)),
+            );
+        for (i, (heading, refs)) in sections.enumerate() {
+            if i > 0 {
+                writeln!(formatter)?;
+            }
+            writeln!(formatter.labeled("heading"), "{heading}")?;
+            for ref_name in &refs {
+                template.format(ref_name, formatter.as_mut())?;
+            }
+        }
+    } else {
+        for (_, ref_name) in &listed_refs {
+            template.format(ref_name, formatter.as_mut())?;
+        }
+    }
     drop(formatter);
 
     // Print only one of these hints. It's not important to mention unexported
@@ -199,3 +462,97 @@ pub fn cmd_branch_list(
 
     Ok(())
 }
+
+/// Describes the remote target relative to the local target: `=` if they are
+/// the same, otherwise the number of commits the local target is ahead (`+N`)
+/// and behind (`-N`) of the remote target.
+fn format_table_cell(
+    repo: &dyn Repo,
+    local_target: &RefTarget,
+    remote_target: &RefTarget,
+) -> Result<String, CommandError> {
+    if local_target == remote_target {
+        return Ok("=".to_owned());
+    } else if local_target.is_absent() {
+        return Ok("deleted".to_owned());
+    }
+    let (Some(local_id), Some(remote_id)) = (local_target.as_normal(), remote_target.as_normal())
+    else {
+        return Ok("conflict".to_owned());
+    };
+    let count_range = |root_id: &CommitId, head_id: &CommitId| -> Result<usize, CommandError> {
+        if repo.index().is_ancestor(head_id, root_id) {
+            return Ok(0);
+        }
+        let count = RevsetExpression::commit(root_id.clone())
+            .range(&RevsetExpression::commit(head_id.clone()))
+            .evaluate_programmatic(repo)?
+            .iter()
+            .count();
+        Ok(count)
+    };
+    let ahead = count_range(remote_id, local_id)?;
+    let behind = count_range(local_id, remote_id)?;
+    let cell = match (ahead, behind) {
+        (ahead, 0) => format!("+{ahead}"),
+        (0, behind) => format!("-{behind}"),
+        (ahead, behind) => format!("+{ahead} -{behind}"),
+    };
+    Ok(cell)
+}
+
+/// Prints the branches as rows, and the remotes as columns.
+fn write_branch_table(
+    formatter: &mut dyn Formatter,
+    rows: &[(&str, Vec<(&str, String)>)],
+) -> io::Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let remotes = rows
+        .iter()
+        .flat_map(|(_, cells)| cells.iter().map(|&(remote, _)| remote))
+        .sorted()
+        .dedup()
+        .collect_vec();
+    let header = iter::once("Branch")
+        .chain(remotes.iter().copied())
+        .map(str::to_owned)
+        .collect_vec();
+    let lines = rows
+        .iter()
+        .map(|(name, cells)| {
+            let remote_cells = remotes.iter().map(|remote| {
+                cells
+                    .iter()
+                    .find(|(cell_remote, _)| cell_remote == remote)
+                    .map_or_else(String::new, |(_, cell)| cell.clone())
+            });
+            iter::once(name.to_string())
+                .chain(remote_cells)
+                .collect_vec()
+        })
+        .collect_vec();
+    let widths = (0..header.len())
+        .map(|i| {
+            iter::once(&header)
+                .chain(&lines)
+                .map(|line| line[i].width())
+                .max()
+                .unwrap()
+        })
+        .collect_vec();
+    let format_line = |line: &[String]| {
+        line.iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell}{}", " ".repeat(width - cell.width())))
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    writeln!(formatter.labeled("heading"), "{}", format_line(&header))?;
+    for line in &lines {
+        writeln!(formatter, "{}", format_line(line))?;
+    }
+    Ok(())
+}
@@ -28,6 +28,7 @@ use jj_lib::git;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::Repo;
+use jj_lib::str_util::NegatableStringPattern;
 use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
 
@@ -101,28 +102,43 @@ pub fn cmd_branch(
 
 fn find_local_branches<'a>(
     view: &'a View,
-    name_patterns: &[StringPattern],
+    name_patterns: &[NegatableStringPattern],
 ) -> Result<Vec<(&'a str, &'a RefTarget)>, CommandError> {
     find_branches_with(name_patterns, |pattern| {
         view.local_branches_matching(pattern)
     })
 }
 
+/// Finds branches matching any of the non-negated `name_patterns`, excluding
+/// the ones matching negated patterns. If all patterns are negated, all
+/// branches but the excluded ones are selected.
 fn find_branches_with<'a, 'b, V, I: Iterator<Item = (&'a str, V)>>(
-    name_patterns: &'b [StringPattern],
+    name_patterns: &'b [NegatableStringPattern],
     mut find_matches: impl FnMut(&'b StringPattern) -> I,
 ) -> Result<Vec<I::Item>, CommandError> {
+    static EVERYTHING: StringPattern = StringPattern::everything();
+    let (excluded_patterns, included_patterns): (Vec<_>, Vec<_>) = name_patterns
+        .iter()
+        .partition(|pattern| pattern.is_negated());
     let mut matching_branches: Vec<I::Item> = vec![];
     let mut unmatched_patterns = vec![];
-    for pattern in name_patterns {
-        let mut matches = find_matches(pattern).peekable();
+    if included_patterns.is_empty() {
+        matching_branches.extend(find_matches(&EVERYTHING));
+    }
+    for pattern in included_patterns {
+        let mut matches = find_matches(pattern.pattern()).peekable();
         if matches.peek().is_none() {
-            unmatched_patterns.push(pattern);
+            unmatched_patterns.push(pattern.pattern());
         }
         matching_branches.extend(matches);
     }
     match &unmatched_patterns[..] {
         [] => {
+            matching_branches.retain(|(name, _)| {
+                !excluded_patterns
+                    .iter()
+                    .any(|pattern| pattern.pattern().matches(name))
+            });
             matching_branches.sort_unstable_by_key(|(name, _)| *name);
             matching_branches.dedup_by_key(|(name, _)| *name);
             Ok(matching_branches)
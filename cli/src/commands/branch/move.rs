@@ -16,10 +16,15 @@ use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
+use jj_lib::str_util::NegatableStringPattern;
 use jj_lib::str_util::StringPattern;
 
 use super::find_branches_with;
 use super::is_fast_forward;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::user_error_with_hint;
@@ -34,6 +39,8 @@ use crate::ui::Ui;
 /// If `--from` options are given, branches currently pointing to the specified
 /// revisions will be updated. The branches can also be filtered by names.
 ///
+/// If several branches are moved, the previous target of each is reported.
+///
 /// Example: pull up the nearest branches to the working-copy parent
 ///
 /// $ jj branch move --from 'heads(::@- & branches())' --to @-
@@ -48,17 +55,33 @@ pub struct BranchMoveArgs {
     #[arg(long, default_value = "@", value_name = "REVISION")]
     to: RevisionArg,
 
+    /// Move branches to the current commit of this change
+    ///
+    /// Unlike `--to`, the argument is always interpreted as a change ID (or a
+    /// unique prefix of it), so the branch follows the change across rewrites.
+    #[arg(long, conflicts_with = "to", value_name = "CHANGE_ID")]
+    to_change: Option<String>,
+
     /// Allow moving branches backwards or sideways
     #[arg(long, short = 'B')]
     allow_backwards: bool,
 
+    /// Also move branches that would otherwise be left behind
+    ///
+    /// Branches pointing to commits between the old and the new target (i.e.
+    /// `old_target..new_target`) are moved to the new target as well, so they
+    /// stay at or ahead of the moved branches.
+    #[arg(long)]
+    also_descendants: bool,
+
     /// Move branches matching the given name patterns
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// select branches by wildcard pattern. Prefix a pattern with `~` to
+    /// exclude the matching branches. For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
-    #[arg(group = "source", value_parser = StringPattern::parse)]
-    names: Vec<StringPattern>,
+    #[arg(group = "source", value_parser = NegatableStringPattern::parse)]
+    names: Vec<NegatableStringPattern>,
 }
 
 pub fn cmd_branch_move(
@@ -69,8 +92,12 @@ pub fn cmd_branch_move(
     let mut workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo().clone();
 
-    let target_commit = workspace_command.resolve_single_rev(&args.to)?;
-    let matched_branches = {
+    let target_commit = if let Some(change) = &args.to_change {
+        workspace_command.resolve_change_id(change)?
+    } else {
+        workspace_command.resolve_single_rev(&args.to)?
+    };
+    let mut matched_branches = {
         let is_source_commit = if !args.from.is_empty() {
             workspace_command
                 .parse_union_revsets(&args.from)?
@@ -102,17 +129,45 @@ pub fn cmd_branch_move(
     }
 
     if !args.allow_backwards {
-        if let Some((name, _)) = matched_branches
+        let backward_names = matched_branches
             .iter()
-            .find(|(_, old_target)| !is_fast_forward(repo.as_ref(), old_target, target_commit.id()))
-        {
+            .filter(|(_, old_target)| {
+                !is_fast_forward(repo.as_ref(), old_target, target_commit.id())
+            })
+            .map(|(name, _)| name)
+            .collect_vec();
+        if !backward_names.is_empty() {
             return Err(user_error_with_hint(
-                format!("Refusing to move branch backwards or sideways: {name}"),
+                format!(
+                    "Refusing to move branch backwards or sideways: {}",
+                    backward_names.iter().join(", ")
+                ),
                 "Use --allow-backwards to allow it.",
             ));
         }
     }
 
+    if args.also_descendants {
+        let old_target_ids = matched_branches
+            .iter()
+            .flat_map(|(_, old_target)| old_target.added_ids())
+            .cloned()
+            .collect_vec();
+        let in_between = RevsetExpression::commits(old_target_ids)
+            .range(&RevsetExpression::commit(target_commit.id().clone()))
+            .evaluate_programmatic(repo.as_ref())?;
+        let is_in_between = in_between.containing_fn();
+        let descendant_branches = repo
+            .view()
+            .local_branches()
+            .filter(|(name, _)| matched_branches.iter().all(|(matched, _)| matched != name))
+            .filter(|(_, target)| target.as_normal() != Some(target_commit.id()))
+            .filter(|(_, target)| target.added_ids().any(&is_in_between))
+            .collect_vec();
+        matched_branches.extend(descendant_branches);
+        matched_branches.sort_unstable_by_key(|(name, _)| *name);
+    }
+
     let mut tx = workspace_command.start_transaction();
     for (name, _) in &matched_branches {
         tx.mut_repo()
@@ -123,6 +178,47 @@ pub fn cmd_branch_move(
         write!(formatter, "Moved {} branches to ", matched_branches.len())?;
         tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
         writeln!(formatter)?;
+        if matched_branches.len() > 1 {
+            for (name, old_target) in &matched_branches {
+                let old_ids = old_target.added_ids().map(short_commit_hash).join(", ");
+                writeln!(formatter, "  {name} (was at {old_ids})")?;
+            }
+        }
+    }
+
+    // Commits which were reachable only from the moved branches are still
+    // visible, but they can easily be lost track of. Ancestors of working-copy
+    // commits are kept track of by the working copies.
+    let old_target_ids = matched_branches
+        .iter()
+        .flat_map(|(_, old_target)| old_target.added_ids())
+        .cloned()
+        .collect_vec();
+    let unreachable_commits: Vec<_> = RevsetExpression::commit(target_commit.id().clone())
+        .range(&RevsetExpression::commits(old_target_ids))
+        .minus(
+            &RevsetExpression::branches(StringPattern::everything())
+                .union(&RevsetExpression::working_copies(
+                    StringPattern::everything(),
+                ))
+                .ancestors(),
+        )
+        .evaluate_programmatic(tx.repo())?
+        .iter()
+        .commits(tx.repo().store())
+        .try_collect()?;
+    if !unreachable_commits.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "The following commits are no longer reachable from any branch:"
+        )?;
+        if let Some(mut formatter) = ui.status_formatter() {
+            for commit in &unreachable_commits {
+                write!(formatter, "  ")?;
+                tx.write_commit_summary(formatter.as_mut(), commit)?;
+                writeln!(formatter)?;
+            }
+        }
     }
     if matched_branches.len() > 1 && args.names.is_empty() {
         writeln!(
@@ -18,8 +18,10 @@ use jj_lib::op_store::RefTarget;
 
 use super::has_tracked_remote_branches;
 use super::is_fast_forward;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -31,10 +33,24 @@ pub struct BranchSetArgs {
     #[arg(long, short, visible_alias = "to")]
     revision: Option<RevisionArg>,
 
+    /// Point the branch to the current commit of this change
+    ///
+    /// Unlike `--revision`, the argument is always interpreted as a change ID
+    /// (or a unique prefix of it). It's an error if the change is divergent.
+    #[arg(long, conflicts_with = "revision", value_name = "CHANGE_ID")]
+    to_change: Option<String>,
+
     /// Allow moving the branch backwards or sideways
     #[arg(long, short = 'B')]
     allow_backwards: bool,
 
+    /// Only update the branches if they currently point to this revision
+    ///
+    /// This is useful in scripts to make sure the branches weren't moved by
+    /// someone else in the meantime.
+    #[arg(long, value_name = "REVISION")]
+    if_target: Option<RevisionArg>,
+
     /// The branches to update
     #[arg(required = true, value_parser = NonEmptyStringValueParser::new())]
     names: Vec<String>,
@@ -46,8 +62,16 @@ pub fn cmd_branch_set(
     args: &BranchSetArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
-    let target_commit =
-        workspace_command.resolve_single_rev(args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
+    let target_commit = if let Some(change) = &args.to_change {
+        workspace_command.resolve_change_id(change)?
+    } else {
+        workspace_command.resolve_single_rev(args.revision.as_ref().unwrap_or(&RevisionArg::AT))?
+    };
+    let expected_commit = args
+        .if_target
+        .as_ref()
+        .map(|revision| workspace_command.resolve_single_rev(revision))
+        .transpose()?;
     let repo = workspace_command.repo().as_ref();
     let branch_names = &args.names;
     let mut new_branch_count = 0;
@@ -61,6 +85,21 @@ pub fn cmd_branch_set(
         } else if old_target.as_normal() != Some(target_commit.id()) {
             moved_branch_count += 1;
         }
+        if let Some(expected_commit) = &expected_commit {
+            if old_target.as_normal() != Some(expected_commit.id()) {
+                let actual = if let Some(id) = old_target.as_normal() {
+                    format!("it points to {}", short_commit_hash(id))
+                } else if old_target.is_absent() {
+                    "it doesn't exist".to_owned()
+                } else {
+                    "it is conflicted".to_owned()
+                };
+                return Err(user_error(format!(
+                    "Branch {name} was expected to point to {expected}, but {actual}",
+                    expected = short_commit_hash(expected_commit.id())
+                )));
+            }
+        }
         if !args.allow_backwards && !is_fast_forward(repo, old_target, target_commit.id()) {
             return Err(user_error_with_hint(
                 format!("Refusing to move branch backwards or sideways: {name}"),
@@ -15,10 +15,14 @@
 use std::collections::HashMap;
 
 use itertools::Itertools as _;
+use jj_lib::git;
+use jj_lib::str_util::StringPattern;
 
 use super::find_remote_branches;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RemoteBranchName;
 use crate::cli_util::RemoteBranchNamePattern;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::commit_templater::RefName;
@@ -38,8 +42,16 @@ pub struct BranchTrackArgs {
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
     ///
     /// Examples: branch@remote, glob:main@*, glob:jjfan-*@upstream
-    #[arg(required = true, value_name = "BRANCH@REMOTE")]
+    #[arg(
+        required_unless_present = "all_from_remote",
+        conflicts_with = "all_from_remote",
+        value_name = "BRANCH@REMOTE"
+    )]
     names: Vec<RemoteBranchNamePattern>,
+
+    /// Track all branches of the given remote that aren't tracked yet
+    #[arg(long, value_name = "REMOTE")]
+    all_from_remote: Option<String>,
 }
 
 pub fn cmd_branch_track(
@@ -50,14 +62,36 @@ pub fn cmd_branch_track(
     let mut workspace_command = command.workspace_helper(ui)?;
     let view = workspace_command.repo().view();
     let mut names = Vec::new();
-    for (name, remote_ref) in find_remote_branches(view, &args.names)? {
-        if remote_ref.is_tracking() {
-            writeln!(
-                ui.warning_default(),
-                "Remote branch already tracked: {name}"
-            )?;
-        } else {
-            names.push(name);
+    if let Some(remote) = &args.all_from_remote {
+        if remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+            return Err(user_error(format!(
+                "Git-tracking branches cannot be tracked: {remote}"
+            )));
+        }
+        let branch_pattern = StringPattern::everything();
+        let remote_pattern = StringPattern::exact(remote);
+        let remote_branches = view.remote_branches_matching(&branch_pattern, &remote_pattern);
+        for ((branch, remote), remote_ref) in remote_branches {
+            if !remote_ref.is_tracking() {
+                names.push(RemoteBranchName {
+                    branch: branch.to_owned(),
+                    remote: remote.to_owned(),
+                });
+            }
+        }
+        for name in &names {
+            writeln!(ui.status(), "Tracking remote branch {name}")?;
+        }
+    } else {
+        for (name, remote_ref) in find_remote_branches(view, &args.names)? {
+            if remote_ref.is_tracking() {
+                writeln!(
+                    ui.warning_default(),
+                    "Remote branch already tracked: {name}"
+                )?;
+            } else {
+                names.push(name);
+            }
         }
     }
     let mut tx = workspace_command.start_transaction();
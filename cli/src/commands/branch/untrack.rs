@@ -14,9 +14,11 @@
 
 use itertools::Itertools as _;
 use jj_lib::git;
+use jj_lib::str_util::StringPattern;
 
 use super::find_remote_branches;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RemoteBranchName;
 use crate::cli_util::RemoteBranchNamePattern;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -34,8 +36,19 @@ pub struct BranchUntrackArgs {
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
     ///
     /// Examples: branch@remote, glob:main@*, glob:jjfan-*@upstream
-    #[arg(required = true, value_name = "BRANCH@REMOTE")]
+    #[arg(
+        required_unless_present = "all",
+        conflicts_with = "all",
+        value_name = "BRANCH@REMOTE"
+    )]
     names: Vec<RemoteBranchNamePattern>,
+
+    /// Untrack the given branches on all remotes they are tracked on
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select branches by wildcard pattern.
+    #[arg(long, value_name = "BRANCH", value_parser = StringPattern::parse)]
+    all: Vec<StringPattern>,
 }
 
 pub fn cmd_branch_untrack(
@@ -46,6 +59,24 @@ pub fn cmd_branch_untrack(
     let mut workspace_command = command.workspace_helper(ui)?;
     let view = workspace_command.repo().view();
     let mut names = Vec::new();
+    let all_remotes = StringPattern::everything();
+    for branch_pattern in &args.all {
+        let remote_branches = view.remote_branches_matching(branch_pattern, &all_remotes);
+        for ((branch, remote), remote_ref) in remote_branches {
+            if remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO || !remote_ref.is_tracking() {
+                continue;
+            }
+            let name = RemoteBranchName {
+                branch: branch.to_owned(),
+                remote: remote.to_owned(),
+            };
+            // The same branch may be matched by several patterns
+            if !names.contains(&name) {
+                writeln!(ui.status(), "Untracking remote branch {name}")?;
+                names.push(name);
+            }
+        }
+    }
     for (name, remote_ref) in find_remote_branches(view, &args.names)? {
         if name.remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
             // This restriction can be lifted if we want to support untracked @git branches.
@@ -73,6 +73,7 @@ pub(crate) fn cmd_file_untrack(
         fsmonitor_settings: command.settings().fsmonitor_settings()?,
         progress: None,
         max_new_file_size: command.settings().max_new_file_size()?,
+        conflict_marker_style: command.settings().conflict_marker_style()?,
     })?;
     if wc_tree_id != *new_commit.tree_id() {
         let wc_tree = store.get_root_tree(&wc_tree_id)?;
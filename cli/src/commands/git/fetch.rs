@@ -17,6 +17,8 @@ use jj_lib::git;
 use jj_lib::git::GitFetchError;
 use jj_lib::repo::Repo;
 use jj_lib::settings::ConfigResultExt as _;
+use jj_lib::settings::GitSettings;
+use jj_lib::settings::LocalBranchUpdate;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
 
@@ -50,6 +52,21 @@ pub struct GitFetchArgs {
     /// Fetch from all remotes
     #[arg(long, conflicts_with = "remotes")]
     all_remotes: bool,
+    /// How to update local branches tracking the fetched remote branches
+    #[arg(long, value_enum, default_value_t = UpdateBranches::All)]
+    update_branches: UpdateBranches,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum UpdateBranches {
+    /// Move tracking local branches only if the remote branch was
+    /// fast-forwarded from the local target
+    Track,
+    /// Merge remote changes into tracking local branches, which may result in
+    /// conflicted branches
+    All,
+    /// Only update the remote-tracking branches
+    None,
 }
 
 #[tracing::instrument(skip(ui, command))]
@@ -67,6 +84,14 @@ pub fn cmd_git_fetch(
     } else {
         args.remotes.clone()
     };
+    let git_settings = GitSettings {
+        local_branch_update: match args.update_branches {
+            UpdateBranches::Track => LocalBranchUpdate::FastForward,
+            UpdateBranches::All => LocalBranchUpdate::Merge,
+            UpdateBranches::None => LocalBranchUpdate::Skip,
+        },
+        ..command.settings().git_settings()
+    };
     let mut tx = workspace_command.start_transaction();
     for remote in &remotes {
         let stats = with_remote_git_callbacks(ui, None, |cb| {
@@ -76,7 +101,7 @@ pub fn cmd_git_fetch(
                 remote,
                 &args.branch,
                 cb,
-                &command.settings().git_settings(),
+                &git_settings,
             )
         })
         .map_err(|err| match err {
@@ -17,12 +17,15 @@ use std::collections::HashSet;
 use std::fmt;
 use std::io;
 use std::io::Write;
+use std::rc::Rc;
 
 use clap::ArgGroup;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
+use jj_lib::git::GitFetchError;
 use jj_lib::git::GitPushError;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::RefTarget;
@@ -33,6 +36,8 @@ use jj_lib::refs::LocalAndRemoteRef;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::settings::ConfigResultExt as _;
+use jj_lib::settings::GitSettings;
+use jj_lib::settings::LocalBranchUpdate;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
@@ -46,12 +51,17 @@ use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
+use crate::command_error::CommandErrorKind;
 use crate::commands::git::get_single_remote;
 use crate::commands::git::map_git_error;
+use crate::generic_templater::GenericTemplateLanguage;
 use crate::git_util::get_git_repo;
+use crate::git_util::print_git_import_stats;
 use crate::git_util::with_remote_git_callbacks;
 use crate::git_util::GitSidebandProgressMessageWriter;
 use crate::revset_util;
+use crate::template_builder::TemplateLanguage as _;
+use crate::templater::TemplatePropertyExt as _;
 use crate::ui::Ui;
 
 /// Push to a Git remote
@@ -73,12 +83,17 @@ use crate::ui::Ui;
 ///     https://martinvonz.github.io/jj/latest/branches/#conflicts
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("specific").args(&["branch", "change", "revisions"]).multiple(true)))]
+#[command(group(ArgGroup::new("specific").args(&["branch", "change", "revisions", "descendants_of"]).multiple(true)))]
 #[command(group(ArgGroup::new("what").args(&["all", "deleted", "tracked"]).conflicts_with("specific")))]
 pub struct GitPushArgs {
-    /// The remote to push to (only named remotes are supported)
-    #[arg(long)]
-    remote: Option<String>,
+    /// The remote to push to (only named remotes are supported, can be
+    /// repeated)
+    ///
+    /// If several remotes are given, the same branches are pushed to each of
+    /// them in turn. A failure on one remote doesn't prevent pushing to the
+    /// others, and the outcome of each remote is reported at the end.
+    #[arg(long = "remote", value_name = "REMOTE")]
+    remotes: Vec<String>,
     /// Push only this branch, or branches matching a pattern (can be repeated)
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
@@ -103,22 +118,121 @@ pub struct GitPushArgs {
     /// correspond to missing local branches.
     #[arg(long)]
     deleted: bool,
+    /// Also delete remote branches whose local branches were deleted
+    ///
+    /// Unlike `--deleted`, this can be combined with the other ways of
+    /// selecting branches. Only tracked branches can be deleted on the remote.
+    /// You'll be asked to confirm before any remote branch is deleted, unless
+    /// `--yes` is given.
+    #[arg(long, conflicts_with_all = ["all", "tracked", "deleted"])]
+    prune: bool,
+    /// Delete the remote branches selected by `--prune` without asking
+    ///
+    /// This is required when the command isn't run in a terminal.
+    #[arg(long, requires = "prune")]
+    yes: bool,
     /// Allow pushing commits with empty descriptions
+    ///
+    /// Without this option, branches that would push commits with empty
+    /// descriptions are skipped, and the other branches are pushed. If no
+    /// other branches are left, nothing is pushed.
     #[arg(long)]
     allow_empty_description: bool,
+    /// Don't push any branch if some of the commits have empty descriptions
+    #[arg(long, conflicts_with = "allow_empty_description")]
+    strict: bool,
     /// Allow pushing commits that are private
     #[arg(long)]
     allow_private: bool,
     /// Push branches pointing to these commits (can be repeated)
     #[arg(long, short)]
     revisions: Vec<RevisionArg>,
+    /// Push branches pointing to descendants of these commits (can be
+    /// repeated)
+    ///
+    /// This is the same as `--revisions 'REVISION::'`, and includes branches
+    /// pointing to the commits themselves.
+    #[arg(long, value_name = "REVISION")]
+    descendants_of: Vec<RevisionArg>,
     /// Push this commit by creating a branch based on its change ID (can be
     /// repeated)
     #[arg(long, short)]
     change: Vec<RevisionArg>,
+    /// Prefix of the branch names created by `--change`
+    ///
+    /// This overrides the `git.push-branch-prefix` setting.
+    #[arg(long, requires = "change", value_name = "PREFIX")]
+    change_prefix: Option<String>,
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
+    /// Render each branch change to push using the given template
+    ///
+    /// The following keywords are defined:
+    ///
+    /// * `branch: String`: Branch name.
+    /// * `old_commit_id: String`: Commit ID on the remote, or empty if the
+    ///   branch is added.
+    /// * `new_commit_id: String`: Commit ID to push, or empty if the branch is
+    ///   deleted.
+    /// * `direction: String`: "forward", "backward", or "sideways" if the
+    ///   branch is moved, or empty otherwise.
+    ///
+    /// For the syntax, see https://github.com/martinvonz/jj/blob/main/docs/templates.md
+    #[arg(long, short = 'T', verbatim_doc_comment)]
+    template: Option<String>,
+    /// Refuse to push if a remote branch moved since it was last fetched
+    ///
+    /// By default, a branch that unexpectedly moved on the remote can still be
+    /// updated if `jj git fetch` would resolve the resulting branch conflict to
+    /// the pushed commit. With this option, every remote branch must be exactly
+    /// at the position of the corresponding remote-tracking branch.
+    #[arg(long)]
+    force_with_lease: bool,
+    /// Push this branch regardless of where it is on the remote (can be
+    /// repeated)
+    ///
+    /// The branch is updated even if it moved on the remote since it was last
+    /// fetched, which may discard the commits pushed by others. It's also
+    /// exempt from `--only-fast-forward`. The other branches are still checked
+    /// as usual. The branch must be among the branches being pushed.
+    #[arg(long, value_name = "BRANCH")]
+    force_branch: Vec<String>,
+    /// Push each branch separately and continue if some of them fail
+    ///
+    /// The outcome of each branch is reported at the end. The command fails if
+    /// any of the branches couldn't be pushed.
+    #[arg(long)]
+    keep_going: bool,
+    /// Start tracking the remote branches of the selected branches before
+    /// pushing
+    ///
+    /// Without this flag, a branch can't be pushed if a remote branch of the
+    /// same name exists but isn't tracked. This is the same as running `jj
+    /// branch track <branch>@<remote>` for such branches first. Branches that
+    /// are already tracked are left as is, and new branches are tracked by the
+    /// push itself.
+    #[arg(long, conflicts_with = "what")]
+    set_upstream: bool,
+    /// Fetch and retry once if a remote branch moved since it was last fetched
+    ///
+    /// The moved branches are fetched from the remote without updating the
+    /// local branches. The push is retried only if the local branches can then
+    /// be fast-forwarded on the remote; nothing is ever force-pushed.
+    #[arg(long, conflicts_with = "keep_going")]
+    retry_on_conflict: bool,
+    /// Refuse to push if any branch would be moved backward or sideways
+    ///
+    /// Nothing is pushed if any of the branch updates isn't a fast-forward.
+    /// Adding and deleting branches is still allowed.
+    #[arg(long)]
+    only_fast_forward: bool,
+    /// Refuse to push if any of the branches already exists on the remote
+    #[arg(long, conflicts_with = "update_only")]
+    create_only: bool,
+    /// Refuse to push if any of the branches doesn't exist on the remote yet
+    #[arg(long)]
+    update_only: bool,
 }
 
 fn make_branch_term(branch_names: &[impl fmt::Display]) -> String {
@@ -137,6 +251,15 @@ enum BranchMoveDirection {
     Sideways,
 }
 
+/// Branch change to push, as rendered by `--template`.
+#[derive(Clone, Debug)]
+struct BranchPushSummary {
+    name: String,
+    old_target: Option<CommitId>,
+    new_target: Option<CommitId>,
+    direction: Option<BranchMoveDirection>,
+}
+
 pub fn cmd_git_push(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -145,19 +268,75 @@ pub fn cmd_git_push(
     let mut workspace_command = command.workspace_helper(ui)?;
     let git_repo = get_git_repo(workspace_command.repo().store())?;
 
-    let remote = if let Some(name) = &args.remote {
-        name.clone()
+    let remotes = if args.remotes.is_empty() {
+        vec![get_default_push_remote(ui, command.settings(), &git_repo)?]
     } else {
-        get_default_push_remote(ui, command.settings(), &git_repo)?
+        args.remotes.iter().unique().cloned().collect()
     };
+    if let [remote] = remotes.as_slice() {
+        return push_to_remote(ui, command, &mut workspace_command, &git_repo, remote, args);
+    }
 
+    let mut outcomes = vec![];
+    for remote in &remotes {
+        let succeeded =
+            match push_to_remote(ui, command, &mut workspace_command, &git_repo, remote, args) {
+                Ok(()) => true,
+                // Push errors are reported as user errors. Anything else is
+                // unlikely to be specific to the remote.
+                Err(err) if err.kind == CommandErrorKind::User => {
+                    writeln!(
+                        ui.warning_default(),
+                        "Failed to push to {remote}: {}",
+                        err.error
+                    )?;
+                    false
+                }
+                Err(err) => return Err(err),
+            };
+        outcomes.push((remote, succeeded));
+    }
+    writeln!(ui.status(), "Push results by remote:")?;
+    for (remote, succeeded) in &outcomes {
+        let outcome = if *succeeded { "ok" } else { "failed" };
+        writeln!(ui.status(), "  {remote}: {outcome}")?;
+    }
+    let failed_remotes = outcomes
+        .iter()
+        .filter(|(_, succeeded)| !succeeded)
+        .map(|(remote, _)| remote)
+        .collect_vec();
+    if failed_remotes.is_empty() {
+        Ok(())
+    } else {
+        Err(user_error(format!(
+            "Failed to push to {}",
+            failed_remotes.iter().join(", ")
+        )))
+    }
+}
+
+/// Pushes the branches selected by `args` to a single `remote`.
+fn push_to_remote(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    workspace_command: &mut WorkspaceCommandHelper,
+    git_repo: &git2::Repository,
+    remote: &str,
+    args: &GitPushArgs,
+) -> Result<(), CommandError> {
     let repo = workspace_command.repo().clone();
     let mut tx = workspace_command.start_transaction();
     let tx_description;
     let mut branch_updates = vec![];
+    // Branches that were explicitly requested but can't be pushed (only
+    // populated with --keep-going)
+    let mut rejected_branches = vec![];
+    // Branches to be deleted on the remote by --prune
+    let mut pruned_branches = vec![];
     if args.all {
-        for (branch_name, targets) in repo.view().local_remote_branches(&remote) {
-            match classify_branch_update(branch_name, &remote, targets) {
+        for (branch_name, targets) in repo.view().local_remote_branches(remote) {
+            match classify_branch_update(branch_name, remote, targets) {
                 Ok(Some(update)) => branch_updates.push((branch_name.to_owned(), update)),
                 Ok(None) => {}
                 Err(reason) => reason.print(ui)?,
@@ -165,11 +344,11 @@ pub fn cmd_git_push(
         }
         tx_description = format!("push all branches to git remote {remote}");
     } else if args.tracked {
-        for (branch_name, targets) in repo.view().local_remote_branches(&remote) {
+        for (branch_name, targets) in repo.view().local_remote_branches(remote) {
             if !targets.remote_ref.is_tracking() {
                 continue;
             }
-            match classify_branch_update(branch_name, &remote, targets) {
+            match classify_branch_update(branch_name, remote, targets) {
                 Ok(Some(update)) => branch_updates.push((branch_name.to_owned(), update)),
                 Ok(None) => {}
                 Err(reason) => reason.print(ui)?,
@@ -177,11 +356,11 @@ pub fn cmd_git_push(
         }
         tx_description = format!("push all tracked branches to git remote {remote}");
     } else if args.deleted {
-        for (branch_name, targets) in repo.view().local_remote_branches(&remote) {
+        for (branch_name, targets) in repo.view().local_remote_branches(remote) {
             if targets.local_target.is_present() {
                 continue;
             }
-            match classify_branch_update(branch_name, &remote, targets) {
+            match classify_branch_update(branch_name, remote, targets) {
                 Ok(Some(update)) => branch_updates.push((branch_name.to_owned(), update)),
                 Ok(None) => {}
                 Err(reason) => reason.print(ui)?,
@@ -192,54 +371,101 @@ pub fn cmd_git_push(
         let mut seen_branches: HashSet<&str> = HashSet::new();
 
         // Process --change branches first because matching branches can be moved.
-        let change_branch_names = update_change_branches(
+        let branch_prefix = match &args.change_prefix {
+            Some(prefix) => prefix.clone(),
+            None => command.settings().push_branch_prefix(),
+        };
+        let change_branch_names =
+            update_change_branches(ui, &mut tx, &args.change, &branch_prefix)?;
+        let branches_by_name = find_branches_to_push(repo.view(), &args.branch, remote)?;
+        let use_default_revset = args.branch.is_empty()
+            && args.change.is_empty()
+            && args.revisions.is_empty()
+            && args.descendants_of.is_empty();
+        let branches_targeted = find_branches_targeted_by_revisions(
             ui,
-            &mut tx,
-            &args.change,
-            &command.settings().push_branch_prefix(),
-        )?;
-        let change_branches = change_branch_names.iter().map(|branch_name| {
-            let targets = LocalAndRemoteRef {
-                local_target: tx.repo().view().get_local_branch(branch_name),
-                remote_ref: tx.repo().view().get_remote_branch(branch_name, &remote),
-            };
-            (branch_name.as_ref(), targets)
-        });
-        let branches_by_name = find_branches_to_push(repo.view(), &args.branch, &remote)?;
-        for (branch_name, targets) in change_branches.chain(branches_by_name.iter().copied()) {
+            tx.base_workspace_helper(),
+            remote,
+            &args.revisions,
+            &args.descendants_of,
+            use_default_revset,
+        )?
+        .into_iter()
+        .map(|(branch_name, _)| branch_name.to_owned())
+        .collect_vec();
+        if args.set_upstream {
+            let branch_names = change_branch_names
+                .iter()
+                .map(|name| name.as_str())
+                .chain(branches_by_name.iter().map(|&(name, _)| name))
+                .chain(branches_targeted.iter().map(|name| name.as_str()))
+                .unique()
+                .collect_vec();
+            track_remote_branches_to_push(ui, &mut tx, remote, &branch_names)?;
+        }
+        // The targets are looked up again since --change and --set-upstream
+        // may have updated them.
+        let tx_view = tx.repo().view();
+        let get_targets = |branch_name: &str| LocalAndRemoteRef {
+            local_target: tx_view.get_local_branch(branch_name),
+            remote_ref: tx_view.get_remote_branch(branch_name, remote),
+        };
+        let change_branches = change_branch_names
+            .iter()
+            .map(|branch_name| (branch_name.as_str(), get_targets(branch_name)));
+        let branches_by_name = branches_by_name
+            .iter()
+            .map(|&(branch_name, _)| (branch_name, get_targets(branch_name)));
+        for (branch_name, targets) in change_branches.chain(branches_by_name) {
             if !seen_branches.insert(branch_name) {
                 continue;
             }
-            match classify_branch_update(branch_name, &remote, targets) {
+            match classify_branch_update(branch_name, remote, targets) {
                 Ok(Some(update)) => branch_updates.push((branch_name.to_owned(), update)),
                 Ok(None) => writeln!(
                     ui.status(),
                     "Branch {branch_name}@{remote} already matches {branch_name}",
                 )?,
+                Err(reason) if args.keep_going => {
+                    reason.print(ui)?;
+                    rejected_branches.push(branch_name.to_owned());
+                }
                 Err(reason) => return Err(reason.into()),
             }
         }
 
-        let use_default_revset =
-            args.branch.is_empty() && args.change.is_empty() && args.revisions.is_empty();
-        let branches_targeted = find_branches_targeted_by_revisions(
-            ui,
-            tx.base_workspace_helper(),
-            &remote,
-            &args.revisions,
-            use_default_revset,
-        )?;
-        for &(branch_name, targets) in &branches_targeted {
+        for branch_name in &branches_targeted {
             if !seen_branches.insert(branch_name) {
                 continue;
             }
-            match classify_branch_update(branch_name, &remote, targets) {
+            let targets = get_targets(branch_name);
+            match classify_branch_update(branch_name, remote, targets) {
                 Ok(Some(update)) => branch_updates.push((branch_name.to_owned(), update)),
                 Ok(None) => {}
                 Err(reason) => reason.print(ui)?,
             }
         }
 
+        if args.prune {
+            for (branch_name, targets) in repo.view().local_remote_branches(remote) {
+                if targets.local_target.is_present() || !seen_branches.insert(branch_name) {
+                    continue;
+                }
+                if targets.remote_ref.is_present() && !targets.remote_ref.is_tracking() {
+                    remote_untracked_reason(branch_name, remote).print(ui)?;
+                    continue;
+                }
+                match classify_branch_update(branch_name, remote, targets) {
+                    Ok(Some(update)) => {
+                        pruned_branches.push(branch_name.to_owned());
+                        branch_updates.push((branch_name.to_owned(), update));
+                    }
+                    Ok(None) => {}
+                    Err(reason) => reason.print(ui)?,
+                }
+            }
+        }
+
         tx_description = format!(
             "push {} to git remote {}",
             make_branch_term(
@@ -248,12 +474,19 @@ pub fn cmd_git_push(
                     .map(|(branch, _)| branch.as_str())
                     .collect_vec()
             ),
-            &remote
+            remote
         );
     }
     if branch_updates.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
-        return Ok(());
+        return if rejected_branches.is_empty() {
+            Ok(())
+        } else {
+            Err(user_error(format!(
+                "Failed to push {}",
+                make_branch_term(&rejected_branches)
+            )))
+        };
     }
 
     let mut branch_push_direction = HashMap::new();
@@ -278,37 +511,308 @@ pub fn cmd_git_push(
         );
     }
 
-    validate_commits_ready_to_push(&branch_updates, &remote, &tx, command, args)?;
+    let mut forced_branches = HashSet::new();
+    for branch_name in &args.force_branch {
+        if !branch_updates.iter().any(|(name, _)| name == branch_name) {
+            return Err(user_error_with_hint(
+                format!("Branch {branch_name} passed to --force-branch is not being pushed"),
+                "Select the branch with --branch, or leave out --force-branch.",
+            ));
+        }
+        forced_branches.insert(branch_name.clone());
+    }
 
-    writeln!(ui.status(), "Branch changes to push to {}:", &remote)?;
-    for (branch_name, update) in &branch_updates {
-        match (&update.old_target, &update.new_target) {
-            (Some(old_target), Some(new_target)) => {
-                let old = short_commit_hash(old_target);
-                let new = short_commit_hash(new_target);
-                // TODO(ilyagr): Add color. Once there is color, "Move branch ... sideways" may
-                // read more naturally than "Move sideways branch ...". Without color, it's hard
-                // to see at a glance if one branch among many was moved sideways (say).
-                // TODO: People on Discord suggest "Move branch ... forward by n commits",
-                // possibly "Move branch ... sideways (X forward, Y back)".
-                let msg = match branch_push_direction.get(branch_name).unwrap() {
-                    BranchMoveDirection::Forward => {
-                        format!("Move forward branch {branch_name} from {old} to {new}")
-                    }
-                    BranchMoveDirection::Backward => {
-                        format!("Move backward branch {branch_name} from {old} to {new}")
-                    }
-                    BranchMoveDirection::Sideways => {
-                        format!("Move sideways branch {branch_name} from {old} to {new}")
-                    }
+    if args.only_fast_forward {
+        let non_fast_forward_branches = branch_updates
+            .iter()
+            .map(|(branch_name, _)| branch_name)
+            .filter(|branch_name| !forced_branches.contains(*branch_name))
+            .filter(|branch_name| {
+                branch_push_direction
+                    .get(*branch_name)
+                    .map_or(false, |&direction| {
+                        direction != BranchMoveDirection::Forward
+                    })
+            })
+            .collect_vec();
+        if !non_fast_forward_branches.is_empty() {
+            return Err(user_error_with_hint(
+                format!(
+                    "Refusing to push non-fast-forward update to {}",
+                    make_branch_term(&non_fast_forward_branches)
+                ),
+                "Rebase the changes onto the remote branches, or push without \
+                 --only-fast-forward.",
+            ));
+        }
+    }
+
+    if args.create_only || args.update_only {
+        let offending_branches = branch_updates
+            .iter()
+            .filter(|(_, update)| update.old_target.is_some() == args.create_only)
+            .map(|(branch_name, _)| branch_name)
+            .collect_vec();
+        if !offending_branches.is_empty() {
+            let message = if args.create_only {
+                format!(
+                    "Refusing to update existing {} on {remote} with --create-only",
+                    make_branch_term(&offending_branches)
+                )
+            } else {
+                format!(
+                    "Refusing to create new {} on {remote} with --update-only",
+                    make_branch_term(&offending_branches)
+                )
+            };
+            return Err(user_error(message));
+        }
+    }
+
+    if !args.allow_empty_description && !args.strict {
+        skip_branches_with_empty_description(ui, &mut branch_updates, remote, &tx)?;
+    }
+    validate_commits_ready_to_push(ui, &branch_updates, remote, &tx, command, args)?;
+
+    let template = args
+        .template
+        .as_ref()
+        .map(|template_text| {
+            command.parse_template(
+                ui,
+                &push_summary_template_language(),
+                template_text,
+                GenericTemplateLanguage::wrap_self,
+            )
+        })
+        .transpose()?
+        .map(|template| template.labeled("git_push"));
+    writeln!(ui.status(), "Branch changes to push to {}:", remote)?;
+    if let Some(template) = &template {
+        if let Some(mut formatter) = ui.status_formatter() {
+            for (branch_name, update) in &branch_updates {
+                let summary = BranchPushSummary {
+                    name: branch_name.clone(),
+                    old_target: update.old_target.clone(),
+                    new_target: update.new_target.clone(),
+                    direction: branch_push_direction.get(branch_name).copied(),
                 };
-                writeln!(ui.status(), "  {msg}")?;
+                template.format(&summary, formatter.as_mut())?;
             }
-            (Some(old_target), None) => {
+        }
+    } else {
+        for (branch_name, update) in &branch_updates {
+            match (&update.old_target, &update.new_target) {
+                (Some(old_target), Some(new_target)) => {
+                    let old = short_commit_hash(old_target);
+                    let new = short_commit_hash(new_target);
+                    // TODO(ilyagr): Add color. Once there is color, "Move branch ... sideways" may
+                    // read more naturally than "Move sideways branch ...". Without color, it's hard
+                    // to see at a glance if one branch among many was moved sideways (say).
+                    // TODO: People on Discord suggest "Move branch ... forward by n commits",
+                    // possibly "Move branch ... sideways (X forward, Y back)".
+                    let msg = match branch_push_direction.get(branch_name).unwrap() {
+                        BranchMoveDirection::Forward => {
+                            format!("Move forward branch {branch_name} from {old} to {new}")
+                        }
+                        BranchMoveDirection::Backward => {
+                            format!("Move backward branch {branch_name} from {old} to {new}")
+                        }
+                        BranchMoveDirection::Sideways => {
+                            format!("Move sideways branch {branch_name} from {old} to {new}")
+                        }
+                    };
+                    writeln!(ui.status(), "  {msg}")?;
+                }
+                (Some(old_target), None) => {
+                    writeln!(
+                        ui.status(),
+                        "  Delete branch {branch_name} from {}",
+                        short_commit_hash(old_target)
+                    )?;
+                }
+                (None, Some(new_target)) => {
+                    writeln!(
+                        ui.status(),
+                        "  Add branch {branch_name} to {}",
+                        short_commit_hash(new_target)
+                    )?;
+                }
+                (None, None) => {
+                    panic!("Not pushing any change to branch {branch_name}");
+                }
+            }
+        }
+    }
+
+    print_non_fast_forward_hint(
+        ui,
+        repo.as_ref(),
+        remote,
+        &branch_updates,
+        &branch_push_direction,
+        &forced_branches,
+    )?;
+
+    if args.dry_run {
+        writeln!(ui.status(), "Dry-run requested, not pushing.")?;
+        return Ok(());
+    }
+
+    if !pruned_branches.is_empty() && !args.yes {
+        if !Ui::can_prompt() {
+            return Err(user_error_with_hint(
+                format!(
+                    "Refusing to delete {} from {remote} without confirmation",
+                    make_branch_term(&pruned_branches)
+                ),
+                "Use --yes to delete them when not running in a terminal.",
+            ));
+        }
+        let prompt = format!(
+            "Delete {} from {remote}?",
+            make_branch_term(&pruned_branches)
+        );
+        if !ui.prompt_yes_no(&prompt, Some(false))? {
+            return Err(user_error("Aborted, nothing was pushed"));
+        }
+    }
+
+    if args.keep_going {
+        return push_branches_individually(
+            ui,
+            tx,
+            git_repo,
+            remote,
+            branch_updates,
+            rejected_branches,
+            args.force_with_lease,
+            &forced_branches,
+            tx_description,
+        );
+    }
+
+    let targets = GitBranchPushTargets {
+        branch_updates,
+        force_with_lease: args.force_with_lease,
+        forced_branches,
+    };
+    match push_branches_with_progress(ui, &mut tx, git_repo, remote, &targets) {
+        Ok(()) => {}
+        Err(GitPushError::RefInUnexpectedLocation(refs) | GitPushError::RefLeaseBroken(refs))
+            if args.retry_on_conflict =>
+        {
+            retry_push_after_fetch(
+                ui,
+                command.settings(),
+                &mut tx,
+                git_repo,
+                remote,
+                &refs,
+                targets,
+            )?
+        }
+        Err(err) => return Err(map_push_error(err)),
+    }
+    tx.finish(ui, tx_description)?;
+    Ok(())
+}
+
+/// Pushes the branches while reporting the progress messages sent by the
+/// remote.
+fn push_branches_with_progress(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    git_repo: &git2::Repository,
+    remote: &str,
+    targets: &GitBranchPushTargets,
+) -> Result<(), GitPushError> {
+    let mut writer = GitSidebandProgressMessageWriter::new(ui);
+    let mut sideband_progress_callback = |progress_message: &[u8]| {
+        _ = writer.write(ui, progress_message);
+    };
+    let result = with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
+        git::push_branches(tx.mut_repo(), git_repo, remote, targets, cb)
+    });
+    _ = writer.flush(ui);
+    result
+}
+
+/// Fetches the branches that moved on the remote, and pushes again if the
+/// local branches can be fast-forwarded from their new remote positions.
+/// Returns the targets that were pushed by the retry.
+fn retry_push_after_fetch(
+    ui: &mut Ui,
+    settings: &UserSettings,
+    tx: &mut WorkspaceCommandTransaction,
+    git_repo: &git2::Repository,
+    remote: &str,
+    moved_refs: &[String],
+    targets: GitBranchPushTargets,
+) -> Result<(), CommandError> {
+    let moved_branches = moved_refs
+        .iter()
+        .filter_map(|ref_name| ref_name.strip_prefix("refs/heads/"))
+        .collect_vec();
+    writeln!(
+        ui.status(),
+        "Fetching {} from {remote} to retry the push",
+        make_branch_term(&moved_branches)
+    )?;
+    let branch_patterns = moved_branches
+        .iter()
+        .map(|&branch_name| StringPattern::exact(branch_name))
+        .collect_vec();
+    // Only the remote-tracking branches are updated so that the local branches
+    // still point to the commits to be pushed.
+    let git_settings = GitSettings {
+        local_branch_update: LocalBranchUpdate::Skip,
+        ..settings.git_settings()
+    };
+    let stats = with_remote_git_callbacks(ui, None, |cb| {
+        git::fetch(
+            tx.mut_repo(),
+            git_repo,
+            remote,
+            &branch_patterns,
+            cb,
+            &git_settings,
+        )
+    })
+    .map_err(|err| match err {
+        GitFetchError::GitImportError(err) => err.into(),
+        GitFetchError::InternalGitError(err) => map_git_error(err),
+        _ => user_error(err),
+    })?;
+    print_git_import_stats(ui, tx.repo(), &stats.import_stats, true)?;
+
+    let mut branch_updates = vec![];
+    for (branch_name, update) in targets.branch_updates {
+        if !moved_branches.contains(&branch_name.as_str()) {
+            branch_updates.push((branch_name, update));
+            continue;
+        }
+        let view = tx.repo().view();
+        let branch_targets = LocalAndRemoteRef {
+            local_target: view.get_local_branch(&branch_name),
+            remote_ref: view.get_remote_branch(&branch_name, remote),
+        };
+        let Some(update) = classify_branch_update(&branch_name, remote, branch_targets)? else {
+            writeln!(
+                ui.status(),
+                "Branch {branch_name}@{remote} already matches {branch_name}",
+            )?;
+            continue;
+        };
+        match (&update.old_target, &update.new_target) {
+            (Some(old_target), Some(new_target))
+                if tx.repo().index().is_ancestor(old_target, new_target) =>
+            {
                 writeln!(
                     ui.status(),
-                    "  Delete branch {branch_name} from {}",
-                    short_commit_hash(old_target)
+                    "  Move forward branch {branch_name} from {} to {}",
+                    short_commit_hash(old_target),
+                    short_commit_hash(new_target)
                 )?;
             }
             (None, Some(new_target)) => {
@@ -318,26 +822,114 @@ pub fn cmd_git_push(
                     short_commit_hash(new_target)
                 )?;
             }
-            (None, None) => {
-                panic!("Not pushing any change to branch {branch_name}");
+            _ => {
+                return Err(user_error_with_hint(
+                    format!(
+                        "Not retrying the push since branch {branch_name} can't be fast-forwarded \
+                         from where it moved on the remote"
+                    ),
+                    "Make the branch point to where you want it to be, and push again.",
+                ));
             }
         }
+        branch_updates.push((branch_name, update));
     }
 
-    if args.dry_run {
-        writeln!(ui.status(), "Dry-run requested, not pushing.")?;
+    let targets = GitBranchPushTargets {
+        branch_updates,
+        force_with_lease: targets.force_with_lease,
+        forced_branches: targets.forced_branches,
+    };
+    if targets.branch_updates.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
         return Ok(());
     }
+    if let Err(err) = push_branches_with_progress(ui, tx, git_repo, remote, &targets) {
+        writeln!(ui.status(), "Retried push failed.")?;
+        return Err(map_push_error(err));
+    }
+    writeln!(ui.status(), "Retried push succeeded.")?;
+    Ok(())
+}
+
+/// Pushes the branches one by one, and reports the outcome of each of them.
+/// Branches that were pushed successfully are recorded even if others failed.
+#[allow(clippy::too_many_arguments)]
+fn push_branches_individually(
+    ui: &mut Ui,
+    mut tx: WorkspaceCommandTransaction,
+    git_repo: &git2::Repository,
+    remote: &str,
+    branch_updates: Vec<(String, BranchPushUpdate)>,
+    rejected_branches: Vec<String>,
+    force_with_lease: bool,
+    forced_branches: &HashSet<String>,
+    tx_description: String,
+) -> Result<(), CommandError> {
+    let mut outcomes = vec![];
+    for (branch_name, update) in branch_updates {
+        let targets = GitBranchPushTargets {
+            branch_updates: vec![(branch_name.clone(), update)],
+            force_with_lease,
+            forced_branches: forced_branches.clone(),
+        };
+        let result = push_branches_with_progress(ui, &mut tx, git_repo, remote, &targets);
+        outcomes.push((branch_name, result));
+    }
 
-    let targets = GitBranchPushTargets { branch_updates };
-    let mut writer = GitSidebandProgressMessageWriter::new(ui);
-    let mut sideband_progress_callback = |progress_message: &[u8]| {
-        _ = writer.write(ui, progress_message);
-    };
-    with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
-        git::push_branches(tx.mut_repo(), &git_repo, &remote, &targets, cb)
-    })
-    .map_err(|err| match err {
+    let mut failed_branches = rejected_branches;
+    writeln!(ui.status(), "Push results:")?;
+    for branch_name in &failed_branches {
+        writeln!(ui.status(), "  {branch_name}: rejected")?;
+    }
+    for (branch_name, result) in &outcomes {
+        match result {
+            Ok(()) => writeln!(ui.status(), "  {branch_name}: pushed")?,
+            Err(err) => {
+                writeln!(ui.status(), "  {branch_name}: failed ({err})")?;
+                failed_branches.push(branch_name.clone());
+            }
+        }
+    }
+    if outcomes.iter().any(|(_, result)| result.is_ok()) {
+        tx.finish(ui, tx_description)?;
+    }
+    if failed_branches.is_empty() {
+        Ok(())
+    } else {
+        Err(user_error(format!(
+            "Failed to push {}",
+            make_branch_term(&failed_branches)
+        )))
+    }
+}
+
+/// Starts tracking the remote branches of `branch_names` which exist on the
+/// `remote` but aren't tracked yet, so they can be pushed. New branches are
+/// tracked by the push itself.
+fn track_remote_branches_to_push(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    remote: &str,
+    branch_names: &[&str],
+) -> Result<(), CommandError> {
+    for &branch_name in branch_names {
+        let remote_ref = tx.repo().get_remote_branch(branch_name, remote);
+        if remote_ref.is_tracking() {
+            writeln!(
+                ui.status(),
+                "Remote branch already tracked: {branch_name}@{remote}"
+            )?;
+        } else if remote_ref.is_present() {
+            tx.mut_repo().track_remote_branch(branch_name, remote);
+            writeln!(ui.status(), "Tracking remote branch {branch_name}@{remote}")?;
+        }
+    }
+    Ok(())
+}
+
+fn map_push_error(err: GitPushError) -> CommandError {
+    match err {
         GitPushError::InternalGitError(err) => map_git_error(err),
         GitPushError::RefInUnexpectedLocation(refs) => user_error_with_hint(
             format!(
@@ -348,16 +940,173 @@ pub fn cmd_git_push(
             "Try fetching from the remote, then make the branch point to where you want it to be, \
              and push again.",
         ),
+        GitPushError::RefLeaseBroken(refs) => user_error_with_hint(
+            format!(
+                "Refusing to push a branch that moved on the remote since it was last fetched. \
+                 Affected refs: {}",
+                refs.join(", ")
+            ),
+            "Try fetching from the remote, then make the branch point to where you want it to be, \
+             and push again.",
+        ),
         _ => user_error(err),
-    })?;
-    writer.flush(ui)?;
-    tx.finish(ui, tx_description)?;
+    }
+}
+
+/// Prints a hint if branches will be moved backward or sideways from their
+/// remote-tracking targets to commits brought in from another remote. Unlike
+/// branches moved or rewritten locally, this suggests that the remote-tracking
+/// branches may be stale, in which case the remote will reject the push.
+fn print_non_fast_forward_hint(
+    ui: &Ui,
+    repo: &dyn Repo,
+    remote: &str,
+    branch_updates: &[(String, BranchPushUpdate)],
+    branch_push_direction: &HashMap<String, BranchMoveDirection>,
+    forced_branches: &HashSet<String>,
+) -> Result<(), CommandError> {
+    let index = repo.index();
+    let stale_branch_names = branch_updates
+        .iter()
+        // Forced branches are meant to overwrite the remote branches.
+        .filter(|(branch_name, _)| !forced_branches.contains(branch_name))
+        .filter(|(branch_name, _)| {
+            branch_push_direction
+                .get(branch_name)
+                .map_or(false, |&direction| {
+                    direction != BranchMoveDirection::Forward
+                })
+        })
+        .filter(|(branch_name, update)| {
+            let Some(new_id) = &update.new_target else {
+                return false;
+            };
+            repo.view()
+                .remote_branches_matching(
+                    &StringPattern::exact(branch_name),
+                    &StringPattern::everything(),
+                )
+                .filter(|((_, remote_name), remote_ref)| {
+                    *remote_name != remote
+                        && *remote_name != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                        && remote_ref.is_tracking()
+                })
+                .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+                .any(|id| index.is_ancestor(new_id, id))
+        })
+        .map(|(branch_name, _)| branch_name.as_str())
+        .collect_vec();
+    if !stale_branch_names.is_empty() {
+        writeln!(
+            ui.hint_default(),
+            "Not fast-forwarding {} on {remote}. If the remote was updated since the last fetch, \
+             run `jj git fetch --remote {remote}` first.",
+            make_branch_term(&stale_branch_names)
+        )?;
+    }
+    Ok(())
+}
+
+fn push_summary_template_language() -> GenericTemplateLanguage<'static, BranchPushSummary> {
+    type L = GenericTemplateLanguage<'static, BranchPushSummary>;
+    let mut language = L::new();
+    language.add_keyword("branch", |self_property| {
+        let out_property = self_property.map(|summary| summary.name);
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("old_commit_id", |self_property| {
+        let out_property =
+            self_property.map(|summary| summary.old_target.map(|id| id.hex()).unwrap_or_default());
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("new_commit_id", |self_property| {
+        let out_property =
+            self_property.map(|summary| summary.new_target.map(|id| id.hex()).unwrap_or_default());
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("direction", |self_property| {
+        let out_property = self_property.map(|summary| {
+            match summary.direction {
+                Some(BranchMoveDirection::Forward) => "forward",
+                Some(BranchMoveDirection::Backward) => "backward",
+                Some(BranchMoveDirection::Sideways) => "sideways",
+                None => "",
+            }
+            .to_owned()
+        });
+        Ok(L::wrap_string(out_property))
+    });
+    language
+}
+
+/// Commits between the remote (or immutable) heads and the `new_heads`.
+fn commits_to_push_expression(
+    tx: &WorkspaceCommandTransaction,
+    remote: &str,
+    new_heads: Vec<CommitId>,
+) -> Result<Rc<RevsetExpression>, CommandError> {
+    let workspace_helper = tx.base_workspace_helper();
+    let old_heads = workspace_helper
+        .repo()
+        .view()
+        .remote_branches(remote)
+        .flat_map(|(_, old_head)| old_head.target.added_ids())
+        .cloned()
+        .collect_vec();
+    Ok(RevsetExpression::commits(old_heads)
+        .union(&revset_util::parse_immutable_heads_expression(
+            &workspace_helper.revset_parse_context(),
+        )?)
+        .range(&RevsetExpression::commits(new_heads)))
+}
+
+/// Removes the branches that would push commits with empty descriptions, so
+/// the other branches can still be pushed. If all branches would be removed,
+/// they are kept, and `validate_commits_ready_to_push()` reports the error.
+fn skip_branches_with_empty_description(
+    ui: &Ui,
+    branch_updates: &mut Vec<(String, BranchPushUpdate)>,
+    remote: &str,
+    tx: &WorkspaceCommandTransaction,
+) -> Result<(), CommandError> {
+    let mut skipped_branches = vec![];
+    for (branch_name, update) in branch_updates.iter() {
+        let Some(new_target) = &update.new_target else {
+            continue;
+        };
+        let commits_to_push = commits_to_push_expression(tx, remote, vec![new_target.clone()])?;
+        let has_empty_description = tx
+            .base_workspace_helper()
+            .attach_revset_evaluator(commits_to_push)?
+            .evaluate_to_commits()?
+            .process_results(|mut commits| commits.any(|commit| commit.description().is_empty()))?;
+        if has_empty_description {
+            skipped_branches.push(branch_name.clone());
+        }
+    }
+    if skipped_branches.is_empty() || skipped_branches.len() == branch_updates.len() {
+        return Ok(());
+    }
+    writeln!(
+        ui.warning_default(),
+        "Skipping {} with commits that have no description",
+        make_branch_term(&skipped_branches)
+    )?;
+    writeln!(
+        ui.hint_default(),
+        "Describe the commits, or use --allow-empty-description to push them anyway."
+    )?;
+    branch_updates.retain(|(branch_name, _)| !skipped_branches.contains(branch_name));
     Ok(())
 }
 
 /// Validates that the commits that will be pushed are ready (have authorship
 /// information, are not conflicted, etc.)
+///
+/// If more than one commit isn't ready, all of them are reported before
+/// failing.
 fn validate_commits_ready_to_push(
+    ui: &Ui,
     branch_updates: &[(String, BranchPushUpdate)],
     remote: &str,
     tx: &WorkspaceCommandTransaction,
@@ -365,23 +1114,11 @@ fn validate_commits_ready_to_push(
     args: &GitPushArgs,
 ) -> Result<(), CommandError> {
     let workspace_helper = tx.base_workspace_helper();
-    let repo = workspace_helper.repo();
-
     let new_heads = branch_updates
         .iter()
         .filter_map(|(_, update)| update.new_target.clone())
         .collect_vec();
-    let old_heads = repo
-        .view()
-        .remote_branches(remote)
-        .flat_map(|(_, old_head)| old_head.target.added_ids())
-        .cloned()
-        .collect_vec();
-    let commits_to_push = RevsetExpression::commits(old_heads)
-        .union(&revset_util::parse_immutable_heads_expression(
-            &tx.base_workspace_helper().revset_parse_context(),
-        )?)
-        .range(&RevsetExpression::commits(new_heads));
+    let commits_to_push = commits_to_push_expression(tx, remote, new_heads)?;
 
     let config = command.settings().config();
     let is_private = if let Ok(revset) = config.get_string("git.private-commits") {
@@ -393,6 +1130,7 @@ fn validate_commits_ready_to_push(
         Box::new(|_: &CommitId| false)
     };
 
+    let mut blocked_commits = vec![];
     for commit in workspace_helper
         .attach_revset_evaluator(commits_to_push)?
         .evaluate_to_commits()?
@@ -420,14 +1158,37 @@ fn validate_commits_ready_to_push(
             reasons.push("it is private");
         }
         if !reasons.is_empty() {
-            return Err(user_error(format!(
-                "Won't push commit {} since {}",
-                short_commit_hash(commit.id()),
-                reasons.join(" and ")
-            )));
+            blocked_commits.push((commit.id().clone(), reasons));
+        }
+    }
+    match blocked_commits.as_slice() {
+        [] => Ok(()),
+        [(commit_id, reasons)] => Err(user_error(format!(
+            "Won't push commit {} since {}",
+            short_commit_hash(commit_id),
+            reasons.join(" and ")
+        ))),
+        _ => {
+            writeln!(
+                ui.warning_default(),
+                "The following commits are not ready to be pushed:"
+            )?;
+            if let Some(mut formatter) = ui.status_formatter() {
+                for (commit_id, reasons) in &blocked_commits {
+                    writeln!(
+                        formatter,
+                        "  {}: {}",
+                        short_commit_hash(commit_id),
+                        reasons.join(" and ")
+                    )?;
+                }
+            }
+            Err(user_error(format!(
+                "Won't push {} commits",
+                blocked_commits.len()
+            )))
         }
     }
-    Ok(())
 }
 
 fn get_default_push_remote(
@@ -494,16 +1255,20 @@ fn classify_branch_update(
             message: format!("Branch {branch_name}@{remote_name} is conflicted"),
             hint: Some("Run `jj git fetch` to update the conflicted remote branch.".to_owned()),
         }),
-        BranchPushAction::RemoteUntracked => Err(RejectedBranchUpdateReason {
-            message: format!("Non-tracking remote branch {branch_name}@{remote_name} exists"),
-            hint: Some(format!(
-                "Run `jj branch track {branch_name}@{remote_name}` to import the remote branch."
-            )),
-        }),
+        BranchPushAction::RemoteUntracked => Err(remote_untracked_reason(branch_name, remote_name)),
         BranchPushAction::Update(update) => Ok(Some(update)),
     }
 }
 
+fn remote_untracked_reason(branch_name: &str, remote_name: &str) -> RejectedBranchUpdateReason {
+    RejectedBranchUpdateReason {
+        message: format!("Non-tracking remote branch {branch_name}@{remote_name} exists"),
+        hint: Some(format!(
+            "Run `jj branch track {branch_name}@{remote_name}` to import the remote branch."
+        )),
+    }
+}
+
 /// Creates or moves branches based on the change IDs.
 fn update_change_branches(
     ui: &Ui,
@@ -528,13 +1293,16 @@ fn update_change_branches(
         let view = tx.base_repo().view();
         if view.get_local_branch(&branch_name).is_absent() {
             // A local branch with the full change ID doesn't exist already, so use the
-            // short ID if it's not ambiguous (which it shouldn't be most of the time).
+            // short ID if it's not ambiguous (which it shouldn't be most of the time),
+            // and if the branch wasn't created for another change.
+            let short_branch_name = format!("{branch_prefix}{short_change_id}");
             if workspace_command
                 .resolve_single_rev(&RevisionArg::from(short_change_id.clone()))
                 .is_ok()
+                && !is_branch_of_other_change(tx.base_repo().as_ref(), &short_branch_name, &commit)?
             {
                 // Short change ID is not ambiguous, so update the branch name to use it.
-                branch_name = format!("{branch_prefix}{short_change_id}");
+                branch_name = short_branch_name;
             };
         }
         if view.get_local_branch(&branch_name).is_absent() {
@@ -550,6 +1318,24 @@ fn update_change_branches(
     Ok(branch_names)
 }
 
+/// Checks whether the branch points to a commit of another change which isn't
+/// an ancestor of the `commit`. Moving such a branch would hijack it.
+fn is_branch_of_other_change(
+    repo: &dyn Repo,
+    branch_name: &str,
+    commit: &Commit,
+) -> Result<bool, CommandError> {
+    for id in repo.view().get_local_branch(branch_name).added_ids() {
+        let old_commit = repo.store().get_commit(id)?;
+        if old_commit.change_id() != commit.change_id()
+            && !repo.index().is_ancestor(id, commit.id())
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 fn find_branches_to_push<'a>(
     view: &'a View,
     branch_patterns: &[StringPattern],
@@ -586,6 +1372,7 @@ fn find_branches_targeted_by_revisions<'a>(
     workspace_command: &'a WorkspaceCommandHelper,
     remote_name: &str,
     revisions: &[RevisionArg],
+    descendants_of: &[RevisionArg],
     use_default_revset: bool,
 ) -> Result<Vec<(&'a str, LocalAndRemoteRef<'a>)>, CommandError> {
     let mut revision_commit_ids = HashSet::new();
@@ -623,6 +1410,26 @@ fn find_branches_targeted_by_revisions<'a>(
         }
         revision_commit_ids.extend(commit_ids);
     }
+    for rev_arg in descendants_of {
+        let base_ids = workspace_command
+            .parse_revset(rev_arg)?
+            .evaluate_to_commit_ids()?
+            .collect();
+        let expression = RevsetExpression::commits(base_ids)
+            .descendants()
+            .intersection(&RevsetExpression::branches(StringPattern::everything()));
+        let mut commit_ids = expression
+            .evaluate_programmatic(workspace_command.repo().as_ref())?
+            .iter()
+            .peekable();
+        if commit_ids.peek().is_none() {
+            writeln!(
+                ui.warning_default(),
+                "No branches point to descendants of the specified revisions: {rev_arg}"
+            )?;
+        }
+        revision_commit_ids.extend(commit_ids);
+    }
     let branches_targeted = workspace_command
         .repo()
         .view()
@@ -26,6 +26,7 @@ use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::diff_util::DiffFormatArgs;
@@ -52,7 +53,7 @@ pub(crate) struct LogArgs {
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
     /// Show revisions in the opposite order (older revisions first)
-    #[arg(long)]
+    #[arg(long, visible_alias = "reverse")]
     reversed: bool,
     /// Limit number of revisions to show
     ///
@@ -111,6 +112,20 @@ pub(crate) fn cmd_log(
         expression
     };
 
+    // The graph is always in topological order, which would silently discard
+    // the order requested by sorted().
+    if !args.no_graph
+        && matches!(
+            revset_expression.expression().as_ref(),
+            RevsetExpression::Sorted { .. }
+        )
+    {
+        return Err(user_error_with_hint(
+            "sorted() can't be used with the graph",
+            "Use --no-graph to list the commits in the sorted order.",
+        ));
+    }
+
     let repo = workspace_command.repo();
     let matcher = fileset_expression.to_matcher();
     let revset = revset_expression.evaluate()?;
@@ -12,13 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::slice;
 
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::hex_util::to_forward_hex;
+use jj_lib::object_id::HexPrefix;
+use jj_lib::object_id::PrefixResolution;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo::RepoLoader;
 
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::graphlog::get_graphlog;
 use crate::graphlog::Edge;
@@ -43,6 +57,10 @@ pub struct OperationLogArgs {
         value_name = "LIMIT"
     )]
     deprecated_limit: Option<usize>,
+    /// Only show operations which added, rewrote, or abandoned a commit of
+    /// the given change
+    #[arg(long, alias = "limit-by-change", value_name = "CHANGE_ID")]
+    change: Option<String>,
     /// Don't show the graph, show a flat list of operations
     #[arg(long)]
     no_graph: bool,
@@ -58,15 +76,17 @@ pub fn cmd_op_log(
     command: &CommandHelper,
     args: &OperationLogArgs,
 ) -> Result<(), CommandError> {
-    let current_op = if command.is_working_copy_writable() {
+    let (current_op, repo_loader) = if command.is_working_copy_writable() {
         let workspace_command = command.workspace_helper(ui)?;
-        workspace_command.repo().operation().clone()
+        let repo = workspace_command.repo();
+        (repo.operation().clone(), repo.loader())
     } else {
         // Don't load the repo so that the operation history can be inspected
         // even with a corrupted repo state. For example, you can find the first
         // bad operation id to be abandoned.
         let workspace = command.load_workspace()?;
-        command.resolve_operation(ui, workspace.repo_loader())?
+        let op = command.resolve_operation(ui, workspace.repo_loader())?;
+        (op, workspace.repo_loader().clone())
     };
     let op_store = current_op.op_store();
 
@@ -112,15 +132,28 @@ pub fn cmd_op_log(
         )?;
     }
     let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
-    let iter = op_walk::walk_ancestors(slice::from_ref(&current_op)).take(limit);
+    let iter: Box<dyn Iterator<Item = Result<_, CommandError>>> =
+        if let Some(change_str) = &args.change {
+            let change_id = resolve_change_id(&repo_loader, &current_op, change_str)?;
+            Box::new(find_ops_touching_change(
+                &repo_loader,
+                &current_op,
+                change_id,
+            ))
+        } else {
+            Box::new(
+                op_walk::walk_ancestors(slice::from_ref(&current_op)).map(|op| {
+                    let op = op?;
+                    let edges = op.parent_ids().iter().cloned().map(Edge::Direct).collect();
+                    Ok((op, edges))
+                }),
+            )
+        };
+    let iter = iter.take(limit);
     if !args.no_graph {
         let mut graph = get_graphlog(command.settings(), formatter.raw());
-        for op in iter {
-            let op = op?;
-            let mut edges = vec![];
-            for id in op.parent_ids() {
-                edges.push(Edge::Direct(id.clone()));
-            }
+        for op_and_edges in iter {
+            let (op, edges) = op_and_edges?;
             let mut buffer = vec![];
             with_content_format.write_graph_text(
                 ui.new_formatter(&mut buffer).as_mut(),
@@ -139,11 +172,165 @@ pub fn cmd_op_log(
             )?;
         }
     } else {
-        for op in iter {
-            let op = op?;
+        for op_and_edges in iter {
+            let (op, _) = op_and_edges?;
             with_content_format.write(formatter, |formatter| template.format(&op, formatter))?;
         }
     }
 
     Ok(())
 }
+
+type OperationWithEdges = (Operation, Vec<Edge<OperationId>>);
+
+/// Resolves a change ID (or a unique prefix of it) visible at `current_op`. A
+/// full change ID can be specified even if the change is no longer visible.
+fn resolve_change_id(
+    repo_loader: &RepoLoader,
+    current_op: &Operation,
+    change_str: &str,
+) -> Result<ChangeId, CommandError> {
+    let Some(prefix) = to_forward_hex(change_str)
+        .as_deref()
+        .and_then(HexPrefix::new)
+    else {
+        return Err(user_error(format!("Invalid change ID: {change_str}")));
+    };
+    let repo = repo_loader.load_at(current_op)?;
+    match repo.resolve_change_id_prefix(&prefix) {
+        PrefixResolution::SingleMatch(ids) => {
+            let commit = repo.store().get_commit(&ids[0])?;
+            Ok(commit.change_id().clone())
+        }
+        PrefixResolution::AmbiguousMatch => Err(user_error(format!(
+            "Change ID prefix \"{change_str}\" is ambiguous"
+        ))),
+        PrefixResolution::NoMatch => match prefix.as_full_bytes() {
+            Some(bytes) if bytes.len() == repo.store().change_id_length() => {
+                Ok(ChangeId::from_bytes(bytes))
+            }
+            _ => Err(user_error(format!("No such change: {change_str}"))),
+        },
+    }
+}
+
+/// Lazily finds the ancestors of `current_op` in which the visible commits of
+/// the change were added, rewritten, or abandoned. Each operation is returned
+/// with the edges to the nearest such ancestor operations.
+fn find_ops_touching_change<'a>(
+    repo_loader: &'a RepoLoader,
+    current_op: &Operation,
+    change_id: ChangeId,
+) -> impl Iterator<Item = Result<OperationWithEdges, CommandError>> + 'a {
+    let mut matcher = ChangeOpMatcher {
+        repo_loader,
+        change_id,
+        heads_by_op: HashMap::new(),
+        commit_ids_by_op: HashMap::new(),
+        is_match_by_op: HashMap::new(),
+    };
+    op_walk::walk_ancestors(slice::from_ref(current_op)).filter_map(move |op| {
+        let op = match op {
+            Ok(op) => op,
+            Err(err) => return Some(Err(err.into())),
+        };
+        match matcher.is_match(&op) {
+            Ok(true) => Some(matcher.nearest_match_edges(&op).map(|edges| (op, edges))),
+            Ok(false) => None,
+            Err(err) => Some(Err(err)),
+        }
+    })
+}
+
+/// Tells whether operations changed the visible commits of a change. The repo
+/// is only loaded at operations whose view heads differ from their parents'.
+struct ChangeOpMatcher<'a> {
+    repo_loader: &'a RepoLoader,
+    change_id: ChangeId,
+    heads_by_op: HashMap<OperationId, HashSet<CommitId>>,
+    commit_ids_by_op: HashMap<OperationId, Vec<CommitId>>,
+    is_match_by_op: HashMap<OperationId, bool>,
+}
+
+impl ChangeOpMatcher<'_> {
+    fn is_match(&mut self, op: &Operation) -> Result<bool, CommandError> {
+        if let Some(&is_match) = self.is_match_by_op.get(op.id()) {
+            return Ok(is_match);
+        }
+        let parents: Vec<Operation> = op.parents().try_collect()?;
+        let is_match = !parents.is_empty() && self.differs_from_all(op, &parents)?;
+        self.is_match_by_op.insert(op.id().clone(), is_match);
+        Ok(is_match)
+    }
+
+    fn differs_from_all(
+        &mut self,
+        op: &Operation,
+        parents: &[Operation],
+    ) -> Result<bool, CommandError> {
+        // The visible commits can only differ if the view heads do.
+        for parent in parents {
+            if parent.view_id() == op.view_id() {
+                return Ok(false);
+            }
+            self.load_heads(op)?;
+            self.load_heads(parent)?;
+            if self.heads_by_op[op.id()] == self.heads_by_op[parent.id()] {
+                return Ok(false);
+            }
+        }
+        for parent in parents {
+            self.load_commit_ids(op)?;
+            self.load_commit_ids(parent)?;
+            if self.commit_ids_by_op[op.id()] == self.commit_ids_by_op[parent.id()] {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn load_heads(&mut self, op: &Operation) -> Result<(), CommandError> {
+        if !self.heads_by_op.contains_key(op.id()) {
+            let heads = op.view()?.heads().clone();
+            self.heads_by_op.insert(op.id().clone(), heads);
+        }
+        Ok(())
+    }
+
+    fn load_commit_ids(&mut self, op: &Operation) -> Result<(), CommandError> {
+        if !self.commit_ids_by_op.contains_key(op.id()) {
+            let repo = self.repo_loader.load_at(op)?;
+            let mut commit_ids = repo.resolve_change_id(&self.change_id).unwrap_or_default();
+            commit_ids.sort();
+            self.commit_ids_by_op.insert(op.id().clone(), commit_ids);
+        }
+        Ok(())
+    }
+
+    /// Returns the edges from `op` to the nearest matching ancestors.
+    fn nearest_match_edges(
+        &mut self,
+        op: &Operation,
+    ) -> Result<Vec<Edge<OperationId>>, CommandError> {
+        let mut edges = vec![];
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<Operation> = op.parents().try_collect()?;
+        while let Some(ancestor) = queue.pop_front() {
+            if !visited.insert(ancestor.id().clone()) {
+                continue;
+            }
+            if self.is_match(&ancestor)? {
+                if op.parent_ids().contains(ancestor.id()) {
+                    edges.push(Edge::Direct(ancestor.id().clone()));
+                } else {
+                    edges.push(Edge::Indirect(ancestor.id().clone()));
+                }
+            } else {
+                for parent in ancestor.parents() {
+                    queue.push_back(parent?);
+                }
+            }
+        }
+        Ok(edges)
+    }
+}
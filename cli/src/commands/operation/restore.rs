@@ -12,12 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use clap::builder::NonEmptyStringValueParser;
 use jj_lib::object_id::ObjectId;
+use jj_lib::op_store::RefTarget;
 
 use super::view_with_desired_portions_restored;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -39,6 +43,13 @@ pub struct OperationRestoreArgs {
     /// This option is EXPERIMENTAL.
     #[arg(long, value_enum, default_values_t = DEFAULT_UNDO_WHAT)]
     what: Vec<UndoWhatToRestore>,
+
+    /// Create a branch pointing to the current working-copy commit before
+    /// restoring
+    ///
+    /// This makes it easy to get back to the pre-restore state later.
+    #[arg(long, value_name = "NAME", value_parser = NonEmptyStringValueParser::new())]
+    create_branch: Option<String>,
 }
 
 pub fn cmd_op_restore(
@@ -48,6 +59,22 @@ pub fn cmd_op_restore(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let target_op = workspace_command.resolve_single_op(&args.operation)?;
+    let backup_commit = if let Some(name) = &args.create_branch {
+        if workspace_command
+            .repo()
+            .view()
+            .get_local_branch(name)
+            .is_present()
+        {
+            return Err(user_error_with_hint(
+                format!("Branch already exists: {name}"),
+                "Use a different name for the backup branch.",
+            ));
+        }
+        Some(workspace_command.resolve_single_rev(&RevisionArg::AT)?)
+    } else {
+        None
+    };
     let mut tx = workspace_command.start_transaction();
     let new_view = view_with_desired_portions_restored(
         target_op.view()?.store_view(),
@@ -55,6 +82,18 @@ pub fn cmd_op_restore(
         &args.what,
     );
     tx.mut_repo().set_view(new_view);
+    if let (Some(name), Some(commit)) = (&args.create_branch, &backup_commit) {
+        // The commit may not be visible in the restored view, so keep it
+        // reachable as a head.
+        tx.mut_repo().add_head(commit)?;
+        tx.mut_repo()
+            .set_local_branch_target(name, RefTarget::normal(commit.id().clone()));
+        if let Some(mut formatter) = ui.status_formatter() {
+            write!(formatter, "Created branch {name} pointing to ")?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
     tx.finish(ui, format!("restore to operation {}", target_op.id().hex()))?;
 
     Ok(())
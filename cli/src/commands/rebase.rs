@@ -13,6 +13,9 @@
 // limitations under the License.
 
 use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
 use std::io::Write;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -23,7 +26,9 @@ use itertools::Itertools;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
+use jj_lib::dag_walk;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
@@ -32,8 +37,10 @@ use jj_lib::rewrite::move_commits;
 use jj_lib::rewrite::rebase_commit_with_options;
 use jj_lib::rewrite::CommitRewriter;
 use jj_lib::rewrite::EmptyBehaviour;
+use jj_lib::rewrite::MoveCommitsOptions;
 use jj_lib::rewrite::MoveCommitsStats;
 use jj_lib::rewrite::RebaseOptions;
+use jj_lib::rewrite::RebasedCommit;
 use jj_lib::settings::UserSettings;
 use tracing::instrument;
 
@@ -44,7 +51,10 @@ use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::cli_error;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
+use crate::rebase_todo::edit_rebase_todo;
+use crate::rebase_todo::TodoAction;
 use crate::ui::Ui;
 
 /// Move revisions to different parent(s)
@@ -82,9 +92,11 @@ use crate::ui::Ui;
 ///
 /// In other words, `jj rebase -b X -d Y` rebases commits in the revset
 /// `(Y..X)::` (which is equivalent to `jj rebase -s 'roots(Y..X)' -d Y` for a
-/// single root). For example, either `jj rebase -b L -d O` or `jj rebase -b M
-/// -d O` would transform your history like this (because `L` and `M` are on the
-/// same "branch", relative to the destination):
+/// single root). Similarly, `jj rebase -b X --insert-after Y` inserts the
+/// commits in `(Y..X)::` between `Y` and its children. For example, either
+/// `jj rebase -b L -d O` or `jj rebase -b M -d O` would transform your history
+/// like this (because `L` and `M` are on the same "branch", relative to the
+/// destination):
 ///
 /// ```text
 /// O           N'
@@ -135,7 +147,7 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 #[command(group(ArgGroup::new("to_rebase").args(&["branch", "source", "revisions"])))]
-#[command(group(ArgGroup::new("target").args(&["destination", "insert_after", "insert_before"]).multiple(true).required(true)))]
+#[command(group(ArgGroup::new("target").args(&["destination", "insert_after", "insert_before", "interactive"]).multiple(true).required(true)))]
 pub(crate) struct RebaseArgs {
     /// Rebase the whole branch relative to destination's ancestors (can be
     /// repeated)
@@ -152,7 +164,12 @@ pub(crate) struct RebaseArgs {
     ///
     /// Each specified revision will become a direct child of the destination
     /// revision(s), even if some of the source revisions are descendants
-    /// of others.
+    /// of others. Such a nested source is detached from the tree of its
+    /// ancestor source, which is rebased without it.
+    ///
+    /// A single argument may resolve to multiple revisions, e.g.
+    /// `-s 'roots(feature::)'`. Revisions specified more than once are
+    /// rebased only once.
     ///
     /// If none of `-b`, `-s`, or `-r` is provided, then the default is `-b @`.
     #[arg(long, short)]
@@ -173,30 +190,82 @@ pub(crate) struct RebaseArgs {
     /// The revision(s) to insert after (can be repeated to create a merge
     /// commit)
     ///
-    /// Only works with `-r`.
+    /// Only works with `-r` and `-b`.
     #[arg(
         long,
         short = 'A',
         visible_alias = "after",
         conflicts_with = "destination",
-        conflicts_with = "source",
-        conflicts_with = "branch"
+        conflicts_with = "source"
     )]
     insert_after: Vec<RevisionArg>,
     /// The revision(s) to insert before (can be repeated to create a merge
     /// commit)
     ///
-    /// Only works with `-r`.
+    /// Only works with `-r` and `-b`.
     #[arg(
         long,
         short = 'B',
         visible_alias = "before",
         conflicts_with = "destination",
-        conflicts_with = "source",
-        conflicts_with = "branch"
+        conflicts_with = "source"
     )]
     insert_before: Vec<RevisionArg>,
 
+    /// Rebase onto the merge base of the source and destination revisions
+    /// instead of the destination itself
+    ///
+    /// This keeps the history shared with the destination, while dropping
+    /// the commits between the merge base and the source.
+    #[arg(long, requires = "source", conflicts_with_all = ["branch", "revisions"])]
+    onto_merge_base: bool,
+
+    /// Reorder or drop the commits of a linear stack by editing a todo list
+    ///
+    /// The stack consists of the `-s` revision and its descendants, which must
+    /// form a linear sequence. An editor is opened with a `pick` line per
+    /// commit, oldest first. The lines can be reordered, and `pick` can be
+    /// changed to `drop` to abandon the commit. The first picked commit is
+    /// rebased onto the destination, or onto the parents of the `-s` revision
+    /// if no destination is given.
+    #[arg(
+        long,
+        short,
+        requires = "source",
+        conflicts_with_all = [
+            "insert_after",
+            "insert_before",
+            "onto_merge_base",
+            "rebase_merges",
+            "skip_emptied",
+            "abandon_emptied",
+        ]
+    )]
+    interactive: bool,
+
+    /// How to handle merges among the rebased commits
+    ///
+    /// With `flatten`, the rebased commits are laid out linearly onto the
+    /// destination in topological order, dropping the merges between them.
+    /// Only works with `-d`.
+    #[arg(long, value_enum, default_value_t = RebaseMerges::Preserve)]
+    rebase_merges: RebaseMerges,
+
+    /// Whether the rebased commits keep their parents outside the target set
+    ///
+    /// When a merge commit is rebased with `-r` together with some of its
+    /// parents, its other parents are kept by default. Use `false` to drop
+    /// them, so the merge only keeps the parents within the target set.
+    #[arg(
+        long,
+        value_name = "BOOL",
+        default_value_t = true,
+        action = clap::ArgAction::Set,
+        requires = "revisions",
+        conflicts_with_all = ["source", "branch"]
+    )]
+    retain_merge_parents_outside_set: bool,
+
     /// Deprecated. Use --skip-emptied instead.
     #[arg(long, conflicts_with = "revisions", hide = true)]
     skip_empty: bool,
@@ -207,6 +276,29 @@ pub(crate) struct RebaseArgs {
     /// parents.
     #[arg(long, conflicts_with = "revisions")]
     skip_emptied: bool,
+
+    /// If true, when rebasing would produce an empty commit, the commit and
+    /// all of its descendants are abandoned. It will not be abandoned if it
+    /// was already empty before the rebase. Will never skip merge commits with
+    /// multiple non-empty parents.
+    #[arg(long, conflicts_with_all = ["revisions", "skip_emptied"])]
+    abandon_emptied: bool,
+
+    /// Don't warn if the rebased commits are divergent
+    ///
+    /// A rebased commit is divergent if another visible commit has the same
+    /// change ID, e.g. when a hidden predecessor of a rewritten commit is
+    /// rebased.
+    #[arg(long)]
+    keep_divergent: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum RebaseMerges {
+    /// Keep the merge structure of the rebased commits
+    Preserve,
+    /// Rebase the commits as a linear sequence in topological order
+    Flatten,
 }
 
 #[instrument(skip_all)]
@@ -221,14 +313,33 @@ pub(crate) fn cmd_rebase(
         ));
     }
 
+    if args.rebase_merges == RebaseMerges::Flatten {
+        if !args.insert_after.is_empty() || !args.insert_before.is_empty() {
+            return Err(cli_error(
+                "--rebase-merges=flatten cannot be used with --insert-after or --insert-before",
+            ));
+        }
+        if args.skip_emptied {
+            return Err(cli_error(
+                "--rebase-merges=flatten cannot be used with --skip-emptied",
+            ));
+        }
+        if args.abandon_emptied {
+            return Err(cli_error(
+                "--rebase-merges=flatten cannot be used with --abandon-emptied",
+            ));
+        }
+    }
+
     let rebase_options = RebaseOptions {
-        empty: match args.skip_emptied {
+        empty: match args.skip_emptied || args.abandon_emptied {
             true => EmptyBehaviour::AbandonNewlyEmpty,
             false => EmptyBehaviour::Keep,
         },
         simplify_ancestor_merge: false,
     };
     let mut workspace_command = command.workspace_helper(ui)?;
+    let old_repo = workspace_command.repo().clone();
     if !args.revisions.is_empty() {
         assert_eq!(
             // In principle, `-r --skip-empty` could mean to abandon the `-r`
@@ -249,65 +360,136 @@ pub(crate) fn cmd_rebase(
             .parse_union_revsets(&args.revisions)?
             .evaluate_to_commits()?
             .try_collect()?; // in reverse topological order
-        if !args.insert_after.is_empty() && !args.insert_before.is_empty() {
-            let after_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_after)?;
-            let before_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_before)?;
-            rebase_revisions_after_before(
+        let move_options = MoveCommitsOptions {
+            retain_external_parents: args.retain_merge_parents_outside_set,
+        };
+        if !args.insert_after.is_empty() || !args.insert_before.is_empty() {
+            let (after_commits, before_commits) =
+                resolve_insert_after_before(&workspace_command, args)?;
+            rebase_revisions_after_or_before(
                 ui,
                 command.settings(),
                 &mut workspace_command,
                 &after_commits,
                 &before_commits,
                 &target_commits,
+                &move_options,
             )?;
-        } else if !args.insert_after.is_empty() {
-            let after_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_after)?;
-            rebase_revisions_after(
-                ui,
-                command.settings(),
-                &mut workspace_command,
-                &after_commits,
-                &target_commits,
-            )?;
-        } else if !args.insert_before.is_empty() {
-            let before_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_before)?;
-            rebase_revisions_before(
+        } else {
+            let new_parents = workspace_command
+                .resolve_some_revsets_default_single(&args.destination)?
+                .into_iter()
+                .collect_vec();
+            rebase_revisions(
                 ui,
                 command.settings(),
                 &mut workspace_command,
-                &before_commits,
+                &new_parents,
                 &target_commits,
+                args.rebase_merges,
+                &move_options,
             )?;
+        }
+    } else if args.interactive {
+        let source_commits = workspace_command.resolve_some_revsets_default_single(&args.source)?;
+        let [source_commit] = source_commits
+            .into_iter()
+            .collect_vec()
+            .try_into()
+            .map_err(|_| user_error("--interactive requires a single source revision"))?;
+        let new_parents = if args.destination.is_empty() {
+            source_commit.parents().try_collect()?
         } else {
-            let new_parents = workspace_command
+            workspace_command
                 .resolve_some_revsets_default_single(&args.destination)?
                 .into_iter()
-                .collect_vec();
+                .collect_vec()
+        };
+        rebase_interactive(
+            ui,
+            command.settings(),
+            &mut workspace_command,
+            &new_parents,
+            &source_commit,
+        )?;
+    } else if !args.source.is_empty() {
+        let destination_commits =
+            workspace_command.resolve_some_revsets_default_single(&args.destination)?;
+        let source_commits = resolve_source_commits(&workspace_command, &args.source)?;
+        let new_parents = if args.onto_merge_base {
+            let source_ids = source_commits.iter().ids().cloned().collect_vec();
+            let destination_ids = destination_commits.iter().ids().cloned().collect_vec();
+            RevsetExpression::commits(source_ids)
+                .ancestors()
+                .intersection(&RevsetExpression::commits(destination_ids).ancestors())
+                .heads()
+                .evaluate_programmatic(workspace_command.repo().as_ref())?
+                .iter()
+                .commits(workspace_command.repo().store())
+                .try_collect()?
+        } else {
+            destination_commits.into_iter().collect_vec()
+        };
+        if args.rebase_merges == RebaseMerges::Flatten {
+            let target_commits: Vec<_> =
+                RevsetExpression::commits(source_commits.iter().ids().cloned().collect_vec())
+                    .descendants()
+                    .evaluate_programmatic(workspace_command.repo().as_ref())?
+                    .iter()
+                    .commits(workspace_command.repo().store())
+                    .try_collect()?; // in reverse topological order
             rebase_revisions(
                 ui,
                 command.settings(),
                 &mut workspace_command,
                 &new_parents,
                 &target_commits,
+                args.rebase_merges,
+                &MoveCommitsOptions::default(),
+            )?;
+        } else {
+            rebase_descendants_transaction(
+                ui,
+                command.settings(),
+                &mut workspace_command,
+                new_parents,
+                &source_commits,
+                rebase_options,
+                args.abandon_emptied,
             )?;
         }
-    } else if !args.source.is_empty() {
-        let new_parents = workspace_command
-            .resolve_some_revsets_default_single(&args.destination)?
-            .into_iter()
-            .collect_vec();
-        let source_commits = workspace_command.resolve_some_revsets_default_single(&args.source)?;
-        rebase_descendants_transaction(
+    } else if !args.insert_after.is_empty() || !args.insert_before.is_empty() {
+        let branch_commits = if args.branch.is_empty() {
+            IndexSet::from([workspace_command.resolve_single_rev(&RevisionArg::AT)?])
+        } else {
+            workspace_command.resolve_some_revsets_default_single(&args.branch)?
+        };
+        let (after_commits, before_commits) =
+            resolve_insert_after_before(&workspace_command, args)?;
+        // The branch is computed relative to the new parents as if they were
+        // passed to `-d`.
+        let new_parent_ids = if after_commits.is_empty() {
+            before_commits
+                .iter()
+                .flat_map(|commit| commit.parent_ids().iter().cloned())
+                .collect_vec()
+        } else {
+            after_commits.iter().ids().cloned().collect_vec()
+        };
+        let target_commits: Vec<_> = branch_roots_expression(&new_parent_ids, &branch_commits)
+            .descendants()
+            .evaluate_programmatic(workspace_command.repo().as_ref())?
+            .iter()
+            .commits(workspace_command.repo().store())
+            .try_collect()?; // in reverse topological order
+        rebase_revisions_after_or_before(
             ui,
             command.settings(),
             &mut workspace_command,
-            new_parents,
-            &source_commits,
-            rebase_options,
+            &after_commits,
+            &before_commits,
+            &target_commits,
+            &MoveCommitsOptions::default(),
         )?;
     } else {
         let new_parents = workspace_command
@@ -319,18 +501,115 @@ pub(crate) fn cmd_rebase(
         } else {
             workspace_command.resolve_some_revsets_default_single(&args.branch)?
         };
-        rebase_branch(
-            ui,
-            command.settings(),
-            &mut workspace_command,
-            new_parents,
-            &branch_commits,
-            rebase_options,
-        )?;
+        if args.rebase_merges == RebaseMerges::Flatten {
+            let target_commits: Vec<_> = branch_roots_expression(
+                &new_parents.iter().ids().cloned().collect_vec(),
+                &branch_commits,
+            )
+            .descendants()
+            .evaluate_programmatic(workspace_command.repo().as_ref())?
+            .iter()
+            .commits(workspace_command.repo().store())
+            .try_collect()?; // in reverse topological order
+            rebase_revisions(
+                ui,
+                command.settings(),
+                &mut workspace_command,
+                &new_parents,
+                &target_commits,
+                args.rebase_merges,
+                &MoveCommitsOptions::default(),
+            )?;
+        } else {
+            rebase_branch(
+                ui,
+                command.settings(),
+                &mut workspace_command,
+                new_parents,
+                &branch_commits,
+                rebase_options,
+                args.abandon_emptied,
+            )?;
+        }
+    }
+    if !args.keep_divergent {
+        warn_divergent_rebased_commits(ui, &workspace_command, &old_repo)?;
     }
     Ok(())
 }
 
+/// Warns about the commits created by the rebase whose change ID is shared
+/// with another visible commit.
+fn warn_divergent_rebased_commits(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    old_repo: &ReadonlyRepo,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let old_heads = RevsetExpression::commits(old_repo.view().heads().iter().cloned().collect());
+    let divergent_commits: Vec<_> = old_heads
+        .range(&RevsetExpression::visible_heads())
+        .evaluate_programmatic(repo.as_ref())?
+        .iter()
+        .commits(repo.store())
+        .filter_ok(|commit| {
+            repo.resolve_change_id(commit.change_id())
+                .map_or(false, |commit_ids| commit_ids.len() > 1)
+        })
+        .try_collect()?;
+    if divergent_commits.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        ui.warning_default(),
+        "The following rebased commits are divergent:"
+    )?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        for commit in &divergent_commits {
+            write!(formatter, "  ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    writeln!(
+        ui.hint_default(),
+        "Abandon the unwanted commits, or use --keep-divergent to suppress this warning."
+    )?;
+    Ok(())
+}
+
+/// Resolves the `--insert-after` and `--insert-before` revisions. Either of
+/// them may be empty if not specified.
+fn resolve_insert_after_before(
+    workspace_command: &WorkspaceCommandHelper,
+    args: &RebaseArgs,
+) -> Result<(IndexSet<Commit>, IndexSet<Commit>), CommandError> {
+    let resolve = |revision_args: &[RevisionArg]| {
+        if revision_args.is_empty() {
+            Ok(IndexSet::new())
+        } else {
+            workspace_command.resolve_some_revsets_default_single(revision_args)
+        }
+    };
+    Ok((resolve(&args.insert_after)?, resolve(&args.insert_before)?))
+}
+
+/// Resolves the `-s` arguments to the union of the specified revisions, in
+/// topological order (ancestors first).
+fn resolve_source_commits(
+    workspace_command: &WorkspaceCommandHelper,
+    revision_args: &[RevisionArg],
+) -> Result<IndexSet<Commit>, CommandError> {
+    let commits: Vec<_> = workspace_command
+        .parse_union_revsets(revision_args)?
+        .evaluate_to_commits()?
+        .try_collect()?; // in reverse topological order
+    if commits.is_empty() {
+        return Err(user_error("Empty revision set"));
+    }
+    Ok(commits.into_iter().rev().collect())
+}
+
 fn rebase_branch(
     ui: &mut Ui,
     settings: &UserSettings,
@@ -338,18 +617,13 @@ fn rebase_branch(
     new_parents: Vec<Commit>,
     branch_commits: &IndexSet<Commit>,
     rebase_options: RebaseOptions,
+    abandon_emptied_descendants: bool,
 ) -> Result<(), CommandError> {
     let parent_ids = new_parents
         .iter()
         .map(|commit| commit.id().clone())
         .collect_vec();
-    let branch_commit_ids = branch_commits
-        .iter()
-        .map(|commit| commit.id().clone())
-        .collect_vec();
-    let roots_expression = RevsetExpression::commits(parent_ids)
-        .range(&RevsetExpression::commits(branch_commit_ids))
-        .roots();
+    let roots_expression = branch_roots_expression(&parent_ids, branch_commits);
     let root_commits: IndexSet<_> = roots_expression
         .evaluate_programmatic(workspace_command.repo().as_ref())
         .unwrap()
@@ -363,17 +637,38 @@ fn rebase_branch(
         new_parents,
         &root_commits,
         rebase_options,
+        abandon_emptied_descendants,
     )
 }
 
+/// Roots of the commits in `branch_commits` that aren't ancestors of
+/// `new_parent_ids`.
+fn branch_roots_expression(
+    new_parent_ids: &[CommitId],
+    branch_commits: &IndexSet<Commit>,
+) -> Rc<RevsetExpression> {
+    let branch_commit_ids = branch_commits
+        .iter()
+        .map(|commit| commit.id().clone())
+        .collect_vec();
+    RevsetExpression::commits(new_parent_ids.to_vec())
+        .range(&RevsetExpression::commits(branch_commit_ids))
+        .roots()
+}
+
 /// Rebases `old_commits` onto `new_parents`.
+///
+/// If `abandon_emptied_descendants` is true, the descendants of the commits
+/// abandoned by `rebase_options` are abandoned as well.
 fn rebase_descendants(
     tx: &mut WorkspaceCommandTransaction,
     settings: &UserSettings,
     new_parents: Vec<Commit>,
     old_commits: &[impl Borrow<Commit>],
     rebase_options: RebaseOptions,
+    abandon_emptied_descendants: bool,
 ) -> Result<usize, CommandError> {
+    let mut abandoned_ids = HashSet::new();
     for old_commit in old_commits.iter() {
         let rewriter = CommitRewriter::new(
             tx.mut_repo(),
@@ -383,11 +678,46 @@ fn rebase_descendants(
                 .map(|parent| parent.id().clone())
                 .collect(),
         );
-        rebase_commit_with_options(settings, rewriter, &rebase_options)?;
+        let rebased_commit = rebase_commit_with_options(settings, rewriter, &rebase_options)?;
+        if let RebasedCommit::Abandoned { .. } = rebased_commit {
+            abandoned_ids.insert(old_commit.borrow().id().clone());
+        }
+    }
+    if !abandon_emptied_descendants {
+        let num_rebased = old_commits.len()
+            + tx.mut_repo()
+                .rebase_descendants_with_options(settings, rebase_options)?;
+        return Ok(num_rebased);
     }
-    let num_rebased = old_commits.len()
-        + tx.mut_repo()
-            .rebase_descendants_with_options(settings, rebase_options)?;
+
+    let mut num_rebased = old_commits.len();
+    let root_ids = old_commits
+        .iter()
+        .map(|commit| commit.borrow().id().clone())
+        .collect_vec();
+    tx.mut_repo()
+        .transform_descendants(settings, root_ids, |rewriter| {
+            let old_commit_id = rewriter.old_commit().id().clone();
+            if rewriter
+                .old_commit()
+                .parent_ids()
+                .iter()
+                .any(|parent_id| abandoned_ids.contains(parent_id))
+            {
+                rewriter.abandon();
+                abandoned_ids.insert(old_commit_id);
+            } else if rewriter.parents_changed() {
+                let rebased_commit =
+                    rebase_commit_with_options(settings, rewriter, &rebase_options)?;
+                if let RebasedCommit::Abandoned { .. } = rebased_commit {
+                    abandoned_ids.insert(old_commit_id);
+                }
+            } else {
+                return Ok(());
+            }
+            num_rebased += 1;
+            Ok(())
+        })?;
     Ok(num_rebased)
 }
 
@@ -398,6 +728,7 @@ fn rebase_descendants_transaction(
     new_parents: Vec<Commit>,
     old_commits: &IndexSet<Commit>,
     rebase_options: RebaseOptions,
+    abandon_emptied_descendants: bool,
 ) -> Result<(), CommandError> {
     workspace_command.check_rewritable(old_commits.iter().ids())?;
     let (skipped_commits, old_commits) = old_commits
@@ -417,8 +748,14 @@ fn rebase_descendants_transaction(
         check_rebase_destinations(workspace_command.repo(), &new_parents, old_commit)?;
     }
     let mut tx = workspace_command.start_transaction();
-    let num_rebased =
-        rebase_descendants(&mut tx, settings, new_parents, &old_commits, rebase_options)?;
+    let num_rebased = rebase_descendants(
+        &mut tx,
+        settings,
+        new_parents,
+        &old_commits,
+        rebase_options,
+        abandon_emptied_descendants,
+    )?;
     writeln!(ui.status(), "Rebased {num_rebased} commits")?;
     let tx_message = if old_commits.len() == 1 {
         format!(
@@ -438,6 +775,8 @@ fn rebase_revisions(
     workspace_command: &mut WorkspaceCommandHelper,
     new_parents: &[Commit],
     target_commits: &[Commit],
+    rebase_merges: RebaseMerges,
+    move_options: &MoveCommitsOptions,
 ) -> Result<(), CommandError> {
     if target_commits.is_empty() {
         return Ok(());
@@ -453,14 +792,69 @@ fn rebase_revisions(
         }
     }
 
-    move_commits_transaction(
-        ui,
-        settings,
-        workspace_command,
-        &new_parents.iter().ids().cloned().collect_vec(),
-        &[],
-        target_commits,
-    )
+    match rebase_merges {
+        RebaseMerges::Preserve => move_commits_transaction(
+            ui,
+            settings,
+            workspace_command,
+            &new_parents.iter().ids().cloned().collect_vec(),
+            &[],
+            target_commits,
+            move_options,
+        ),
+        RebaseMerges::Flatten => {
+            for commit in target_commits.iter() {
+                check_rebase_destinations(workspace_command.repo(), new_parents, commit)?;
+            }
+            flatten_commits_transaction(
+                ui,
+                settings,
+                workspace_command,
+                &new_parents.iter().ids().cloned().collect_vec(),
+                target_commits,
+            )
+        }
+    }
+}
+
+/// Inserts `target_commits` after `after_commits` and/or before
+/// `before_commits`.
+fn rebase_revisions_after_or_before(
+    ui: &mut Ui,
+    settings: &UserSettings,
+    workspace_command: &mut WorkspaceCommandHelper,
+    after_commits: &IndexSet<Commit>,
+    before_commits: &IndexSet<Commit>,
+    target_commits: &[Commit],
+    move_options: &MoveCommitsOptions,
+) -> Result<(), CommandError> {
+    match (after_commits.is_empty(), before_commits.is_empty()) {
+        (false, false) => rebase_revisions_after_before(
+            ui,
+            settings,
+            workspace_command,
+            after_commits,
+            before_commits,
+            target_commits,
+            move_options,
+        ),
+        (true, false) => rebase_revisions_before(
+            ui,
+            settings,
+            workspace_command,
+            before_commits,
+            target_commits,
+            move_options,
+        ),
+        (_, true) => rebase_revisions_after(
+            ui,
+            settings,
+            workspace_command,
+            after_commits,
+            target_commits,
+            move_options,
+        ),
+    }
 }
 
 fn rebase_revisions_after(
@@ -469,6 +863,7 @@ fn rebase_revisions_after(
     workspace_command: &mut WorkspaceCommandHelper,
     after_commits: &IndexSet<Commit>,
     target_commits: &[Commit],
+    move_options: &MoveCommitsOptions,
 ) -> Result<(), CommandError> {
     workspace_command.check_rewritable(target_commits.iter().ids())?;
 
@@ -497,6 +892,7 @@ fn rebase_revisions_after(
         &new_parent_ids,
         &new_children,
         target_commits,
+        move_options,
     )
 }
 
@@ -506,6 +902,7 @@ fn rebase_revisions_before(
     workspace_command: &mut WorkspaceCommandHelper,
     before_commits: &IndexSet<Commit>,
     target_commits: &[Commit],
+    move_options: &MoveCommitsOptions,
 ) -> Result<(), CommandError> {
     workspace_command.check_rewritable(target_commits.iter().ids())?;
     let before_commit_ids = before_commits.iter().ids().cloned().collect_vec();
@@ -536,6 +933,7 @@ fn rebase_revisions_before(
         &new_parent_ids,
         &new_children,
         target_commits,
+        move_options,
     )
 }
 
@@ -546,6 +944,7 @@ fn rebase_revisions_after_before(
     after_commits: &IndexSet<Commit>,
     before_commits: &IndexSet<Commit>,
     target_commits: &[Commit],
+    move_options: &MoveCommitsOptions,
 ) -> Result<(), CommandError> {
     workspace_command.check_rewritable(target_commits.iter().ids())?;
     let before_commit_ids = before_commits.iter().ids().cloned().collect_vec();
@@ -560,6 +959,11 @@ fn rebase_revisions_after_before(
         &new_children_expression,
         &new_parents_expression,
     )?;
+    ensure_children_descend_from_parents(
+        workspace_command.repo().as_ref(),
+        &new_children_expression,
+        &new_parents_expression,
+    )?;
 
     let new_parent_ids = after_commit_ids;
     let new_children = before_commits.iter().cloned().collect_vec();
@@ -571,6 +975,7 @@ fn rebase_revisions_after_before(
         &new_parent_ids,
         &new_children,
         target_commits,
+        move_options,
     )
 }
 
@@ -582,6 +987,7 @@ fn move_commits_transaction(
     new_parent_ids: &[CommitId],
     new_children: &[Commit],
     target_commits: &[Commit],
+    move_options: &MoveCommitsOptions,
 ) -> Result<(), CommandError> {
     if target_commits.is_empty() {
         return Ok(());
@@ -608,8 +1014,233 @@ fn move_commits_transaction(
         new_parent_ids,
         new_children,
         target_commits,
+        move_options,
     )?;
 
+    print_move_commits_stats(
+        ui,
+        &MoveCommitsStats {
+            num_rebased_targets,
+            num_rebased_descendants,
+            num_skipped_rebases,
+        },
+    )?;
+    tx.finish(ui, tx_description)
+}
+
+/// Wraps `flatten_commits` in a transaction.
+fn flatten_commits_transaction(
+    ui: &mut Ui,
+    settings: &UserSettings,
+    workspace_command: &mut WorkspaceCommandHelper,
+    new_parent_ids: &[CommitId],
+    target_commits: &[Commit],
+) -> Result<(), CommandError> {
+    let mut tx = workspace_command.start_transaction();
+    let tx_description = if target_commits.len() == 1 {
+        format!("rebase commit {}", target_commits[0].id().hex())
+    } else {
+        format!(
+            "rebase commit {} and {} more as a linear sequence",
+            target_commits[0].id().hex(),
+            target_commits.len() - 1
+        )
+    };
+    let stats = flatten_commits(settings, tx.mut_repo(), new_parent_ids, target_commits)?;
+    print_move_commits_stats(ui, &stats)?;
+    tx.finish(ui, tx_description)
+}
+
+/// Rebases `target_commits` onto `new_parent_ids` as a linear sequence in
+/// topological order, dropping any merges between them. Descendants outside
+/// the target set are rebased onto the nearest ancestors of their parents
+/// outside the set, as `move_commits` does.
+///
+/// `target_commits` should be in reverse topological order.
+fn flatten_commits(
+    settings: &UserSettings,
+    mut_repo: &mut MutableRepo,
+    new_parent_ids: &[CommitId],
+    target_commits: &[Commit],
+) -> Result<MoveCommitsStats, CommandError> {
+    let target_commit_ids: HashSet<_> = target_commits.iter().ids().cloned().collect();
+
+    // Each commit in the target set is replaced by its ancestors outside the set
+    // when it is a parent of a commit outside the set.
+    let mut target_commits_external_parents: HashMap<CommitId, IndexSet<CommitId>> = HashMap::new();
+    for commit in target_commits.iter().rev() {
+        let mut new_parents = IndexSet::new();
+        for old_parent in commit.parent_ids() {
+            if let Some(parents) = target_commits_external_parents.get(old_parent) {
+                new_parents.extend(parents.iter().cloned());
+            } else {
+                new_parents.insert(old_parent.clone());
+            }
+        }
+        target_commits_external_parents.insert(commit.id().clone(), new_parents);
+    }
+
+    // Stack the target commits on top of each other in topological order.
+    let mut new_parents_map: HashMap<CommitId, Vec<CommitId>> = HashMap::new();
+    let mut parent_ids = new_parent_ids.to_vec();
+    for commit in target_commits.iter().rev() {
+        new_parents_map.insert(commit.id().clone(), parent_ids);
+        parent_ids = vec![commit.id().clone()];
+    }
+
+    let to_visit: Vec<_> = RevsetExpression::commits(target_commit_ids.iter().cloned().collect())
+        .descendants()
+        .evaluate_programmatic(mut_repo)?
+        .iter()
+        .commits(mut_repo.store())
+        .try_collect()?;
+    for commit in &to_visit {
+        if !target_commit_ids.contains(commit.id()) {
+            let new_parents = commit
+                .parent_ids()
+                .iter()
+                .flat_map(|parent_id| {
+                    if let Some(parents) = target_commits_external_parents.get(parent_id) {
+                        parents.iter().cloned().collect_vec()
+                    } else {
+                        vec![parent_id.clone()]
+                    }
+                })
+                .unique()
+                .collect();
+            new_parents_map.insert(commit.id().clone(), new_parents);
+        }
+    }
+    let to_visit_ids = to_visit.iter().ids().cloned().collect_vec();
+    let to_visit_commits: HashMap<_, _> = to_visit
+        .into_iter()
+        .map(|commit| (commit.id().clone(), commit))
+        .collect();
+
+    // Visit each commit after its new parents.
+    let mut to_visit = dag_walk::topo_order_reverse(
+        to_visit_ids,
+        |commit_id| commit_id.clone(),
+        |commit_id| {
+            new_parents_map[commit_id]
+                .iter()
+                .filter(|id| to_visit_commits.contains_key(id))
+                .cloned()
+                .collect_vec()
+        },
+    );
+
+    let mut num_rebased_targets = 0;
+    let mut num_rebased_descendants = 0;
+    let mut num_skipped_rebases = 0;
+    while let Some(old_commit_id) = to_visit.pop() {
+        let old_commit = &to_visit_commits[&old_commit_id];
+        let new_parent_ids = mut_repo.new_parents(new_parents_map[&old_commit_id].clone());
+        let rewriter = CommitRewriter::new(mut_repo, old_commit.clone(), new_parent_ids);
+        if rewriter.parents_changed() {
+            rewriter.rebase(settings)?.write()?;
+            if target_commit_ids.contains(&old_commit_id) {
+                num_rebased_targets += 1;
+            } else {
+                num_rebased_descendants += 1;
+            }
+        } else {
+            num_skipped_rebases += 1;
+        }
+    }
+    mut_repo.update_rewritten_references(settings)?;
+
+    Ok(MoveCommitsStats {
+        num_rebased_targets,
+        num_rebased_descendants,
+        num_skipped_rebases,
+    })
+}
+
+/// Lets the user reorder or drop `source_commit` and its descendants by
+/// editing a todo list, and stacks the picked commits onto `new_parents` in
+/// the edited order.
+fn rebase_interactive(
+    ui: &mut Ui,
+    settings: &UserSettings,
+    workspace_command: &mut WorkspaceCommandHelper,
+    new_parents: &[Commit],
+    source_commit: &Commit,
+) -> Result<(), CommandError> {
+    check_rebase_destinations(workspace_command.repo(), new_parents, source_commit)?;
+    let stack: Vec<Commit> = RevsetExpression::commit(source_commit.id().clone())
+        .descendants()
+        .evaluate_programmatic(workspace_command.repo().as_ref())?
+        .iter()
+        .commits(workspace_command.repo().store())
+        .try_collect()?; // in reverse topological order
+    if stack
+        .iter()
+        .tuple_windows()
+        .any(|(child, parent)| child.parent_ids() != [parent.id().clone()])
+    {
+        return Err(user_error(format!(
+            "Cannot rebase interactively: {}:: is not a linear stack of commits",
+            short_commit_hash(source_commit.id())
+        )));
+    }
+    workspace_command.check_rewritable(stack.iter().ids())?;
+
+    let stack = stack.into_iter().rev().collect_vec();
+    let actions = edit_rebase_todo(workspace_command.repo(), &stack, settings)?;
+    let commits_by_id: HashMap<_, _> = stack.iter().map(|commit| (commit.id(), commit)).collect();
+
+    let mut tx = workspace_command.start_transaction();
+    let mut parent_ids = new_parents.iter().ids().cloned().collect_vec();
+    let mut num_rebased_targets = 0;
+    let mut num_skipped_rebases = 0;
+    let mut num_abandoned = 0;
+    for (action, commit_id) in actions {
+        let commit = commits_by_id[&commit_id];
+        match action {
+            TodoAction::Pick => {
+                let rewriter = CommitRewriter::new(tx.mut_repo(), commit.clone(), parent_ids);
+                if rewriter.parents_changed() {
+                    let new_commit = rewriter.rebase(settings)?.write()?;
+                    parent_ids = vec![new_commit.id().clone()];
+                    num_rebased_targets += 1;
+                } else {
+                    parent_ids = vec![commit_id];
+                    num_skipped_rebases += 1;
+                }
+            }
+            TodoAction::Drop => {
+                tx.mut_repo().record_abandoned_commit(commit_id);
+                num_abandoned += 1;
+            }
+        }
+    }
+    tx.mut_repo().update_rewritten_references(settings)?;
+
+    let stats = MoveCommitsStats {
+        num_rebased_targets,
+        num_rebased_descendants: 0,
+        num_skipped_rebases,
+    };
+    print_move_commits_stats(ui, &stats)?;
+    if num_abandoned > 0 {
+        writeln!(ui.status(), "Abandoned {num_abandoned} commits")?;
+    }
+    tx.finish(
+        ui,
+        format!(
+            "rebase commit {} and descendants interactively",
+            source_commit.id().hex()
+        ),
+    )
+}
+
+fn print_move_commits_stats(ui: &Ui, stats: &MoveCommitsStats) -> io::Result<()> {
+    let &MoveCommitsStats {
+        num_rebased_targets,
+        num_rebased_descendants,
+        num_skipped_rebases,
+    } = stats;
     if let Some(mut fmt) = ui.status_formatter() {
         if num_skipped_rebases > 0 {
             writeln!(
@@ -627,8 +1258,7 @@ fn move_commits_transaction(
             writeln!(fmt, "Rebased {num_rebased_descendants} descendant commits")?;
         }
     }
-
-    tx.finish(ui, tx_description)
+    Ok(())
 }
 
 /// Ensure that there is no possible cycle between the potential children and
@@ -653,6 +1283,26 @@ fn ensure_no_commit_loop(
     Ok(())
 }
 
+/// Ensure that every potential child of the rebased commits is a descendant of
+/// at least one of the potential parents, so that the rebased commits fill an
+/// existing slot in the graph instead of creating an unexpected merge.
+fn ensure_children_descend_from_parents(
+    repo: &ReadonlyRepo,
+    children_expression: &Rc<RevsetExpression>,
+    parents_expression: &Rc<RevsetExpression>,
+) -> Result<(), CommandError> {
+    let unreachable_children = children_expression
+        .minus(&parents_expression.descendants())
+        .evaluate_programmatic(repo)?;
+    if unreachable_children.iter().next().is_some() {
+        return Err(user_error_with_hint(
+            "The --insert-before commits must be descendants of the --insert-after commits",
+            "Use --destination to rebase onto unrelated commits.",
+        ));
+    }
+    Ok(())
+}
+
 fn check_rebase_destinations(
     repo: &Arc<ReadonlyRepo>,
     new_parents: &[Commit],
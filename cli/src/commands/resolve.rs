@@ -12,17 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::io::Read as _;
 use std::io::Write;
+use std::time::Duration;
 
 use itertools::Itertools;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::conflicts::materialize_tree_value;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::MaterializedTreeValue;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use pollster::FutureExt;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
+use crate::cli_util::shell_escape;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::formatter::Formatter;
+use crate::merge_tools;
+use crate::merge_tools::ConflictResolveError;
+use crate::merge_tools::ExternalToolError;
+use crate::merge_tools::MergeEditor;
 use crate::ui::Ui;
 
 /// Resolve a conflicted file with an external merge tool
@@ -42,6 +65,10 @@ use crate::ui::Ui;
 //     simplify the present one.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct ResolveArgs {
+    /// The revision to resolve conflicts in
+    ///
+    /// The revision is rewritten in place, and its descendants are rebased
+    /// onto it. The working copy doesn't have to be at the revision.
     #[arg(long, short, default_value = "@")]
     revision: RevisionArg,
     /// Instead of resolving one conflict, list all the conflicts
@@ -49,9 +76,167 @@ pub(crate) struct ResolveArgs {
     // `diff --summary`, but should be more verbose.
     #[arg(long, short)]
     list: bool,
+    /// With `--list`, only print the total number of sides of all the listed
+    /// conflicts
+    #[arg(long, requires = "list")]
+    total_sides: bool,
+    /// With `--list`, also list the paths that are conflicted in the parents
+    /// of the revision but resolved in the revision itself
+    #[arg(long, requires = "list", conflicts_with = "total_sides")]
+    include_resolved: bool,
+    /// Instead of resolving one conflict, print the number of conflicted paths
+    /// grouped by their number of sides
+    ///
+    /// Unlike the other modes, this succeeds even if there are no conflicts.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "list",
+            "print",
+            "tool",
+            "tool_for",
+            "tool_timeout",
+            "accept_all_trivial",
+            "accept_ours",
+            "accept_theirs",
+            "nth",
+        ]
+    )]
+    stat: bool,
+    /// Instead of resolving one conflict, print the conflicts with conflict
+    /// markers as they would appear in the working copy
+    ///
+    /// If several files are conflicted, each is preceded by a header with its
+    /// path.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "list",
+            "tool",
+            "tool_for",
+            "tool_timeout",
+            "accept_ours",
+            "accept_theirs",
+        ]
+    )]
+    print: bool,
     /// Specify 3-way merge tool to be used
     #[arg(long, conflicts_with = "list", value_name = "NAME")]
     tool: Option<String>,
+    /// Specify 3-way merge tool to be used for files with the given extension
+    ///
+    /// Overrides the `ui.merge-editor-by-extension` config for this run. Files
+    /// with other extensions use the configured or default merge tool.
+    #[arg(
+        long,
+        conflicts_with_all = ["list", "tool"],
+        value_name = "EXT=NAME",
+        value_parser = parse_tool_for_arg,
+    )]
+    tool_for: Vec<(String, String)>,
+    /// Kill the external merge tool if it doesn't finish within the given
+    /// number of seconds, and fail the resolution
+    ///
+    /// Overrides the `ui.merge-tool-timeout` config for this run. The file is
+    /// left unresolved.
+    #[arg(long, conflicts_with = "list", value_name = "SECS")]
+    tool_timeout: Option<u64>,
+    /// Style of the conflict markers written for merge tools that edit them
+    ///
+    /// Overrides the `ui.merge-tool-marker-style` config for this run. The
+    /// markers in conflicted files in the working copy always use the `jj`
+    /// style.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "list",
+            "stat",
+            "print",
+            "accept_all_trivial",
+            "accept_ours",
+            "accept_theirs",
+        ],
+        value_name = "STYLE"
+    )]
+    marker_style: Option<MarkerStyle>,
+    /// Resolve all conflicts where the file contents of every side but one are
+    /// unchanged from the base, by taking the changed side
+    ///
+    /// Other conflicts are left untouched.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "list",
+            "print",
+            "tool",
+            "tool_for",
+            "tool_timeout",
+            "accept_ours",
+            "accept_theirs",
+        ]
+    )]
+    accept_all_trivial: bool,
+    /// Resolve the conflict by taking the first side ("ours") as is, without
+    /// running a merge tool
+    #[arg(
+        long,
+        conflicts_with_all = ["list", "tool", "tool_for", "tool_timeout", "accept_theirs"]
+    )]
+    accept_ours: bool,
+    /// Resolve the conflict by taking the second side ("theirs") as is,
+    /// without running a merge tool
+    #[arg(long, conflicts_with_all = ["list", "tool", "tool_for", "tool_timeout"])]
+    accept_theirs: bool,
+    /// Resolve the Nth conflict (1-based) in the sorted list of conflicts
+    /// instead of the first one
+    ///
+    /// The order is the same as in `--list`, after restricting to the given
+    /// paths.
+    #[arg(
+        long,
+        conflicts_with_all = ["list", "print", "accept_all_trivial"],
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+    )]
+    nth: Option<u32>,
+    /// Resolve every conflict in turn with the merge tool instead of the
+    /// first one
+    ///
+    /// If the merge tool exits with a non-zero status, or leaves the output
+    /// unchanged or empty, the conflict is skipped and left unresolved. The
+    /// resolved and skipped paths are reported at the end.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "list",
+            "print",
+            "accept_all_trivial",
+            "accept_ours",
+            "accept_theirs",
+            "nth",
+        ]
+    )]
+    all: bool,
+    /// Instead of running the merge tool, print the command line it would be
+    /// run with
+    ///
+    /// The input files referenced by the command line are written to a
+    /// temporary directory, which is left in place so the command can be run
+    /// manually.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "list",
+            "stat",
+            "print",
+            "tool_timeout",
+            "accept_all_trivial",
+            "accept_ours",
+            "accept_theirs",
+            "all",
+        ]
+    )]
+    print_command: bool,
     /// Restrict to these paths when searching for a conflict to resolve. We
     /// will attempt to resolve the first conflict we can find. You can use
     /// the `--list` argument to find paths to use here.
@@ -60,6 +245,16 @@ pub(crate) struct ResolveArgs {
     paths: Vec<String>,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum MarkerStyle {
+    /// Snapshot of one side and diffs from the base to the other sides
+    Jj,
+    /// Both sides and the base, as written by `git merge --conflict=diff3`
+    Diff3,
+    /// Both sides only, as written by `git merge --conflict=merge`
+    TwoWay,
+}
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_resolve(
     ui: &mut Ui,
@@ -76,31 +271,105 @@ pub(crate) fn cmd_resolve(
         .conflicts()
         .filter(|path| matcher.matches(&path.0))
         .collect_vec();
-    if conflicts.is_empty() {
+    if args.stat {
+        return print_conflict_stats(ui, &conflicts);
+    }
+    let resolved_paths = if args.include_resolved {
+        let conflicted_paths: HashSet<_> = conflicts.iter().map(|(path, _)| path).collect();
+        commit
+            .parent_tree(workspace_command.repo().as_ref())?
+            .conflicts()
+            .map(|(path, _)| path)
+            .filter(|path| matcher.matches(path) && !conflicted_paths.contains(path))
+            .collect_vec()
+    } else {
+        vec![]
+    };
+    if conflicts.is_empty() && resolved_paths.is_empty() {
         return Err(cli_error(if args.paths.is_empty() {
             "No conflicts found at this revision"
         } else {
             "No conflicts found at the given path(s)"
         }));
     }
+    if args.total_sides {
+        let total_sides: usize = conflicts
+            .iter()
+            .map(|(_, conflict)| conflict.clone().simplify().num_sides())
+            .sum();
+        writeln!(ui.stdout(), "{total_sides}")?;
+        return Ok(());
+    }
     if args.list {
-        return print_conflicted_paths(
+        let mut formatter = ui.stdout_formatter();
+        print_conflicted_paths(&conflicts, formatter.as_mut(), &workspace_command)?;
+        return print_resolved_paths(&resolved_paths, formatter.as_mut(), &workspace_command);
+    };
+    if args.print {
+        return print_materialized_conflicts(ui, &workspace_command, conflicts);
+    }
+    if args.accept_all_trivial {
+        return resolve_trivial_conflicts(ui, command, &mut workspace_command, &commit, &conflicts);
+    }
+    if args.all {
+        return resolve_all_conflicts(
+            ui,
+            command,
+            args,
+            &mut workspace_command,
+            &commit,
             &conflicts,
-            ui.stdout_formatter().as_mut(),
-            &workspace_command,
         );
-    };
+    }
 
-    let (repo_path, _) = conflicts.first().unwrap();
+    let (repo_path, _) = if let Some(nth) = args.nth {
+        conflicts.get(nth as usize - 1).ok_or_else(|| {
+            user_error(format!(
+                "Can't resolve conflict #{nth}; there are only {} conflicts",
+                conflicts.len()
+            ))
+        })?
+    } else {
+        conflicts.first().unwrap()
+    };
+    if args.print_command {
+        let merge_editor = merge_editor_for_path(ui, command, args, &workspace_command, repo_path)?;
+        let Some(command_line) = merge_editor.command_for_file(&tree, repo_path)? else {
+            return Err(user_error(
+                "The builtin merge tool doesn't have a command line to print",
+            ));
+        };
+        writeln!(
+            ui.stdout(),
+            "{}",
+            command_line.iter().map(|arg| shell_escape(arg)).join(" ")
+        )?;
+        return Ok(());
+    }
     workspace_command.check_rewritable([commit.id()])?;
-    let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
+    let merge_editor = if args.accept_ours || args.accept_theirs {
+        None
+    } else {
+        Some(merge_editor_for_path(
+            ui,
+            command,
+            args,
+            &workspace_command,
+            repo_path,
+        )?)
+    };
     writeln!(
         ui.status(),
         "Resolving conflicts in: {}",
         workspace_command.format_file_path(repo_path)
     )?;
     let mut tx = workspace_command.start_transaction();
-    let new_tree_id = merge_editor.edit_file(&tree, repo_path)?;
+    let new_tree_id = if let Some(merge_editor) = &merge_editor {
+        merge_editor.edit_file(&tree, repo_path)?
+    } else {
+        let side = if args.accept_ours { 0 } else { 1 };
+        merge_tools::resolve_file_with_side(&tree, repo_path, side)?
+    };
     let new_commit = tx
         .mut_repo()
         .rewrite_commit(command.settings(), &commit)
@@ -110,10 +379,85 @@ pub(crate) fn cmd_resolve(
         ui,
         format!("Resolve conflicts in commit {}", commit.id().hex()),
     )?;
+    print_remaining_conflicts(ui, &workspace_command, &new_commit)
+}
+
+/// Runs the merge tool for each of the `conflicts` in turn, skipping the ones
+/// the tool fails to resolve, and rewrites `commit` with the resolutions.
+fn resolve_all_conflicts(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ResolveArgs,
+    workspace_command: &mut WorkspaceCommandHelper,
+    commit: &Commit,
+    conflicts: &[(RepoPathBuf, MergedTreeValue)],
+) -> Result<(), CommandError> {
+    workspace_command.check_rewritable([commit.id()])?;
+    let store = workspace_command.repo().store().clone();
+    let mut tree = commit.tree()?;
+    let mut resolved_paths = vec![];
+    let mut skipped_paths = vec![];
+    for (repo_path, _) in conflicts {
+        let merge_editor = merge_editor_for_path(ui, command, args, workspace_command, repo_path)?;
+        writeln!(
+            ui.status(),
+            "Resolving conflicts in: {}",
+            workspace_command.format_file_path(repo_path)
+        )?;
+        match merge_editor.edit_file(&tree, repo_path) {
+            Ok(new_tree_id) => {
+                tree = store.get_root_tree(&new_tree_id)?;
+                resolved_paths.push(repo_path);
+            }
+            Err(
+                err @ (ConflictResolveError::EmptyOrUnchanged
+                | ConflictResolveError::ExternalTool(ExternalToolError::ToolAborted {
+                    ..
+                })),
+            ) => {
+                writeln!(ui.warning_default(), "Skipping: {err}")?;
+                skipped_paths.push(repo_path);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        for (heading, paths) in [("Resolved", &resolved_paths), ("Skipped", &skipped_paths)] {
+            if paths.is_empty() {
+                continue;
+            }
+            writeln!(formatter, "{heading} conflicts in {} paths:", paths.len())?;
+            for path in paths {
+                writeln!(formatter, "  {}", workspace_command.format_file_path(path))?;
+            }
+        }
+    }
+    if resolved_paths.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let new_commit = tx
+        .mut_repo()
+        .rewrite_commit(command.settings(), commit)
+        .set_tree_id(tree.id())
+        .write()?;
+    tx.finish(
+        ui,
+        format!("Resolve conflicts in commit {}", commit.id().hex()),
+    )?;
+    print_remaining_conflicts(ui, workspace_command, &new_commit)
+}
 
-    // Print conflicts that are still present after resolution if the workspace
-    // working copy is not at the commit. Otherwise, the conflicting paths will
-    // be printed by the `tx.finish()` instead.
+/// Prints conflicts that are still present after resolution if the workspace
+/// working copy is not at the `new_commit`. Otherwise, the conflicting paths
+/// will be printed by the `tx.finish()` instead.
+fn print_remaining_conflicts(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    new_commit: &Commit,
+) -> Result<(), CommandError> {
     if workspace_command.get_wc_commit_id() != Some(new_commit.id()) {
         if let Some(mut formatter) = ui.status_formatter() {
             let new_tree = new_commit.tree()?;
@@ -123,8 +467,221 @@ pub(crate) fn cmd_resolve(
                     formatter,
                     "After this operation, some files at this revision still have conflicts:"
                 )?;
-                print_conflicted_paths(&new_conflicts, formatter.as_mut(), &workspace_command)?;
+                print_conflicted_paths(&new_conflicts, formatter.as_mut(), workspace_command)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_tool_for_arg(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((extension, name)) if !extension.is_empty() && !name.is_empty() => Ok((
+            extension.trim_start_matches('.').to_owned(),
+            name.to_owned(),
+        )),
+        _ => Err(format!("Expected EXT=NAME, got {s:?}")),
+    }
+}
+
+/// Picks the merge tool for `repo_path`. `--tool` takes precedence, then
+/// `--tool-for` and `ui.merge-editor-by-extension` matching the file
+/// extension. `None` means the default merge tool.
+fn merge_tool_name_for_path(
+    command: &CommandHelper,
+    args: &ResolveArgs,
+    repo_path: &RepoPath,
+) -> Result<Option<String>, CommandError> {
+    if let Some(name) = &args.tool {
+        return Ok(Some(name.clone()));
+    }
+    let extension = merge_tools::file_extension(repo_path);
+    let tool_for = args
+        .tool_for
+        .iter()
+        .rev()
+        .find(|(ext, _)| Some(ext.as_str()) == extension);
+    if let Some((_, name)) = tool_for {
+        return Ok(Some(name.clone()));
+    }
+    Ok(merge_tools::merge_tool_name_for_path(
+        command.settings(),
+        repo_path,
+    )?)
+}
+
+/// Creates the merge editor for `repo_path`, applying the command-line
+/// overrides of the configured tool, timeout, and marker style.
+fn merge_editor_for_path(
+    ui: &Ui,
+    command: &CommandHelper,
+    args: &ResolveArgs,
+    workspace_command: &WorkspaceCommandHelper,
+    repo_path: &RepoPath,
+) -> Result<MergeEditor, CommandError> {
+    let tool_name = merge_tool_name_for_path(command, args, repo_path)?;
+    let mut merge_editor = workspace_command.merge_editor(ui, tool_name.as_deref())?;
+    if let Some(timeout) = args.tool_timeout {
+        merge_editor.set_timeout(Some(Duration::from_secs(timeout)));
+    }
+    if let Some(marker_style) = args.marker_style {
+        merge_editor.set_marker_style(match marker_style {
+            MarkerStyle::Jj => ConflictMarkerStyle::Jj,
+            MarkerStyle::Diff3 => ConflictMarkerStyle::Diff3,
+            MarkerStyle::TwoWay => ConflictMarkerStyle::TwoWay,
+        });
+    }
+    Ok(merge_editor)
+}
+
+fn resolve_trivial_conflicts(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    workspace_command: &mut WorkspaceCommandHelper,
+    commit: &Commit,
+    conflicts: &[(RepoPathBuf, MergedTreeValue)],
+) -> Result<(), CommandError> {
+    workspace_command.check_rewritable([commit.id()])?;
+    let tree = commit.tree()?;
+    let mut tree_builder = MergedTreeBuilder::new(tree.id());
+    let mut num_resolved = 0;
+    for (repo_path, conflict) in conflicts {
+        // Tree-level trivial conflicts are already resolved when snapshotting
+        // and merging, so compare the file contents instead. For example, a
+        // file added with the same contents on both sides is still conflicted
+        // if only one side made it executable.
+        let (Some(file_merge), Some(executable_merge)) =
+            (conflict.to_file_merge(), conflict.to_executable_merge())
+        else {
+            continue;
+        };
+        let Some(&executable) = executable_merge.resolve_trivial() else {
+            continue;
+        };
+        let contents = file_merge.simplify().try_map(|file_id| {
+            file_id
+                .as_ref()
+                .map(|id| -> Result<_, CommandError> {
+                    let mut content = vec![];
+                    tree.store()
+                        .read_file(repo_path, id)?
+                        .read_to_end(&mut content)?;
+                    Ok(content)
+                })
+                .transpose()
+        })?;
+        let Some(content) = contents.resolve_trivial() else {
+            continue;
+        };
+        let value = match content {
+            Some(content) => {
+                let id = tree
+                    .store()
+                    .write_file(repo_path, &mut content.as_slice())?;
+                Some(TreeValue::File { id, executable })
+            }
+            None => None,
+        };
+        tree_builder.set_or_remove(repo_path.clone(), Merge::resolved(value));
+        num_resolved += 1;
+    }
+    let num_remaining = conflicts.len() - num_resolved;
+    if num_resolved == 0 {
+        writeln!(
+            ui.status(),
+            "No trivial conflicts found, {num_remaining} conflicts remain"
+        )?;
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let new_tree_id = tree_builder.write_tree(tree.store())?;
+    tx.mut_repo()
+        .rewrite_commit(command.settings(), commit)
+        .set_tree_id(new_tree_id)
+        .write()?;
+    writeln!(
+        ui.status(),
+        "Resolved {num_resolved} trivial conflicts, {num_remaining} conflicts remain"
+    )?;
+    tx.finish(
+        ui,
+        format!("Resolve trivial conflicts in commit {}", commit.id().hex()),
+    )?;
+    Ok(())
+}
+
+/// Prints the paths that were conflicted in the parents, aligned like the
+/// output of `print_conflicted_paths()`.
+fn print_resolved_paths(
+    resolved_paths: &[RepoPathBuf],
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    let formatted_paths = resolved_paths
+        .iter()
+        .map(|path| workspace_command.format_file_path(path))
+        .collect_vec();
+    let max_path_len = formatted_paths.iter().map(|p| p.len()).max().unwrap_or(0);
+    for formatted_path in formatted_paths {
+        write!(
+            formatter,
+            "{:width$} ",
+            formatted_path,
+            width = max_path_len.min(32) + 3
+        )?;
+        writeln!(formatter.labeled("resolved"), "resolved")?;
+    }
+    Ok(())
+}
+
+/// Prints the number of conflicted paths, followed by the number of paths for
+/// each number of sides.
+fn print_conflict_stats(
+    ui: &Ui,
+    conflicts: &[(RepoPathBuf, MergedTreeValue)],
+) -> Result<(), CommandError> {
+    let mut stdout = ui.stdout();
+    writeln!(stdout, "{} conflicted paths", conflicts.len())?;
+    let paths_by_num_sides = conflicts
+        .iter()
+        .map(|(_, conflict)| conflict.clone().simplify().num_sides())
+        .counts();
+    for (num_sides, num_paths) in paths_by_num_sides.into_iter().sorted() {
+        writeln!(stdout, "  {num_sides}-sided: {num_paths}")?;
+    }
+    Ok(())
+}
+
+fn print_materialized_conflicts(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    conflicts: Vec<(RepoPathBuf, MergedTreeValue)>,
+) -> Result<(), CommandError> {
+    let store = workspace_command.repo().store();
+    let print_headers = conflicts.len() > 1;
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for (repo_path, conflict) in conflicts {
+        if print_headers {
+            writeln!(
+                formatter.labeled("header"),
+                "==> {} <==",
+                workspace_command.format_file_path(&repo_path)
+            )?;
+        }
+        match materialize_tree_value(store, &repo_path, conflict).block_on()? {
+            MaterializedTreeValue::Conflict { contents, .. } => {
+                formatter.write_all(&contents)?;
+            }
+            MaterializedTreeValue::AccessDenied(err) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Path '{}' exists but access is denied: {err}",
+                    workspace_command.format_file_path(&repo_path)
+                )?;
             }
+            _ => panic!("conflicts should materialize as conflicts"),
         }
     }
     Ok(())
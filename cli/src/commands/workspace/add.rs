@@ -116,6 +116,7 @@ pub fn cmd_workspace_add(
 
     // Copy sparse patterns from workspace where the command was run
     let mut new_workspace_command = command.for_workable_repo(ui, new_workspace, repo)?;
+    let checkout_options = new_workspace_command.checkout_options()?;
     let (mut locked_ws, _wc_commit) = new_workspace_command.start_working_copy_mutation()?;
     let sparse_patterns = old_workspace_command
         .working_copy()
@@ -123,7 +124,7 @@ pub fn cmd_workspace_add(
         .to_vec();
     locked_ws
         .locked_wc()
-        .set_sparse_patterns(sparse_patterns)
+        .set_sparse_patterns(sparse_patterns, &checkout_options)
         .map_err(|err| internal_error_with_message("Failed to set sparse patterns", err))?;
     let operation_id = locked_ws.locked_wc().old_operation_id().clone();
     locked_ws.finish(operation_id)?;
@@ -525,6 +525,15 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_commit_list(out_property))
         },
     );
+    map.insert(
+        "parent_count",
+        |_language, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|commit| Ok(commit.parent_ids().len().try_into()?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
     map.insert(
         "author",
         |_language, _build_ctx, self_property, function| {
@@ -543,8 +552,12 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
     );
     map.insert("mine", |language, _build_ctx, self_property, function| {
         function.expect_no_arguments()?;
-        let user_email = language.revset_parse_context.user_email().to_owned();
-        let out_property = self_property.map(move |commit| commit.author().email == user_email);
+        let user_emails = language.revset_parse_context.user_emails().to_vec();
+        let out_property = self_property.map(move |commit| {
+            user_emails
+                .iter()
+                .any(|email| commit.author().email == *email)
+        });
         Ok(L::wrap_boolean(out_property))
     });
     map.insert(
@@ -5,6 +5,9 @@ use std::process::Command;
 use std::process::ExitStatus;
 use std::process::Stdio;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use itertools::Itertools;
 use jj_lib::backend::FileId;
@@ -12,6 +15,7 @@ use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeValue;
 use jj_lib::conflicts;
 use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
@@ -20,6 +24,7 @@ use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::repo_path::RepoPath;
 use pollster::FutureExt;
+use tempfile::TempDir;
 use thiserror::Error;
 
 use super::diff_working_copies::check_out_trees;
@@ -144,28 +149,40 @@ pub enum ExternalToolError {
     },
     #[error("Tool exited with {exit_status} (run with --debug to see the exact invocation)")]
     ToolAborted { exit_status: ExitStatus },
+    #[error(
+        "Tool didn't finish within {} seconds and was killed (run with --debug to see the exact \
+         invocation)",
+        timeout.as_secs()
+    )]
+    TimedOut { timeout: Duration },
     #[error("I/O error")]
     Io(#[source] std::io::Error),
 }
 
-pub fn run_mergetool_external(
+/// Merge tool input files written to a temporary directory.
+struct MergeToolFiles {
+    temp_dir: TempDir,
+    initial_output_content: Vec<u8>,
+    /// Paths to the files by role ("base", "left", "right", and "output").
+    paths: HashMap<&'static str, String>,
+}
+
+fn write_merge_tool_files(
     editor: &ExternalMergeTool,
-    file_merge: Merge<Option<FileId>>,
-    content: Merge<jj_lib::files::ContentHunk>,
+    content: &Merge<jj_lib::files::ContentHunk>,
     repo_path: &RepoPath,
-    conflict: MergedTreeValue,
-    tree: &MergedTree,
-) -> Result<MergedTreeId, ConflictResolveError> {
+    marker_style: ConflictMarkerStyle,
+) -> Result<MergeToolFiles, ConflictResolveError> {
     let initial_output_content: Vec<u8> = if editor.merge_tool_edits_conflict_markers {
         let mut materialized_conflict = vec![];
-        materialize_merge_result(&content, &mut materialized_conflict)
+        materialize_merge_result(content, marker_style, &mut materialized_conflict)
             .expect("Writing to an in-memory buffer should never fail");
         materialized_conflict
     } else {
         vec![]
     };
     assert_eq!(content.num_sides(), 2);
-    let files: HashMap<&str, &[u8]> = maplit::hashmap! {
+    let files: HashMap<&'static str, &[u8]> = maplit::hashmap! {
         "base" => content.get_remove(0).unwrap().0.as_slice(),
         "left" => content.get_add(0).unwrap().0.as_slice(),
         "right" => content.get_add(1).unwrap().0.as_slice(),
@@ -180,7 +197,7 @@ pub fn run_mergetool_external(
         // The default case below should never actually trigger, but we support it just in case
         // resolving the root path ever makes sense.
         .unwrap_or_default();
-    let paths: HashMap<&str, _> = files
+    let paths: HashMap<&'static str, _> = files
         .iter()
         .map(|(role, contents)| -> Result<_, ConflictResolveError> {
             let path = temp_dir.path().join(format!("{role}{suffix}"));
@@ -197,16 +214,53 @@ pub fn run_mergetool_external(
             ))
         })
         .try_collect()?;
+    Ok(MergeToolFiles {
+        temp_dir,
+        initial_output_content,
+        paths,
+    })
+}
+
+/// Returns the merge tool command line with the variables substituted. The
+/// input files are written to a temporary directory which is left in place,
+/// so the command can be run manually.
+pub fn mergetool_external_command(
+    editor: &ExternalMergeTool,
+    content: &Merge<jj_lib::files::ContentHunk>,
+    repo_path: &RepoPath,
+    marker_style: ConflictMarkerStyle,
+) -> Result<Vec<String>, ConflictResolveError> {
+    let MergeToolFiles {
+        temp_dir, paths, ..
+    } = write_merge_tool_files(editor, content, repo_path, marker_style)?;
+    // Keep the files instead of deleting them when the directory is dropped.
+    let _ = temp_dir.into_path();
+    let mut command = vec![editor.program.clone()];
+    command.extend(interpolate_variables(&editor.merge_args, &paths));
+    Ok(command)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_mergetool_external(
+    editor: &ExternalMergeTool,
+    file_merge: Merge<Option<FileId>>,
+    content: Merge<jj_lib::files::ContentHunk>,
+    repo_path: &RepoPath,
+    conflict: MergedTreeValue,
+    tree: &MergedTree,
+    timeout: Option<Duration>,
+    marker_style: ConflictMarkerStyle,
+) -> Result<MergedTreeId, ConflictResolveError> {
+    let MergeToolFiles {
+        temp_dir: _temp_dir,
+        initial_output_content,
+        paths,
+    } = write_merge_tool_files(editor, &content, repo_path, marker_style)?;
 
     let mut cmd = Command::new(&editor.program);
     cmd.args(interpolate_variables(&editor.merge_args, &paths));
     tracing::info!(?cmd, "Invoking the external merge tool:");
-    let exit_status = cmd
-        .status()
-        .map_err(|e| ExternalToolError::FailedToExecute {
-            tool_binary: editor.program.clone(),
-            source: e,
-        })?;
+    let exit_status = run_tool_with_timeout(&editor.program, &mut cmd, timeout)?;
     if !exit_status.success() {
         return Err(ConflictResolveError::from(ExternalToolError::ToolAborted {
             exit_status,
@@ -225,6 +279,7 @@ pub fn run_mergetool_external(
             tree.store(),
             repo_path,
             output_file_contents.as_slice(),
+            marker_style,
         )
         .block_on()?
     } else {
@@ -246,6 +301,35 @@ pub fn run_mergetool_external(
     Ok(new_tree)
 }
 
+/// Runs the tool and waits for it to finish. If it doesn't finish within the
+/// `timeout`, the process is killed.
+fn run_tool_with_timeout(
+    tool_binary: &str,
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+) -> Result<ExitStatus, ExternalToolError> {
+    let failed_to_execute = |source| ExternalToolError::FailedToExecute {
+        tool_binary: tool_binary.to_owned(),
+        source,
+    };
+    let Some(timeout) = timeout else {
+        return cmd.status().map_err(failed_to_execute);
+    };
+    let mut child = cmd.spawn().map_err(failed_to_execute)?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(exit_status) = child.try_wait().map_err(ExternalToolError::Io)? {
+            return Ok(exit_status);
+        }
+        if Instant::now() >= deadline {
+            child.kill().map_err(ExternalToolError::Io)?;
+            child.wait().map_err(ExternalToolError::Io)?;
+            return Err(ExternalToolError::TimedOut { timeout });
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
 pub fn edit_diff_external(
     editor: &ExternalMergeTool,
     left_tree: &MergedTree,
@@ -16,14 +16,23 @@ mod builtin;
 mod diff_working_copies;
 mod external;
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use config::ConfigError;
+use jj_lib::backend::FileId;
 use jj_lib::backend::MergedTreeId;
+use jj_lib::backend::TreeValue;
 use jj_lib::conflicts::extract_as_single_hunk;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::settings::ConfigResultExt as _;
@@ -42,7 +51,7 @@ pub use self::external::generate_diff;
 pub use self::external::invoke_external_diff;
 pub use self::external::DiffToolMode;
 pub use self::external::ExternalMergeTool;
-use self::external::ExternalToolError;
+pub use self::external::ExternalToolError;
 use crate::config::CommandNameAndArgs;
 use crate::ui::Ui;
 
@@ -172,6 +181,29 @@ pub fn get_external_tool_config(
     }
 }
 
+/// Returns the extension of the file name of `path`, if any.
+pub fn file_extension(path: &RepoPath) -> Option<&str> {
+    let (_, name) = path.split()?;
+    Path::new(name.as_str()).extension()?.to_str()
+}
+
+/// Looks up the merge tool configured for the extension of `path` in
+/// `[ui.merge-editor-by-extension]`.
+pub fn merge_tool_name_for_path(
+    settings: &UserSettings,
+    path: &RepoPath,
+) -> Result<Option<String>, ConfigError> {
+    let Some(extension) = file_extension(path) else {
+        return Ok(None);
+    };
+    let tools: HashMap<String, String> = settings
+        .config()
+        .get("ui.merge-editor-by-extension")
+        .optional()?
+        .unwrap_or_default();
+    Ok(tools.get(extension).cloned())
+}
+
 /// Configured diff editor.
 #[derive(Clone, Debug)]
 pub struct DiffEditor {
@@ -252,6 +284,8 @@ impl DiffEditor {
 #[derive(Clone, Debug)]
 pub struct MergeEditor {
     tool: MergeTool,
+    timeout: Option<Duration>,
+    marker_style: ConflictMarkerStyle,
 }
 
 impl MergeEditor {
@@ -260,7 +294,7 @@ impl MergeEditor {
     pub fn with_name(name: &str, settings: &UserSettings) -> Result<Self, MergeToolConfigError> {
         let tool = get_tool_config(settings, name)?
             .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_program(name)));
-        Self::new_inner(name, tool)
+        Self::new_inner(name, tool, settings)
     }
 
     /// Loads the default 3-way merge editor from the settings.
@@ -272,16 +306,47 @@ impl MergeEditor {
             None
         }
         .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_merge_args(&args)));
-        Self::new_inner(&args, tool)
+        Self::new_inner(&args, tool, settings)
     }
 
-    fn new_inner(name: impl ToString, tool: MergeTool) -> Result<Self, MergeToolConfigError> {
+    fn new_inner(
+        name: impl ToString,
+        tool: MergeTool,
+        settings: &UserSettings,
+    ) -> Result<Self, MergeToolConfigError> {
         if matches!(&tool, MergeTool::External(mergetool) if mergetool.merge_args.is_empty()) {
             return Err(MergeToolConfigError::MergeArgsNotConfigured {
                 tool_name: name.to_string(),
             });
         }
-        Ok(MergeEditor { tool })
+        let timeout = settings
+            .config()
+            .get::<u64>("ui.merge-tool-timeout")
+            .optional()?
+            .map(Duration::from_secs);
+        let marker_style = settings
+            .config()
+            .get::<ConflictMarkerStyle>("ui.merge-tool-marker-style")
+            .optional()?
+            .unwrap_or_default();
+        Ok(MergeEditor {
+            tool,
+            timeout,
+            marker_style,
+        })
+    }
+
+    /// Kills the external merge tool if it doesn't finish within `timeout`,
+    /// overriding the `ui.merge-tool-timeout` setting. The builtin merge tool
+    /// is interactive and never times out.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Writes the conflict markers for external merge tools that edit them in
+    /// the given style, overriding the `ui.merge-tool-marker-style` setting.
+    pub fn set_marker_style(&mut self, marker_style: ConflictMarkerStyle) {
+        self.marker_style = marker_style;
     }
 
     /// Starts a merge editor for the specified file.
@@ -290,29 +355,7 @@ impl MergeEditor {
         tree: &MergedTree,
         repo_path: &RepoPath,
     ) -> Result<MergedTreeId, ConflictResolveError> {
-        let conflict = match tree.path_value(repo_path)?.into_resolved() {
-            Err(conflict) => conflict,
-            Ok(Some(_)) => return Err(ConflictResolveError::NotAConflict(repo_path.to_owned())),
-            Ok(None) => return Err(ConflictResolveError::PathNotFound(repo_path.to_owned())),
-        };
-        let file_merge = conflict.to_file_merge().ok_or_else(|| {
-            let mut summary_bytes: Vec<u8> = vec![];
-            conflict
-                .describe(&mut summary_bytes)
-                .expect("Writing to an in-memory buffer should never fail");
-            ConflictResolveError::NotNormalFiles(
-                repo_path.to_owned(),
-                String::from_utf8_lossy(summary_bytes.as_slice()).to_string(),
-            )
-        })?;
-        let simplified_file_merge = file_merge.clone().simplify();
-        // We only support conflicts with 2 sides (3-way conflicts)
-        if simplified_file_merge.num_sides() > 2 {
-            return Err(ConflictResolveError::ConflictTooComplicated {
-                path: repo_path.to_owned(),
-                sides: simplified_file_merge.num_sides(),
-            });
-        };
+        let (conflict, file_merge, simplified_file_merge) = file_conflict_at(tree, repo_path)?;
         let content =
             extract_as_single_hunk(&simplified_file_merge, tree.store(), repo_path).block_on()?;
 
@@ -322,10 +365,95 @@ impl MergeEditor {
                 Ok(tree_id)
             }
             MergeTool::External(editor) => external::run_mergetool_external(
-                editor, file_merge, content, repo_path, conflict, tree,
+                editor,
+                file_merge,
+                content,
+                repo_path,
+                conflict,
+                tree,
+                self.timeout,
+                self.marker_style,
             ),
         }
     }
+
+    /// Returns the command line the external merge tool would be invoked with
+    /// for the specified file, or `None` if the merge tool is builtin.
+    pub fn command_for_file(
+        &self,
+        tree: &MergedTree,
+        repo_path: &RepoPath,
+    ) -> Result<Option<Vec<String>>, ConflictResolveError> {
+        let MergeTool::External(editor) = &self.tool else {
+            return Ok(None);
+        };
+        let (_, _, simplified_file_merge) = file_conflict_at(tree, repo_path)?;
+        let content =
+            extract_as_single_hunk(&simplified_file_merge, tree.store(), repo_path).block_on()?;
+        let command =
+            external::mergetool_external_command(editor, &content, repo_path, self.marker_style)?;
+        Ok(Some(command))
+    }
+}
+
+/// Resolves the conflict at the specified file by taking the given side
+/// (0-based) of the 3-way merge as is.
+pub fn resolve_file_with_side(
+    tree: &MergedTree,
+    repo_path: &RepoPath,
+    side: usize,
+) -> Result<MergedTreeId, ConflictResolveError> {
+    // Simplification may drop sides, so index the sides of the original merge.
+    let (conflict, file_merge, _) = file_conflict_at(tree, repo_path)?;
+    let executable_merge = conflict
+        .to_executable_merge()
+        .expect("file conflict should have executable bits");
+    let new_tree_value = file_merge
+        .get_add(side)
+        .expect("side index should be within the merge")
+        .clone()
+        .map(|id| TreeValue::File {
+            id,
+            executable: *executable_merge.get_add(side).unwrap(),
+        });
+    let mut tree_builder = MergedTreeBuilder::new(tree.id());
+    tree_builder.set_or_remove(repo_path.to_owned(), Merge::resolved(new_tree_value));
+    let new_tree = tree_builder.write_tree(tree.store())?;
+    Ok(new_tree)
+}
+
+type FileMerge = Merge<Option<FileId>>;
+
+/// Looks up the file conflict at `repo_path`, and checks that it can be
+/// resolved as a 3-way merge.
+fn file_conflict_at(
+    tree: &MergedTree,
+    repo_path: &RepoPath,
+) -> Result<(MergedTreeValue, FileMerge, FileMerge), ConflictResolveError> {
+    let conflict = match tree.path_value(repo_path)?.into_resolved() {
+        Err(conflict) => conflict,
+        Ok(Some(_)) => return Err(ConflictResolveError::NotAConflict(repo_path.to_owned())),
+        Ok(None) => return Err(ConflictResolveError::PathNotFound(repo_path.to_owned())),
+    };
+    let file_merge = conflict.to_file_merge().ok_or_else(|| {
+        let mut summary_bytes: Vec<u8> = vec![];
+        conflict
+            .describe(&mut summary_bytes)
+            .expect("Writing to an in-memory buffer should never fail");
+        ConflictResolveError::NotNormalFiles(
+            repo_path.to_owned(),
+            String::from_utf8_lossy(summary_bytes.as_slice()).to_string(),
+        )
+    })?;
+    let simplified_file_merge = file_merge.clone().simplify();
+    // We only support conflicts with 2 sides (3-way conflicts)
+    if simplified_file_merge.num_sides() > 2 {
+        return Err(ConflictResolveError::ConflictTooComplicated {
+            path: repo_path.to_owned(),
+            sides: simplified_file_merge.num_sides(),
+        });
+    };
+    Ok((conflict, file_merge, simplified_file_merge))
 }
 
 #[cfg(test)]
@@ -718,4 +846,31 @@ mod tests {
         // Invalid type
         assert!(get(r#"ui.merge-editor.k = 0"#).is_err());
     }
+
+    #[test]
+    fn test_merge_tool_name_for_path() {
+        let get = |text, path| {
+            let config = config_from_string(text);
+            let settings = UserSettings::from_config(config);
+            merge_tool_name_for_path(&settings, RepoPath::from_internal_string(path))
+        };
+
+        let text = r#"
+            [ui.merge-editor-by-extension]
+            md = "prose-merge"
+            rs = "code-merge"
+        "#;
+        assert_eq!(
+            get(text, "README.md").unwrap().as_deref(),
+            Some("prose-merge")
+        );
+        assert_eq!(
+            get(text, "src/lib.rs").unwrap().as_deref(),
+            Some("code-merge")
+        );
+        assert_eq!(get(text, "docs.md/file").unwrap(), None);
+        assert_eq!(get(text, "file.txt").unwrap(), None);
+        assert_eq!(get(text, "Makefile").unwrap(), None);
+        assert_eq!(get("", "README.md").unwrap(), None);
+    }
 }
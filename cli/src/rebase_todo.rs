@@ -0,0 +1,212 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Todo list of `jj rebase --interactive`.
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use indoc::indoc;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::settings::UserSettings;
+use thiserror::Error;
+
+use crate::cli_util::edit_temp_file;
+use crate::cli_util::short_commit_hash;
+use crate::command_error::CommandError;
+
+/// What to do with a commit listed in the todo list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TodoAction {
+    /// Keep the commit, rebasing it onto the previously picked commit.
+    Pick,
+    /// Abandon the commit.
+    Drop,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ParseRebaseTodoError {
+    #[error(r#"Invalid line in the todo list: "{0}""#)]
+    InvalidLine(String),
+    #[error(r#"Unknown action "{0}" in the todo list"#)]
+    UnknownAction(String),
+    #[error("Commit {0} is not in the commits to rebase")]
+    UnexpectedCommit(String),
+    #[error("Commit {0} is listed more than once in the todo list")]
+    DuplicateCommit(String),
+    #[error("Commit {0} is missing from the todo list (use `drop` to abandon it)")]
+    MissingCommit(String),
+    #[error("Nothing to do: the todo list is empty")]
+    Empty,
+}
+
+/// Lets the user edit the todo list for the given `commits`, which should be
+/// in topological order (oldest first). Returns the actions in the order they
+/// should be applied.
+pub fn edit_rebase_todo(
+    repo: &ReadonlyRepo,
+    commits: &[Commit],
+    settings: &UserSettings,
+) -> Result<Vec<(TodoAction, CommitId)>, CommandError> {
+    let mut commit_ids_map = IndexMap::new();
+    let mut todo = String::new();
+    for commit in commits {
+        let commit_hash = short_commit_hash(commit.id());
+        let subject = commit
+            .description()
+            .lines()
+            .next()
+            .unwrap_or("(no description set)");
+        todo.push_str(&format!("pick {commit_hash} {subject}\n"));
+        commit_ids_map.insert(commit_hash, commit.id());
+    }
+    todo.push_str(indoc! {r#"
+
+        JJ: The commits are listed oldest first. Each line is applied in order:
+        JJ:   pick <commit> = rebase the commit onto the previous one
+        JJ:   drop <commit> = abandon the commit
+        JJ: The lines can be reordered. Every commit must be listed once.
+        JJ: Lines starting with "JJ: " (like this one) will be removed.
+    "#});
+
+    let todo = edit_temp_file(
+        "rebase todo list",
+        ".jjrebase",
+        repo.repo_path(),
+        &todo,
+        settings,
+    )?;
+
+    Ok(parse_rebase_todo(&todo, &commit_ids_map)?)
+}
+
+/// Parses the edited todo list. Every commit in `commit_ids_map` must be
+/// listed exactly once.
+fn parse_rebase_todo<T>(
+    todo: &str,
+    commit_ids_map: &IndexMap<String, &T>,
+) -> Result<Vec<(TodoAction, T)>, ParseRebaseTodoError>
+where
+    T: Eq + std::hash::Hash + Clone,
+{
+    let mut actions = vec![];
+    let mut seen = HashSet::new();
+    for line in todo.lines() {
+        if line.trim().is_empty() || line.starts_with("JJ: ") {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let (Some(action), Some(commit_id_prefix)) = (words.next(), words.next()) else {
+            return Err(ParseRebaseTodoError::InvalidLine(line.to_owned()));
+        };
+        let action = match action {
+            "pick" | "p" => TodoAction::Pick,
+            "drop" | "d" => TodoAction::Drop,
+            _ => return Err(ParseRebaseTodoError::UnknownAction(action.to_owned())),
+        };
+        let Some(&commit_id) = commit_ids_map.get(commit_id_prefix) else {
+            return Err(ParseRebaseTodoError::UnexpectedCommit(
+                commit_id_prefix.to_owned(),
+            ));
+        };
+        if !seen.insert(commit_id) {
+            return Err(ParseRebaseTodoError::DuplicateCommit(
+                commit_id_prefix.to_owned(),
+            ));
+        }
+        actions.push((action, commit_id.clone()));
+    }
+
+    if actions.is_empty() {
+        return Err(ParseRebaseTodoError::Empty);
+    }
+    if let Some((commit_id_prefix, _)) = commit_ids_map
+        .iter()
+        .find(|(_, commit_id)| !seen.contains(*commit_id))
+    {
+        return Err(ParseRebaseTodoError::MissingCommit(
+            commit_id_prefix.to_owned(),
+        ));
+    }
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+    use indoc::indoc;
+
+    use super::*;
+
+    fn commit_ids_map() -> IndexMap<String, &'static i32> {
+        indexmap! {
+            "1".to_string() => &1,
+            "2".to_string() => &2,
+            "3".to_string() => &3,
+        }
+    }
+
+    #[test]
+    fn test_parse_rebase_todo() {
+        let result = parse_rebase_todo(
+            indoc! {"
+                pick 3 third
+                d 1
+                p 2
+
+                JJ: comment
+            "},
+            &commit_ids_map(),
+        );
+        assert_eq!(
+            result,
+            Ok(vec![
+                (TodoAction::Pick, 3),
+                (TodoAction::Drop, 1),
+                (TodoAction::Pick, 2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_rebase_todo_errors() {
+        let parse = |todo| parse_rebase_todo(todo, &commit_ids_map());
+        assert_eq!(
+            parse("pick\n"),
+            Err(ParseRebaseTodoError::InvalidLine("pick".to_owned()))
+        );
+        assert_eq!(
+            parse("squash 1\n"),
+            Err(ParseRebaseTodoError::UnknownAction("squash".to_owned()))
+        );
+        assert_eq!(
+            parse("pick 4\n"),
+            Err(ParseRebaseTodoError::UnexpectedCommit("4".to_owned()))
+        );
+        assert_eq!(
+            parse("pick 1\ndrop 1\n"),
+            Err(ParseRebaseTodoError::DuplicateCommit("1".to_owned()))
+        );
+        assert_eq!(
+            parse("pick 1\npick 3\n"),
+            Err(ParseRebaseTodoError::MissingCommit("2".to_owned()))
+        );
+        assert_eq!(
+            parse("JJ: only comments\n"),
+            Err(ParseRebaseTodoError::Empty)
+        );
+    }
+}
@@ -16,6 +16,8 @@ use std::env;
 use std::fs;
 use std::path::PathBuf;
 use std::process::exit;
+use std::thread;
+use std::time::Duration;
 
 use clap::Parser;
 use itertools::Itertools;
@@ -47,6 +49,7 @@ fn main() {
         match parts.as_slice() {
             [""] => {}
             ["fail"] => exit(1),
+            ["sleep", secs] => thread::sleep(Duration::from_secs(secs.parse().unwrap())),
             ["dump", dest] => {
                 let dest_path = edit_script_path.parent().unwrap().join(dest);
                 fs::copy(&args.file, dest_path).unwrap();
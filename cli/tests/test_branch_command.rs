@@ -81,6 +81,275 @@ fn test_branch_multiple_names() {
     "###);
 }
 
+#[test]
+fn test_branch_create_at_heads() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "c"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "--at-heads", "--prefix", "wip-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created branch wip-zsuskulnrvyr pointing to zsuskuln df2ea0c6 wip-zsuskulnrvyr | (empty) c
+    Created branch wip-qpvuntsmwlqt pointing to qpvuntsm d8d5f980 wip-qpvuntsmwlqt | (empty) a
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  wip-zsuskulnrvyr df2ea0c616d0
+    ○   06a973bcb57f
+    │ ○  wip-qpvuntsmwlqt d8d5f980a897
+    ├─╯
+    ◆   000000000000
+    "###);
+
+    // The names are kept when the heads are rewritten, so the existing
+    // branches aren't overwritten
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "c2"]);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["branch", "create", "--at-heads", "--prefix", "wip-"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch already exists: wip-zsuskulnrvyr
+    Hint: Use `jj branch set` to update it.
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "create", "--at-heads"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the following required arguments were not provided:
+      --prefix <PREFIX>
+
+    Usage: jj branch create --at-heads --prefix <PREFIX> [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["branch", "create", "--at-heads", "--prefix", "wip-", "foo"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--at-heads' cannot be used with '[NAMES]...'
+
+    Usage: jj branch create --at-heads --prefix <PREFIX> [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_create_print_target() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "foo", "-r@-", "--print-target"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    d8d5f980a897bec1a085986377897c00e531ebce
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branches pointing to qpvuntsm d8d5f980 foo | (empty) a
+    "###);
+    let commit_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r@-", "-T", r#"commit_id ++ "\n""#],
+    );
+    assert_eq!(stdout, commit_id);
+
+    // The target isn't printed by default
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &[
+            "branch",
+            "create",
+            "--at-heads",
+            "--prefix",
+            "wip-",
+            "--print-target",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--at-heads' cannot be used with '--print-target'
+
+    Usage: jj branch create --at-heads --prefix <PREFIX> [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_create_from_description() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "Fix login bug!\n\nDetails"]);
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--from-description"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branches pointing to qpvuntsm 09b6e370 fix-login-bug | (empty) Fix login bug!
+    "###);
+
+    // The name is deduplicated against the existing branches
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "fix: Login  bug"]);
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--from-description"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branches pointing to zsuskuln 280cb99d fix-login-bug-2 | (empty) fix: Login  bug
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "--from-description", "-r@-"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branches pointing to qpvuntsm 09b6e370 fix-login-bug fix-login-bug-3 | (empty) Fix login bug!
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    fix-login-bug: qpvuntsm 09b6e370 (empty) Fix login bug!
+    fix-login-bug-2: zsuskuln 280cb99d (empty) fix: Login  bug
+    fix-login-bug-3: qpvuntsm 09b6e370 (empty) Fix login bug!
+    "###);
+
+    // An empty description is refused
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", "--from-description"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot derive a branch name from the description of commit 64dbf1bec68c
+    Hint: Describe the commit first, or specify the branch name.
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["branch", "create", "--from-description", "foo"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--from-description' cannot be used with '[NAMES]...'
+
+    Usage: jj branch create --from-description [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_create_force() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo", "-r@"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+
+    // Existing branch is moved forward, new branch is created
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--force", "foo", "bar"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branches pointing to zsuskuln 22a82dfa bar foo | (empty) b
+    Moved 1 branches to zsuskuln 22a82dfa bar foo | (empty) b
+    Hint: Use -r to specify the target revision.
+    "###);
+
+    // Branch already pointing to the target is reported as unchanged
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--force", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Branch foo already points to zsuskuln 22a82dfa bar foo | (empty) b
+    Nothing changed.
+    "###);
+
+    // Moving backwards requires --allow-backwards
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["branch", "create", "--force", "foo", "-r@-"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to move branch backwards or sideways: foo
+    Hint: Use --allow-backwards to allow it.
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "create",
+            "--force",
+            "--allow-backwards",
+            "foo",
+            "-r@-",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 1 branches to qpvuntsm d8d5f980 foo | (empty) a
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar 22a82dfa8a52
+    ○  foo d8d5f980a897
+    ◆   000000000000
+    "###);
+
+    // Without --force, existing branch is still an error
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch already exists: foo
+    Hint: Use `jj branch set` to update it.
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["branch", "create", "--allow-backwards", "baz"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the following required arguments were not provided:
+      --force
+
+    Usage: jj branch create --force --allow-backwards <NAMES>...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_create_force_tracked_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remote
+    let git_repo_path = test_env.env_root().join("git-repo");
+    git2::Repository::init_bare(git_repo_path).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "push", "--branch", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+
+    // Deleted local branch tracking a remote branch is created, not moved
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--force", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branches pointing to yqosqzyt 413f0529 foo* | (empty) b
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    foo: yqosqzyt 413f0529 (empty) b
+      @origin (behind by 1 commits): qpvuntsm 6d37472c (empty) a
+    "###);
+}
+
 #[test]
 fn test_branch_at_root() {
     let test_env = TestEnvironment::default();
@@ -110,7 +379,7 @@ fn test_branch_empty_name() {
 
     let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "create", ""]);
     insta::assert_snapshot!(stderr, @r###"
-    error: a value is required for '<NAMES>...' but none was supplied
+    error: a value is required for '[NAMES]...' but none was supplied
 
     For more information, try '--help'.
     "###);
@@ -185,6 +454,7 @@ fn test_branch_move() {
     Hint: Use --allow-backwards to allow it.
     "###);
 
+    // The working-copy commit left behind isn't reported as unreachable
     let (_stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
         &["branch", "move", "--to=@-", "--allow-backwards", "foo"],
@@ -232,6 +502,160 @@ fn test_branch_move() {
     "###);
 }
 
+#[test]
+fn test_branch_move_backwards_unreachable() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "d"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(a)", "-m", "e"]);
+
+    // Commits still reachable from the other branch aren't listed
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "move",
+            "--to=description(a)",
+            "--allow-backwards",
+            "foo",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 1 branches to qpvuntsm d8d5f980 foo | (empty) a
+    Warning: The following commits are no longer reachable from any branch:
+      royxmykx 13fdb50a (empty) d
+      mzvwutvl 6442cbe4 (empty) c
+    "###);
+
+    // Moving forward doesn't make anything unreachable
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "move", "--to=description(e)", "foo"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 1 branches to vruxwmqv c6c1f0ef foo | (empty) e
+    "###);
+}
+
+#[test]
+fn test_branch_move_multiple_names() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(a)", "-m", "d"]);
+
+    // All backward moves are reported
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "a", "b", "c"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to move branch backwards or sideways: b, c
+    Hint: Use --allow-backwards to allow it.
+    "###);
+
+    // The previous target of each moved branch is reported
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "move",
+            "a",
+            "b",
+            "c",
+            "--to=@",
+            "--allow-backwards",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 3 branches to vruxwmqv bf984740 a b c | (empty) d
+      a (was at d8d5f980a897)
+      b (was at 22a82dfa8a52)
+      c (was at c5c00ff0672e)
+    Warning: The following commits are no longer reachable from any branch:
+      royxmykx c5c00ff0 (empty) c
+      zsuskuln 22a82dfa (empty) b
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  a b c bf9847403d6f
+    │ ○   c5c00ff0672e
+    │ ○   22a82dfa8a52
+    ├─╯
+    ○   d8d5f980a897
+    ◆   000000000000
+    "###);
+}
+
+#[test]
+fn test_branch_set_if_target() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "c"]);
+
+    // The branch is moved if it points to the expected target
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "set",
+            "foo",
+            "--if-target=description(a)",
+            "--to=description(b)",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 1 branches to zsuskuln 22a82dfa foo | (empty) b
+    "###);
+
+    // Otherwise, it's left unchanged
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "branch",
+            "set",
+            "foo",
+            "--if-target=description(a)",
+            "--to=@",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch foo was expected to point to d8d5f980a897, but it points to 22a82dfa8a52
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "branch",
+            "set",
+            "bar",
+            "--if-target=description(a)",
+            "--to=@",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch bar was expected to point to d8d5f980a897, but it doesn't exist
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    foo: zsuskuln 22a82dfa (empty) b
+    "###);
+}
+
 #[test]
 fn test_branch_move_matching() {
     let test_env = TestEnvironment::default();
@@ -261,78 +685,287 @@ fn test_branch_move_matching() {
     error: the following required arguments were not provided:
       <--from <REVISIONS>|NAMES>
 
-    Usage: jj branch move <--from <REVISIONS>|NAMES>
+    Usage: jj branch move <--from <REVISIONS>|NAMES>
+
+    For more information, try '--help'.
+    "###);
+
+    // No branches pointing to the source revisions
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "--from=none()"]);
+    insta::assert_snapshot!(stderr, @r###"
+    No branches to update.
+    "###);
+
+    // No matching branches within the source revisions
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "--from=::@", "glob:a?"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No matching branches for patterns: a?
+    "###);
+
+    // Noop move
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "--to=a1", "a2"]);
+    insta::assert_snapshot!(stderr, @r###"
+    No branches to update.
+    "###);
+
+    // Move from multiple revisions
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "--from=::@"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 2 branches to vruxwmqv a2781dd9 b1 c1 | (empty) head2
+      b1 (was at f652c32197cf)
+      c1 (was at f4f38657a3dd)
+    Hint: Specify branch by name to update just one of the branches.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  b1 c1 a2781dd9ee37
+    ○   f4f38657a3dd
+    ○   f652c32197cf
+    │ ○   6b5e840ea72b
+    │ ○  a1 a2 230dd059e1b0
+    ├─╯
+    ◆   000000000000
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // Try to move multiple branches, but one of them isn't fast-forward
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "glob:?1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to move branch backwards or sideways: a1
+    Hint: Use --allow-backwards to allow it.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @   a2781dd9ee37
+    ○  c1 f4f38657a3dd
+    ○  b1 f652c32197cf
+    │ ○   6b5e840ea72b
+    │ ○  a1 a2 230dd059e1b0
+    ├─╯
+    ◆   000000000000
+    "###);
+
+    // Select by revision and name
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "move", "--from=::a1+", "--to=a1+", "glob:?1"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 1 branches to kkmpptxz 6b5e840e a1 | (empty) head1
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @   a2781dd9ee37
+    ○  c1 f4f38657a3dd
+    ○  b1 f652c32197cf
+    │ ○  a1 6b5e840ea72b
+    │ ○  a2 230dd059e1b0
+    ├─╯
+    ◆   000000000000
+    "###);
+}
+
+#[test]
+fn test_branch_move_to_change() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get_log = || {
+        let template = r#"separate(" ", description.first_line(), branches)"#;
+        test_env.jj_cmd_success(&repo_path, &["log", "-T", template])
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-mA"]);
+    let change_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r@", "--no-graph", "-Tchange_id.short()"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mB"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "-rroot()", "foo"]);
+
+    // Rewrite the change, so the original commit becomes hidden
+    test_env.jj_cmd_ok(&repo_path, &["describe", "@-", "-mA rewritten"]);
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "move", "foo", "--to-change", &change_id],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Moved 1 branches to qpvuntsm dc4ed6b3 foo | (empty) A rewritten
+    "###);
+    insta::assert_snapshot!(get_log(), @r###"
+    @  B
+    ○  A rewritten foo
+    ◆
+    "###);
+
+    // The argument isn't resolved as a revset
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["branch", "move", "foo", "--to-change", "@"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid change ID: @
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["branch", "move", "foo", "--to-change", "yyyyyyy"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such change: yyyyyyy
+    "###);
+}
+
+#[test]
+fn test_branch_set_to_change() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get_log = || {
+        let template = r#"separate(" ", description.first_line(), branches)"#;
+        test_env.jj_cmd_success(&repo_path, &["log", "-T", template])
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-mA"]);
+    let change_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r@", "--no-graph", "-Tchange_id.short()"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mB"]);
+    // Rewrite the change, so the original commit becomes hidden
+    test_env.jj_cmd_ok(&repo_path, &["describe", "@-", "-mA rewritten"]);
 
-    For more information, try '--help'.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "set", "foo", "--to-change", &change_id],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branches pointing to qpvuntsm f7c8fbb6 foo | (empty) A rewritten
+    Hint: Consider using `jj branch move` if your intention was to move existing branches.
+    "###);
+    insta::assert_snapshot!(get_log(), @r###"
+    @  B
+    ○  A rewritten foo
+    ◆
     "###);
 
-    // No branches pointing to the source revisions
-    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "--from=none()"]);
+    // The argument isn't resolved as a revset
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "foo", "--to-change", "@"]);
     insta::assert_snapshot!(stderr, @r###"
-    No branches to update.
+    Error: Invalid change ID: @
     "###);
 
-    // No matching branches within the source revisions
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "--from=::@", "glob:a?"]);
+    // Divergent changes can't be resolved to a single commit. Rewrite the
+    // original commit concurrently with the first rewrite.
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "@-", "-mA divergent", "--at-operation", "@--"],
+    );
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["branch", "set", "foo", "--to-change", &change_id],
+    );
     insta::assert_snapshot!(stderr, @r###"
-    Error: No matching branches for patterns: a?
+    Concurrent modification detected, resolving automatically.
+    Error: Change qpvuntsmwlqt is divergent
+    Hint: Use `jj log -r qpvuntsmwlqt` to see the divergent commits.
     "###);
+}
 
-    // Noop move
-    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "--to=a1", "a2"]);
-    insta::assert_snapshot!(stderr, @r###"
-    No branches to update.
+#[test]
+fn test_branch_move_also_descendants() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get_log = || {
+        let template = r#"separate(" ", description.first_line(), branches)"#;
+        test_env.jj_cmd_success(&repo_path, &["log", "-T", template])
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-mA"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mB"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "feature1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mC"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "feature2"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mD"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mE", "description(A)"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "side"]);
+    insta::assert_snapshot!(get_log(), @r###"
+    @  E side
+    │ ○  D
+    │ ○  C feature2
+    │ ○  B feature1
+    ├─╯
+    ○  A main
+    ◆
     "###);
 
-    // Move from multiple revisions
-    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "--from=::@"]);
+    // Without the flag, the branches in between are left behind
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "move", "main", "--to=description(D)"],
+    );
     insta::assert_snapshot!(stderr, @r###"
-    Moved 2 branches to vruxwmqv a2781dd9 b1 c1 | (empty) head2
-    Hint: Specify branch by name to update just one of the branches.
+    Moved 1 branches to vruxwmqv 7141f5a1 main | (empty) D
     "###);
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  b1 c1 a2781dd9ee37
-    ○   f4f38657a3dd
-    ○   f652c32197cf
-    │ ○   6b5e840ea72b
-    │ ○  a1 a2 230dd059e1b0
+    insta::assert_snapshot!(get_log(), @r###"
+    @  E side
+    │ ○  D main
+    │ ○  C feature2
+    │ ○  B feature1
     ├─╯
-    ◆   000000000000
+    ○  A
+    ◆
     "###);
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
 
-    // Try to move multiple branches, but one of them isn't fast-forward
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "glob:?1"]);
+    // Branches between the old and the new target are moved along, so none of
+    // them ends up behind "main". The sideways branch isn't moved.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "move",
+            "main",
+            "--to=description(D)",
+            "--also-descendants",
+        ],
+    );
     insta::assert_snapshot!(stderr, @r###"
-    Error: Refusing to move branch backwards or sideways: a1
-    Hint: Use --allow-backwards to allow it.
+    Moved 3 branches to vruxwmqv 7141f5a1 feature1 feature2 main | (empty) D
+      feature1 (was at 0b63ea0b5508)
+      feature2 (was at 99b85a999bde)
+      main (was at a7e4cec4256b)
     "###);
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @   a2781dd9ee37
-    ○  c1 f4f38657a3dd
-    ○  b1 f652c32197cf
-    │ ○   6b5e840ea72b
-    │ ○  a1 a2 230dd059e1b0
+    insta::assert_snapshot!(get_log(), @r###"
+    @  E side
+    │ ○  D feature1 feature2 main
+    │ ○  C
+    │ ○  B
     ├─╯
-    ◆   000000000000
+    ○  A
+    ◆
     "###);
 
-    // Select by revision and name
+    // Branches beyond the new target are kept in place
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
     let (_stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
-        &["branch", "move", "--from=::a1+", "--to=a1+", "glob:?1"],
+        &[
+            "branch",
+            "move",
+            "main",
+            "--to=description(B)",
+            "--also-descendants",
+        ],
     );
     insta::assert_snapshot!(stderr, @r###"
-    Moved 1 branches to kkmpptxz 6b5e840e a1 | (empty) head1
+    Moved 1 branches to zsuskuln 0b63ea0b feature1 main | (empty) B
     "###);
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @   a2781dd9ee37
-    ○  c1 f4f38657a3dd
-    ○  b1 f652c32197cf
-    │ ○  a1 6b5e840ea72b
-    │ ○  a2 230dd059e1b0
+    insta::assert_snapshot!(get_log(), @r###"
+    @  E side
+    │ ○  D
+    │ ○  C feature2
+    │ ○  B feature1 main
     ├─╯
-    ◆   000000000000
+    ○  A
+    ◆
     "###);
 }
 
@@ -481,13 +1114,15 @@ fn test_branch_forget_glob() {
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "glob:foo-[1-3]"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Forgot 2 branches.
+    Forgot local branch foo-1
+    Forgot local branch foo-3
     "###);
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "glob:foo-[1-3]"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Forgot 2 branches.
+    Forgot local branch foo-1
+    Forgot local branch foo-3
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
     @  bar-2 foo-4 230dd059e1b0
@@ -502,7 +1137,7 @@ fn test_branch_forget_glob() {
     );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Forgot 1 branches.
+    Forgot local branch foo-4
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
     @  bar-2 230dd059e1b0
@@ -563,6 +1198,15 @@ fn test_branch_delete_glob() {
     Deleted 2 branches.
     "###);
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "delete", "glob:foo-*", "~glob:foo-[4-9]"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Deleted 2 branches.
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "glob:foo-[1-3]"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
@@ -666,13 +1310,23 @@ fn test_branch_forget_export() {
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "foo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "forget",
+            "--include-remotes",
+            "--include-git",
+            "foo",
+        ],
+    );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Forgot 1 branches.
+    Forgot local branch foo
+    Forgot remote branch foo@git
     "###);
-    // Forgetting a branch deletes local and remote-tracking branches including
-    // the corresponding git-tracking branch.
+    // Forgetting a branch with --include-git deletes local and remote-tracking
+    // branches including the corresponding git-tracking branch.
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
     let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r=foo", "--no-graph"]);
     insta::assert_snapshot!(stderr, @r###"
@@ -687,6 +1341,22 @@ fn test_branch_forget_export() {
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
+
+    // The Git-tracking branch is kept by default, in which case it's deleted by
+    // the next export
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "bar"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot local branch bar
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    bar (deleted)
+      @git: rlvkpnrz 65b6b74e (empty) (no description set)
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
 }
 
 #[test]
@@ -733,9 +1403,33 @@ fn test_branch_forget_fetched_branch() {
       @origin: mzyxwzks 9f01a0e0 message
     "###);
 
+    // By default, the remote branch is untracked instead of forgotten, so
+    // fetching doesn't recreate the local branch
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot local branch feature1
+    Stopped tracking remote branch feature1@origin
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1@origin: mzyxwzks 9f01a0e0 message
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1@origin: mzyxwzks 9f01a0e0 message
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
+
     // TEST 1: with export-import
     // Forget the branch
-    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "forget", "--include-remotes", "feature1"],
+    );
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
 
     // At this point `jj git export && jj git import` does *not* recreate the
@@ -768,7 +1462,10 @@ fn test_branch_forget_fetched_branch() {
     "###);
 
     // TEST 2: No export/import (otherwise the same as test 1)
-    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "forget", "--include-remotes", "feature1"],
+    );
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
     // Fetch works even without the export-import
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
@@ -794,10 +1491,14 @@ fn test_branch_forget_fetched_branch() {
             &[&git_repo.find_commit(first_git_repo_commit).unwrap()],
         )
         .unwrap();
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "forget", "--include-remotes", "feature1"],
+    );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Forgot 1 branches.
+    Forgot local branch feature1
+    Forgot remote branch feature1@origin
     "###);
 
     // Fetching a moved branch does not create a conflict
@@ -861,7 +1562,10 @@ fn test_branch_forget_deleted_or_nonexistent_branch() {
     // ============ End of test setup ============
 
     // We can forget a deleted branch
-    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "forget", "--include-remotes", "feature1"],
+    );
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
 
     // Can't forget a non-existent branch
@@ -1131,7 +1835,7 @@ fn test_branch_track_untrack_patterns() {
     test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
     insta::assert_snapshot!(
         test_env.jj_cmd_cli_error(&repo_path, &["branch", "track", "main"]), @r###"
-    error: invalid value 'main' for '<BRANCH@REMOTE>...': remote branch must be specified in branch@remote form
+    error: invalid value 'main' for '[BRANCH@REMOTE]...': remote branch must be specified in branch@remote form
 
     For more information, try '--help'.
     "###);
@@ -1161,63 +1865,251 @@ fn test_branch_track_untrack_patterns() {
     test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
     let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
     insta::assert_snapshot!(stderr, @r###"
-    Warning: Remote branch already tracked: feature1@origin
+    Warning: Remote branch already tracked: feature1@origin
+    Nothing changed.
+    "###);
+
+    // Untrack non-tracking branch
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "feature2@origin"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Remote branch not tracked yet: feature2@origin
+    Nothing changed.
+    "###);
+
+    // Untrack Git-tracking branch
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "main@git"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Git-tracking branch cannot be untracked: main@git
+    Nothing changed.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: omvolwpu 1336caed commit
+      @git: omvolwpu 1336caed commit
+      @origin: omvolwpu 1336caed commit
+    feature2@origin: omvolwpu 1336caed commit
+    main: qpvuntsm 230dd059 (empty) (no description set)
+      @git: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Untrack by pattern
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "glob:*@*"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Git-tracking branch cannot be untracked: feature1@git
+    Warning: Remote branch not tracked yet: feature2@origin
+    Warning: Git-tracking branch cannot be untracked: main@git
+    Stopped tracking 1 remote branches.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: omvolwpu 1336caed commit
+      @git: omvolwpu 1336caed commit
+    feature1@origin: omvolwpu 1336caed commit
+    feature2@origin: omvolwpu 1336caed commit
+    main: qpvuntsm 230dd059 (empty) (no description set)
+      @git: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Track by pattern
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "track", "glob:feature?@origin"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Started tracking 2 remote branches.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: omvolwpu 1336caed commit
+      @git: omvolwpu 1336caed commit
+      @origin: omvolwpu 1336caed commit
+    feature2: omvolwpu 1336caed commit
+      @origin: omvolwpu 1336caed commit
+    main: qpvuntsm 230dd059 (empty) (no description set)
+      @git: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Tracking remote branches can be listed in fully-qualified form
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["branch", "list", "--all-remotes", "--fully-qualified"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    feature1: omvolwpu 1336caed commit
+    feature1@git: omvolwpu 1336caed commit
+    feature1@origin: omvolwpu 1336caed commit
+    feature2: omvolwpu 1336caed commit
+    feature2@origin: omvolwpu 1336caed commit
+    main: qpvuntsm 230dd059 (empty) (no description set)
+    main@git: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+}
+
+#[test]
+fn test_branch_track_all_from_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remote
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    let git_commit_oid = git_repo
+        .commit(None, &signature, &signature, "commit", &tree, &[])
+        .unwrap();
+    for name in [
+        "refs/heads/feature1",
+        "refs/heads/feature2",
+        "refs/heads/feature3",
+    ] {
+        git_repo.reference(name, git_commit_oid, true, "").unwrap();
+    }
+
+    // Fetch without auto tracking, then track one of the branches
+    test_env.add_config("git.auto-local-branch = false");
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "track", "--all-from-remote", "origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Tracking remote branch feature2@origin
+    Tracking remote branch feature3@origin
+    Started tracking 2 remote branches.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: onlznoyw feaa9c58 (empty) commit
+      @origin: onlznoyw feaa9c58 (empty) commit
+    feature2: onlznoyw feaa9c58 (empty) commit
+      @origin: onlznoyw feaa9c58 (empty) commit
+    feature3: onlznoyw feaa9c58 (empty) commit
+      @origin: onlznoyw feaa9c58 (empty) commit
+    "###);
+
+    // Nothing left to track
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "track", "--all-from-remote", "origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
     Nothing changed.
     "###);
 
-    // Untrack non-tracking branch
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "feature2@origin"]);
+    // The Git-tracking pseudo remote can't be tracked
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["branch", "track", "--all-from-remote", "git"]);
     insta::assert_snapshot!(stderr, @r###"
-    Warning: Remote branch not tracked yet: feature2@origin
-    Nothing changed.
+    Error: Git-tracking branches cannot be tracked: git
     "###);
 
-    // Untrack Git-tracking branch
-    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "main@git"]);
+    // Names can't be specified at the same time
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &[
+            "branch",
+            "track",
+            "--all-from-remote",
+            "origin",
+            "feature1@origin",
+        ],
+    );
     insta::assert_snapshot!(stderr, @r###"
-    Warning: Git-tracking branch cannot be untracked: main@git
-    Nothing changed.
-    "###);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: omvolwpu 1336caed commit
-      @git: omvolwpu 1336caed commit
-      @origin: omvolwpu 1336caed commit
-    feature2@origin: omvolwpu 1336caed commit
-    main: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+    error: the argument '--all-from-remote <REMOTE>' cannot be used with '[BRANCH@REMOTE]...'
+
+    Usage: jj branch track --all-from-remote <REMOTE> [BRANCH@REMOTE]...
+
+    For more information, try '--help'.
     "###);
+}
 
-    // Untrack by pattern
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "glob:*@*"]);
+#[test]
+fn test_branch_untrack_all() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "--colocate", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remotes
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    for remote in ["origin", "upstream"] {
+        test_env.jj_cmd_ok(&repo_path, &["git", "remote", "add", remote, "../git-repo"]);
+    }
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    let git_commit_oid = git_repo
+        .commit(None, &signature, &signature, "commit", &tree, &[])
+        .unwrap();
+    for name in ["refs/heads/feature1", "refs/heads/feature2"] {
+        git_repo.reference(name, git_commit_oid, true, "").unwrap();
+    }
+
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--all-remotes"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: onlznoyw feaa9c58 (empty) commit
+      @git: onlznoyw feaa9c58 (empty) commit
+      @origin: onlznoyw feaa9c58 (empty) commit
+      @upstream: onlznoyw feaa9c58 (empty) commit
+    feature2: onlznoyw feaa9c58 (empty) commit
+      @git: onlznoyw feaa9c58 (empty) commit
+      @origin: onlznoyw feaa9c58 (empty) commit
+      @upstream: onlznoyw feaa9c58 (empty) commit
+    "###);
+
+    // The branch is untracked on every remote, but not on the Git-tracking
+    // pseudo remote
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "--all", "feature1"]);
     insta::assert_snapshot!(stderr, @r###"
-    Warning: Git-tracking branch cannot be untracked: feature1@git
-    Warning: Remote branch not tracked yet: feature2@origin
-    Warning: Git-tracking branch cannot be untracked: main@git
-    Stopped tracking 1 remote branches.
+    Untracking remote branch feature1@origin
+    Untracking remote branch feature1@upstream
+    Stopped tracking 2 remote branches.
     "###);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: omvolwpu 1336caed commit
-      @git: omvolwpu 1336caed commit
-    feature1@origin: omvolwpu 1336caed commit
-    feature2@origin: omvolwpu 1336caed commit
-    main: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+    feature1: onlznoyw feaa9c58 (empty) commit
+      @git: onlznoyw feaa9c58 (empty) commit
+    feature1@origin: onlznoyw feaa9c58 (empty) commit
+    feature1@upstream: onlznoyw feaa9c58 (empty) commit
+    feature2: onlznoyw feaa9c58 (empty) commit
+      @git: onlznoyw feaa9c58 (empty) commit
+      @origin: onlznoyw feaa9c58 (empty) commit
+      @upstream: onlznoyw feaa9c58 (empty) commit
     "###);
 
-    // Track by pattern
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "track", "glob:feature?@origin"]);
+    // The remaining tracked branches can be matched by pattern
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "--all", "glob:feature*"]);
     insta::assert_snapshot!(stderr, @r###"
-    Started tracking 2 remote branches.
+    Untracking remote branch feature2@origin
+    Untracking remote branch feature2@upstream
+    Stopped tracking 2 remote branches.
     "###);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: omvolwpu 1336caed commit
-      @git: omvolwpu 1336caed commit
-      @origin: omvolwpu 1336caed commit
-    feature2: omvolwpu 1336caed commit
-      @origin: omvolwpu 1336caed commit
-    main: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+
+    // Nothing left to untrack
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "--all", "glob:feature*"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // Names can't be specified at the same time
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["branch", "untrack", "--all", "feature1", "feature1@origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--all <BRANCH>' cannot be used with '[BRANCH@REMOTE]...'
+
+    Usage: jj branch untrack --all <BRANCH> [BRANCH@REMOTE]...
+
+    For more information, try '--help'.
     "###);
 }
 
@@ -1555,6 +2447,29 @@ fn test_branch_list_filtered() {
     "###);
     insta::assert_snapshot!(stderr, @"");
 
+    // Negated name patterns exclude the matching branches.
+    let (stdout, stderr) = query(&["glob:remote-*", "~remote-delete"]);
+    insta::assert_snapshot!(stdout, @r###"
+    remote-keep: nlwprzpn 911e9120 (empty) remote-keep
+    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = query(&["glob:*-keep", "glob:*-rewrite", "~glob:local-*"]);
+    insta::assert_snapshot!(stdout, @r###"
+    remote-keep: nlwprzpn 911e9120 (empty) remote-keep
+    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+
+    // Only negated name patterns select all the other branches.
+    let (stdout, stderr) = query(&["~glob:remote-*"]);
+    insta::assert_snapshot!(stdout, @r###"
+    local-keep: kpqxywon c7b4c09c (empty) local-keep
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+
     // Name pattern and revset are OR-ed.
     let (stdout, stderr) = query(&["local-keep", "-rbranches(remote-rewrite)"]);
     insta::assert_snapshot!(stdout, @r###"
@@ -1807,6 +2722,397 @@ fn test_branch_list_conflicted() {
     "###);
 }
 
+#[test]
+fn test_branch_list_table() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "remote"]);
+    let remote_path = test_env.env_root().join("remote");
+    for branch in ["main", "feature", "stable"] {
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", branch]);
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", branch]);
+    }
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    let remote_git_path = remote_git_path.to_str().unwrap();
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path, "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(
+        &local_path,
+        &["git", "remote", "add", "upstream", remote_git_path],
+    );
+    test_env.jj_cmd_ok(&local_path, &["git", "fetch", "--remote", "upstream"]);
+
+    // Move main forward, feature sideways, and add a local-only branch
+    test_env.jj_cmd_ok(&local_path, &["new", "main", "-m", "local 1"]);
+    test_env.jj_cmd_ok(&local_path, &["new", "-m", "local 2"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "set", "main"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "set", "feature", "-r@-", "-B"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "local-only"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["branch", "list", "--all-remotes", "--format=table"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Branch      origin  upstream
+    feature     +2 -1   +2 -1
+    local-only
+    main        +2      +2
+    stable      =       =
+    "###);
+
+    // Without --all-remotes, only the remote branches that differ are shown
+    let stdout = test_env.jj_cmd_success(&local_path, &["branch", "list", "--format=table"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Branch      origin  upstream
+    feature     +2 -1   +2 -1
+    local-only
+    main        +2      +2
+    stable
+    "###);
+}
+
+#[test]
+fn test_branch_list_json() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "foo", "-r", "description(a)"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "create",
+            "foo",
+            "-r",
+            "description(b)",
+            "--at-op=@-",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+
+    // The conflicted target lists all the added commits
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--format=json"]);
+    insta::assert_snapshot!(stdout, @r###"
+    [
+      {
+        "name": "bar",
+        "target": [
+          "06a973bcb57fc824c8214f55c07436ec9197dd33"
+        ],
+        "conflict": false,
+        "remotes": []
+      },
+      {
+        "name": "foo",
+        "target": [
+          "d8d5f980a897bec1a085986377897c00e531ebce",
+          "06a973bcb57fc824c8214f55c07436ec9197dd33"
+        ],
+        "conflict": true,
+        "remotes": []
+      }
+    ]
+    "###);
+
+    // Remote branches are listed if they would be listed in the text output
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["branch", "list", "--format=json", "--all-remotes", "bar"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [
+      {
+        "name": "bar",
+        "target": [
+          "06a973bcb57fc824c8214f55c07436ec9197dd33"
+        ],
+        "conflict": false,
+        "remotes": [
+          {
+            "remote": "git",
+            "target": [
+              "06a973bcb57fc824c8214f55c07436ec9197dd33"
+            ],
+            "conflict": false,
+            "tracking": true
+          }
+        ]
+      }
+    ]
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["branch", "list", "--format=json", "-T", "name"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--format <FORMAT>' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj branch list --format <FORMAT> [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_list_group_by_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+
+    // Initialize two remotes
+    for remote in ["origin", "upstream"] {
+        test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", remote]);
+        let remote_path = test_env.env_root().join(remote);
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", remote]);
+        test_env.jj_cmd_ok(
+            &remote_path,
+            &["branch", "create", &format!("{remote}-branch")],
+        );
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", "shared"]);
+        test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+    }
+
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "local"]);
+    let local_path = test_env.env_root().join("local");
+    for remote in ["origin", "upstream"] {
+        let remote_git_path = test_env.env_root().join(remote).join(".jj/repo/store/git");
+        test_env.jj_cmd_ok(
+            &local_path,
+            &[
+                "git",
+                "remote",
+                "add",
+                remote,
+                remote_git_path.to_str().unwrap(),
+            ],
+        );
+        test_env.jj_cmd_ok(&local_path, &["git", "fetch", "--remote", remote]);
+    }
+    test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "local-only"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "local-only"]);
+
+    let stdout = test_env.jj_cmd_success(&local_path, &["branch", "list", "--all-remotes"]);
+    insta::assert_snapshot!(stdout, @r###"
+    local-only: nkmrtpmo 92476d3c (empty) local-only
+    origin-branch: nlmovnzk 156a6bd3 (empty) origin
+      @origin: nlmovnzk 156a6bd3 (empty) origin
+    shared (conflicted):
+      + nlmovnzk 156a6bd3 (empty) origin
+      + lsvxsoxl f07bcc2d (empty) upstream
+      @origin (behind by 1 commits): nlmovnzk 156a6bd3 (empty) origin
+      @upstream (behind by 1 commits): lsvxsoxl f07bcc2d (empty) upstream
+    upstream-branch: lsvxsoxl f07bcc2d (empty) upstream
+      @upstream: lsvxsoxl f07bcc2d (empty) upstream
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["branch", "list", "--all-remotes", "--group-by-remote"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Local branches:
+    local-only: nkmrtpmo 92476d3c (empty) local-only
+    origin-branch: nlmovnzk 156a6bd3 (empty) origin
+    shared (conflicted):
+      + nlmovnzk 156a6bd3 (empty) origin
+      + lsvxsoxl f07bcc2d (empty) upstream
+    upstream-branch: lsvxsoxl f07bcc2d (empty) upstream
+
+    Remote branches of origin:
+    origin-branch@origin: nlmovnzk 156a6bd3 (empty) origin
+    shared@origin: nlmovnzk 156a6bd3 (empty) origin
+
+    Remote branches of upstream:
+    shared@upstream: lsvxsoxl f07bcc2d (empty) upstream
+    upstream-branch@upstream: lsvxsoxl f07bcc2d (empty) upstream
+    "###);
+
+    // Synchronized remote branches are omitted by default
+    let stdout = test_env.jj_cmd_success(&local_path, &["branch", "list", "--group-by-remote"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Local branches:
+    local-only: nkmrtpmo 92476d3c (empty) local-only
+    origin-branch: nlmovnzk 156a6bd3 (empty) origin
+    shared (conflicted):
+      + nlmovnzk 156a6bd3 (empty) origin
+      + lsvxsoxl f07bcc2d (empty) upstream
+    upstream-branch: lsvxsoxl f07bcc2d (empty) upstream
+
+    Remote branches of origin:
+    shared@origin: nlmovnzk 156a6bd3 (empty) origin
+
+    Remote branches of upstream:
+    shared@upstream: lsvxsoxl f07bcc2d (empty) upstream
+    "###);
+}
+
+#[test]
+fn test_branch_list_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+
+    // Initialize two remotes
+    for remote in ["origin", "upstream"] {
+        test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", remote]);
+        let remote_path = test_env.env_root().join(remote);
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", remote]);
+        test_env.jj_cmd_ok(
+            &remote_path,
+            &["branch", "create", &format!("{remote}-branch")],
+        );
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", "shared"]);
+        test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+    }
+
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "local"]);
+    let local_path = test_env.env_root().join("local");
+    for remote in ["origin", "upstream"] {
+        let remote_git_path = test_env.env_root().join(remote).join(".jj/repo/store/git");
+        test_env.jj_cmd_ok(
+            &local_path,
+            &[
+                "git",
+                "remote",
+                "add",
+                remote,
+                remote_git_path.to_str().unwrap(),
+            ],
+        );
+        test_env.jj_cmd_ok(&local_path, &["git", "fetch", "--remote", remote]);
+    }
+    test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "local-only"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "local-only"]);
+    // Make upstream-branch a remote-only branch
+    test_env.jj_cmd_ok(
+        &local_path,
+        &["branch", "untrack", "upstream-branch@upstream"],
+    );
+    test_env.jj_cmd_ok(&local_path, &["branch", "delete", "upstream-branch"]);
+
+    // Only the branches on the given remote are listed. Tracked branches are
+    // listed with their local targets.
+    let stdout = test_env.jj_cmd_success(&local_path, &["branch", "list", "--remote=origin"]);
+    insta::assert_snapshot!(stdout, @r###"
+    origin-branch: nlmovnzk 156a6bd3 (empty) origin
+    shared (conflicted):
+      + nlmovnzk 156a6bd3 (empty) origin
+      + lsvxsoxl f07bcc2d (empty) upstream
+      @origin (behind by 1 commits): nlmovnzk 156a6bd3 (empty) origin
+    "###);
+
+    // Non-tracking remote branches of the given remote are listed too
+    let stdout = test_env.jj_cmd_success(&local_path, &["branch", "list", "--remote=upstream"]);
+    insta::assert_snapshot!(stdout, @r###"
+    shared (conflicted):
+      + nlmovnzk 156a6bd3 (empty) origin
+      + lsvxsoxl f07bcc2d (empty) upstream
+      @upstream (behind by 1 commits): lsvxsoxl f07bcc2d (empty) upstream
+    upstream-branch@upstream: lsvxsoxl f07bcc2d (empty) upstream
+    "###);
+
+    // Can be combined with name patterns
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["branch", "list", "--remote=upstream", "glob:shared*"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    shared (conflicted):
+      + nlmovnzk 156a6bd3 (empty) origin
+      + lsvxsoxl f07bcc2d (empty) upstream
+      @upstream (behind by 1 commits): lsvxsoxl f07bcc2d (empty) upstream
+    "###);
+
+    // Non-tracking remote branches are omitted with --tracked
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["branch", "list", "--remote=glob:*", "--tracked"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    origin-branch: nlmovnzk 156a6bd3 (empty) origin
+      @origin: nlmovnzk 156a6bd3 (empty) origin
+    shared (conflicted):
+      + nlmovnzk 156a6bd3 (empty) origin
+      + lsvxsoxl f07bcc2d (empty) upstream
+      @origin (behind by 1 commits): nlmovnzk 156a6bd3 (empty) origin
+      @upstream (behind by 1 commits): lsvxsoxl f07bcc2d (empty) upstream
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&local_path, &["branch", "list", "--remote=unknown"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_branch_list_sort() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Create branches in an order different from their names. "middle" is
+    // rewritten last, so its committer date is newer than its author date.
+    for name in ["middle", "zzz", "aaa"] {
+        test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", name]);
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", name]);
+    }
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "middle", "-m", "middle (rewritten)"],
+    );
+
+    let list = |sort: &str| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["branch", "list", "--sort", sort, "-T", r#"name ++ "\n""#],
+        )
+    };
+    insta::assert_snapshot!(list("name"), @r###"
+    aaa
+    middle
+    zzz
+    "###);
+    insta::assert_snapshot!(list("name-"), @r###"
+    zzz
+    middle
+    aaa
+    "###);
+    insta::assert_snapshot!(list("author-date"), @r###"
+    middle
+    zzz
+    aaa
+    "###);
+    insta::assert_snapshot!(list("author-date-"), @r###"
+    aaa
+    zzz
+    middle
+    "###);
+    insta::assert_snapshot!(list("committer-date"), @r###"
+    zzz
+    aaa
+    middle
+    "###);
+    insta::assert_snapshot!(list("committer-date-"), @r###"
+    middle
+    aaa
+    zzz
+    "###);
+}
+
 fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     let template = r#"branches ++ " " ++ commit_id.short()"#;
     test_env.jj_cmd_success(cwd, &["log", "-T", template])
@@ -77,6 +77,48 @@ fn test_log_parents() {
     "###);
 }
 
+#[test]
+fn test_log_parent_count() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=c"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(a)", "description(b)", "-m=merge"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "description(a)",
+            "description(b)",
+            "description(c)",
+            "-m=octopus",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(merge)", "-m=linear"]);
+
+    let template = r#"description.first_line() ++ " " ++ parent_count ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  linear 1
+    ○    merge 2
+    ├─╮
+    │ │ ○  octopus 3
+    ╭─┬─┤
+    │ │ ○  c 1
+    │ ○ │  b 1
+    │ ├─╯
+    ○ │  a 1
+    ├─╯
+    ◆   0
+    "###);
+}
+
 #[test]
 fn test_log_author_timestamp() {
     let test_env = TestEnvironment::default();
@@ -576,6 +576,72 @@ fn test_git_fetch_all() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_created_and_updated_revsets() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+    let source_git_repo_path = test_env.env_root().join("source");
+    let _git_repo = git2::Repository::init(source_git_repo_path.clone()).unwrap();
+    create_colocated_repo_and_branches_from_trunk1(&test_env, &source_git_repo_path);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "clone", "source", "target"]);
+    let target_jj_repo_path = test_env.env_root().join("target");
+    let get_fetched_log = |revset: &str| {
+        test_env.jj_cmd_success(
+            &target_jj_repo_path,
+            &["log", "--no-graph", "-r", revset, "-T", "description"],
+        )
+    };
+
+    // Everything is newly created by the initial fetch
+    insta::assert_snapshot!(get_fetched_log("fetch_created()"), @r###"
+    descr_for_b
+    descr_for_a2
+    descr_for_a1
+    descr_for_trunk1
+    "###);
+    insta::assert_snapshot!(get_fetched_log("fetch_updated()"), @"");
+
+    // Create one branch and update another one in the source repo
+    create_commit(&test_env, &source_git_repo_path, "c", &["trunk1"]);
+    test_env.jj_cmd_ok(
+        &source_git_repo_path,
+        &["describe", "a1", "-m", "modified descr_for_a1"],
+    );
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(get_fetched_log("fetch_created()"), @r###"
+    descr_for_c
+    "###);
+    insta::assert_snapshot!(get_fetched_log("fetch_updated()"), @r###"
+    modified descr_for_a1
+    "###);
+
+    // Fetching nothing new keeps the record, and so do unrelated operations
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["new"]);
+    insta::assert_snapshot!(get_fetched_log("fetch_created()"), @r###"
+    descr_for_c
+    "###);
+    insta::assert_snapshot!(get_fetched_log("fetch_updated()"), @r###"
+    modified descr_for_a1
+    "###);
+
+    // Deleting remote branches replaces the last fetch with an empty one
+    test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &["branch", "forget", "--include-remotes", "c"],
+    );
+    insta::assert_snapshot!(get_fetched_log("fetch_created()"), @"");
+    insta::assert_snapshot!(get_fetched_log("fetch_updated()"), @"");
+
+    // Undoing it recreates the remote branch, which counts as a fetch too
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["op", "undo"]);
+    insta::assert_snapshot!(get_fetched_log("fetch_created()"), @r###"
+    descr_for_c
+    "###);
+    insta::assert_snapshot!(get_fetched_log("fetch_updated()"), @"");
+}
+
 #[test]
 fn test_git_fetch_some_of_many_branches() {
     let test_env = TestEnvironment::default();
@@ -1218,6 +1284,95 @@ fn test_git_fetch_removed_parent_branch() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_update_branches() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = true");
+    let source_git_repo_path = test_env.env_root().join("source");
+    let _git_repo = git2::Repository::init(source_git_repo_path.clone()).unwrap();
+    test_env.jj_cmd_ok(&source_git_repo_path, &["git", "init", "--git-repo", "."]);
+    create_commit(&test_env, &source_git_repo_path, "trunk1", &[]);
+    create_commit(&test_env, &source_git_repo_path, "ff", &["trunk1"]);
+    create_commit(&test_env, &source_git_repo_path, "diverged", &["trunk1"]);
+
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "target"]);
+    let target_jj_repo_path = test_env.env_root().join("target");
+    test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &["git", "remote", "add", "origin", "../source"],
+    );
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
+
+    // Move "diverged" locally, and advance both branches on the remote
+    test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &["new", "diverged", "-m", "local_diverged"],
+    );
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["branch", "set", "diverged"]);
+    for branch in ["ff", "diverged"] {
+        test_env.jj_cmd_ok(
+            &source_git_repo_path,
+            &["new", branch, "-m", &format!("remote_{branch}")],
+        );
+        test_env.jj_cmd_ok(&source_git_repo_path, &["branch", "set", branch]);
+    }
+    insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
+    diverged: kpqxywon 71a3e5c5 (empty) local_diverged
+      @origin (behind by 1 commits): omyukunl 8b94c391 descr_for_diverged
+    ff: uopstptm 4e1eb19d descr_for_ff
+      @origin: uopstptm 4e1eb19d descr_for_ff
+    trunk1: rqlsnrsw 5db62a93 descr_for_trunk1
+      @origin: rqlsnrsw 5db62a93 descr_for_trunk1
+    "###);
+
+    // Only the fast-forwardable branch is moved
+    test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &["git", "fetch", "--update-branches=track"],
+    );
+    insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
+    diverged: kpqxywon 71a3e5c5 (empty) local_diverged
+      @origin (ahead by 1 commits, behind by 1 commits): nylqrsul cfc91ffc (empty) remote_diverged
+    ff: zlsvtusm 4951a999 (empty) remote_ff
+      @origin: zlsvtusm 4951a999 (empty) remote_ff
+    trunk1: rqlsnrsw 5db62a93 descr_for_trunk1
+      @origin: rqlsnrsw 5db62a93 descr_for_trunk1
+    "###);
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["undo"]);
+
+    // Both branches are merged, and the diverged one becomes conflicted
+    test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &["git", "fetch", "--update-branches=all"],
+    );
+    insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
+    diverged (conflicted):
+      - omyukunl 8b94c391 descr_for_diverged
+      + kpqxywon 71a3e5c5 (empty) local_diverged
+      + nylqrsul cfc91ffc (empty) remote_diverged
+      @origin (behind by 1 commits): nylqrsul cfc91ffc (empty) remote_diverged
+    ff: zlsvtusm 4951a999 (empty) remote_ff
+      @origin: zlsvtusm 4951a999 (empty) remote_ff
+    trunk1: rqlsnrsw 5db62a93 descr_for_trunk1
+      @origin: rqlsnrsw 5db62a93 descr_for_trunk1
+    "###);
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["undo"]);
+
+    // Only the remote-tracking branches are updated
+    test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &["git", "fetch", "--update-branches=none"],
+    );
+    insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
+    diverged: kpqxywon 71a3e5c5 (empty) local_diverged
+      @origin (ahead by 1 commits, behind by 1 commits): nylqrsul cfc91ffc (empty) remote_diverged
+    ff: uopstptm 4e1eb19d descr_for_ff
+      @origin (ahead by 1 commits): zlsvtusm 4951a999 (empty) remote_ff
+    trunk1: rqlsnrsw 5db62a93 descr_for_trunk1
+      @origin: rqlsnrsw 5db62a93 descr_for_trunk1
+    "###);
+}
+
 #[test]
 fn test_git_fetch_remote_only_branch() {
     let test_env = TestEnvironment::default();
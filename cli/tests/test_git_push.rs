@@ -15,6 +15,8 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use crate::common::get_stderr_string;
+use crate::common::get_stdout_string;
 use crate::common::TestEnvironment;
 
 fn set_up() -> (TestEnvironment, PathBuf) {
@@ -140,6 +142,50 @@ fn test_git_push_current_branch() {
     "###);
 }
 
+#[test]
+fn test_git_push_template() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["describe", "branch1", "-m", "modified branch1 commit"],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch2", "-m", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my-branch"]);
+    let template = r#"
+        separate(" ", branch, direction, old_commit_id.substr(0, 12), new_commit_id.substr(0, 12))
+        ++ "\n"
+    "#;
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--all", "--dry-run", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+    branch1 sideways d13ecdbda2a2 96cc58bf409f
+    branch2 forward 8476341eb395 8158993f300f
+    my-branch 8158993f300f
+    Dry-run requested, not pushing.
+    "###);
+
+    // The template is parsed before anything is printed
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--all", "--dry-run", "-T", "unknown"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse template: Keyword "unknown" doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | unknown
+      | ^-----^
+      |
+      = Keyword "unknown" doesn't exist
+    "###);
+}
+
 #[test]
 fn test_git_push_parent_branch() {
     let (test_env, workspace_root) = set_up();
@@ -159,6 +205,112 @@ fn test_git_push_parent_branch() {
     "###);
 }
 
+#[test]
+fn test_git_push_only_fast_forward() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+    // Move branch1 sideways, branch2 forward, and add a new branch
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["describe", "branch1", "-m", "modified branch1 commit"],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch2", "-m", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my-branch"]);
+
+    // Nothing is pushed if any of the branches isn't fast-forwarded
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--all", "--only-fast-forward"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to push non-fast-forward update to branch branch1
+    Hint: Rebase the changes onto the remote branches, or push without --only-fast-forward.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1: xtvrqkyv 96cc58bf (empty) modified branch1 commit
+      @origin (ahead by 1 commits, behind by 1 commits): xtvrqkyv hidden d13ecdbd (empty) description 1
+    branch2: yostqsxw 8158993f (empty) foo
+      @origin (behind by 1 commits): rlzusymt 8476341e (empty) description 2
+    my-branch: yostqsxw 8158993f (empty) foo
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "-bbranch2",
+            "-bmy-branch",
+            "--only-fast-forward",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move forward branch branch2 from 8476341eb395 to 8158993f300f
+      Add branch my-branch to 8158993f300f
+    "###);
+}
+
+#[test]
+fn test_git_push_create_only_update_only() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch2", "-m", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my-branch"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "other-branch"]);
+
+    // Every branch that already exists on the remote is reported
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "-bbranch2", "-bmy-branch", "--create-only"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to update existing branch branch2 on origin with --create-only
+    "###);
+
+    // Every branch that doesn't exist on the remote is reported
+    let stderr =
+        test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all", "--update-only"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to create new branches my-branch, other-branch on origin with --update-only
+    "###);
+
+    // The flags can't be combined
+    let stderr = test_env.jj_cmd_cli_error(
+        &workspace_root,
+        &["git", "push", "--all", "--create-only", "--update-only"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--create-only' cannot be used with '--update-only'
+
+    Usage: jj git push --all --create-only
+
+    For more information, try '--help'.
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "-bmy-branch", "--create-only"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Add branch my-branch to 0b9c82ae62cb
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "-bbranch2", "--update-only"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move forward branch branch2 from 8476341eb395 to 0b9c82ae62cb
+    "###);
+}
+
 #[test]
 fn test_git_push_no_matching_branch() {
     let (test_env, workspace_root) = set_up();
@@ -266,6 +418,174 @@ fn test_git_push_forward_unexpectedly_moved() {
     "###);
 }
 
+#[test]
+fn test_git_push_force_with_lease() {
+    let (test_env, workspace_root) = set_up();
+    let origin_path = test_env.env_root().join("origin");
+
+    // Create two commits on top of branch1 locally, and make the remote know
+    // about the first one.
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch1", "-m=first"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "tmp"]);
+    test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--branch=tmp"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m=second"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch1"]);
+
+    // Move branch1 forward to the first commit on the remote
+    test_env.jj_cmd_ok(&origin_path, &["git", "import"]);
+    test_env.jj_cmd_ok(&origin_path, &["branch", "set", "branch1", "-r=tmp"]);
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+
+    // Pushing fails if the lease is enforced
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--branch=branch1", "--force-with-lease"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move forward branch branch1 from d13ecdbda2a2 to 3f2a46fd3e0d
+    Error: Refusing to push a branch that moved on the remote since it was last fetched. Affected refs: refs/heads/branch1
+    Hint: Try fetching from the remote, then make the branch point to where you want it to be, and push again.
+    "###);
+
+    // Without the lease, the push is allowed since fetching would resolve to
+    // the pushed commit
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--branch=branch1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move forward branch branch1 from d13ecdbda2a2 to 3f2a46fd3e0d
+    "###);
+}
+
+#[test]
+fn test_git_push_keep_going() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+
+    // Move branch1 sideways on the remote so pushing it gets rejected
+    let origin_path = test_env.env_root().join("origin");
+    test_env.jj_cmd_ok(&origin_path, &["new", "branch1", "-m=remote"]);
+    std::fs::write(origin_path.join("remote"), "remote").unwrap();
+    test_env.jj_cmd_ok(&origin_path, &["branch", "set", "branch1"]);
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+
+    // Update both branches locally
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["describe", "branch1", "-m", "modified branch1 commit"],
+    );
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["describe", "branch2", "-m", "modified branch2 commit"],
+    );
+
+    // branch2 is still pushed even though branch1 is rejected
+    let stderr =
+        test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all", "--keep-going"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move sideways branch branch1 from d13ecdbda2a2 to 26e3a41e04a1
+      Move sideways branch branch2 from 8476341eb395 to a00a9e739117
+    Push results:
+      branch1: failed (Refs in unexpected location: ["refs/heads/branch1"])
+      branch2: pushed
+    Error: Failed to push branch branch1
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1: xtvrqkyv 26e3a41e (empty) modified branch1 commit
+      @origin (ahead by 1 commits, behind by 1 commits): xtvrqkyv hidden d13ecdbd (empty) description 1
+    branch2: rlzusymt a00a9e73 (empty) modified branch2 commit
+      @origin: rlzusymt a00a9e73 (empty) modified branch2 commit
+    "###);
+
+    // Explicitly requested branches that can't be pushed are reported too
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--keep-going", "-b=branch1", "-b=branch2"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Branch branch2@origin already matches branch2
+    Branch changes to push to origin:
+      Move sideways branch branch1 from d13ecdbda2a2 to 26e3a41e04a1
+    Push results:
+      branch1: failed (Refs in unexpected location: ["refs/heads/branch1"])
+    Error: Failed to push branch branch1
+    "###);
+}
+
+#[test]
+fn test_git_push_set_upstream() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "untrack", "branch1@origin"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch1", "-m", "foo"]);
+    std::fs::write(workspace_root.join("foo"), "foo").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch1"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "feature"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1: yostqsxw ff036a36 foo
+    branch1@origin: xtvrqkyv d13ecdbd (empty) description 1
+    branch2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    feature: yostqsxw ff036a36 foo
+    "###);
+
+    // A branch can't be pushed if the remote branch isn't tracked
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "-b=branch1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Non-tracking remote branch branch1@origin exists
+    Hint: Run `jj branch track branch1@origin` to import the remote branch.
+    "###);
+
+    // The untracked remote branch starts being tracked, the branch that's
+    // already tracked is left as is, and the new branch is tracked by the push
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--set-upstream",
+            "-b=branch1",
+            "-b=branch2",
+            "-b=feature",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Tracking remote branch branch1@origin
+    Remote branch already tracked: branch2@origin
+    Branch branch2@origin already matches branch2
+    Branch changes to push to origin:
+      Move forward branch branch1 from d13ecdbda2a2 to ff036a362ee0
+      Add branch feature to ff036a362ee0
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1: yostqsxw ff036a36 foo
+      @origin: yostqsxw ff036a36 foo
+    branch2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    feature: yostqsxw ff036a36 foo
+      @origin: yostqsxw ff036a36 foo
+    "###);
+
+    // It can't be used with the options that select the branches by their
+    // state
+    let stderr = test_env.jj_cmd_cli_error(
+        &workspace_root,
+        &["git", "push", "--set-upstream", "--deleted"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--set-upstream' cannot be used with:
+      --all
+      --deleted
+      --tracked
+
+    Usage: jj git push --set-upstream --deleted
+
+    For more information, try '--help'.
+    "###);
+}
+
 #[test]
 fn test_git_push_sideways_unexpectedly_moved() {
     let (test_env, workspace_root) = set_up();
@@ -304,6 +624,198 @@ fn test_git_push_sideways_unexpectedly_moved() {
     Error: Refusing to push a branch that unexpectedly moved on the remote. Affected refs: refs/heads/branch1
     Hint: Try fetching from the remote, then make the branch point to where you want it to be, and push again.
     "###);
+
+    // The retry doesn't force-push over the fetched remote branch
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--retry-on-conflict"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move sideways branch branch1 from d13ecdbda2a2 to 0f8bf988588e
+    Fetching branch branch1 from origin to retry the push
+    branch: branch1@origin [updated] tracked
+    Abandoned 1 commits that are no longer reachable.
+    Error: Not retrying the push since branch branch1 can't be fast-forwarded from where it moved on the remote
+    Hint: Make the branch point to where you want it to be, and push again.
+    "###);
+}
+
+#[test]
+fn test_git_push_sideways_stale_remote_tracking_branch() {
+    let (test_env, workspace_root) = set_up();
+    let origin_path = test_env.env_root().join("origin");
+    let origin_git_repo_path = origin_path
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+
+    // Track branch1 on another remote backed by the same repo
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "remote",
+            "add",
+            "upstream",
+            origin_git_repo_path.to_str().unwrap(),
+        ],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["git", "fetch", "--remote=upstream"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "track", "branch1@upstream"]);
+
+    // Move branch1 sideways on the remote
+    test_env.jj_cmd_ok(&origin_path, &["new", "root()", "-m=remote"]);
+    std::fs::write(origin_path.join("remote"), "remote").unwrap();
+    test_env.jj_cmd_ok(
+        &origin_path,
+        &["branch", "set", "branch1", "--allow-backwards"],
+    );
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+
+    // Fetching from the other remote moves the local branch, but leaves
+    // branch1@origin behind
+    test_env.jj_cmd_ok(&workspace_root, &["git", "fetch", "--remote=upstream"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1: plqpnqut fb89d4e4 remote
+      @origin (ahead by 1 commits, behind by 1 commits): xtvrqkyv hidden d13ecdbd (empty) description 1
+      @upstream: plqpnqut fb89d4e4 remote
+    branch2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    branch2@upstream: rlzusymt 8476341e (empty) description 2
+    "###);
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "-bbranch1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move sideways branch branch1 from d13ecdbda2a2 to fb89d4e41c8f
+    Hint: Not fast-forwarding branch branch1 on origin. If the remote was updated since the last fetch, run `jj git fetch --remote origin` first.
+    "###);
+}
+
+#[test]
+fn test_git_push_force_branch() {
+    let (test_env, workspace_root) = set_up();
+
+    // Move both branches forward on the remote
+    let origin_path = test_env.env_root().join("origin");
+    for branch in ["branch1", "branch2"] {
+        test_env.jj_cmd_ok(
+            &origin_path,
+            &["new", branch, &format!("-m=remote {branch}")],
+        );
+        test_env.jj_cmd_ok(&origin_path, &["branch", "set", branch]);
+    }
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+
+    // Move both branches sideways locally
+    for branch in ["branch1", "branch2"] {
+        test_env.jj_cmd_ok(
+            &workspace_root,
+            &["new", "root()", &format!("-m=local {branch}")],
+        );
+        test_env.jj_cmd_ok(
+            &workspace_root,
+            &["branch", "set", branch, "--allow-backwards"],
+        );
+    }
+
+    // The forced branch must be pushed
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--all", "--force-branch=branch3"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch branch3 passed to --force-branch is not being pushed
+    Hint: Select the branch with --branch, or leave out --force-branch.
+    "###);
+
+    // Only the forced branch is exempt from the check
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--all", "--force-branch=branch1"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move sideways branch branch1 from d13ecdbda2a2 to be5096d2919e
+      Move sideways branch branch2 from 8476341eb395 to b5dfaa02a082
+    Error: Refusing to push a branch that unexpectedly moved on the remote. Affected refs: refs/heads/branch2
+    Hint: Try fetching from the remote, then make the branch point to where you want it to be, and push again.
+    "###);
+
+    // The forced branch is also exempt from --only-fast-forward
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--branch=branch1",
+            "--force-branch=branch1",
+            "--only-fast-forward",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move sideways branch branch1 from d13ecdbda2a2 to be5096d2919e
+    "###);
+    test_env.jj_cmd_ok(&origin_path, &["git", "import"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &origin_path), @r###"
+    branch1: tnvmopkw be5096d2 (empty) local branch1
+      @git: tnvmopkw be5096d2 (empty) local branch1
+    branch2: znkkpsqq f7d12b23 (empty) remote branch2
+      @git: znkkpsqq f7d12b23 (empty) remote branch2
+    "###);
+}
+
+#[test]
+fn test_git_push_retry_on_conflict() {
+    let (test_env, workspace_root) = set_up();
+    let origin_path = test_env.env_root().join("origin");
+
+    // Push a descendant of branch1 as another branch
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch1", "-m=middle"]);
+    std::fs::write(workspace_root.join("middle"), "middle").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "middle"]);
+    test_env.jj_cmd_ok(&workspace_root, &["git", "push", "-b=middle"]);
+
+    // Move branch1 forward to that branch on the remote
+    test_env.jj_cmd_ok(&origin_path, &["git", "import"]);
+    test_env.jj_cmd_ok(&origin_path, &["branch", "set", "branch1", "-r=middle"]);
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+
+    // Move branch1 forward locally without fetching
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m=top"]);
+    std::fs::write(workspace_root.join("top"), "top").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch1"]);
+
+    // The lease is broken, but branch1 can be fast-forwarded after fetching
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "-b=branch1",
+            "--force-with-lease",
+            "--retry-on-conflict",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::with_settings!({filters => vec![(r"\b[0-9a-f]{12}\b", "[hash]")]}, {
+        insta::assert_snapshot!(stderr, @r###"
+        Branch changes to push to origin:
+          Move forward branch branch1 from [hash] to [hash]
+        Fetching branch branch1 from origin to retry the push
+        branch: branch1@origin [updated] tracked
+          Move forward branch branch1 from [hash] to [hash]
+        Retried push succeeded.
+        "###);
+    });
+    let stdout = test_env.jj_cmd_success(
+        &workspace_root,
+        &["log", "-r=branch1@origin", "--no-graph", "-T=description"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    top
+    "###);
 }
 
 // This tests whether the push checks that the remote branches are in expected
@@ -403,7 +915,10 @@ fn test_git_push_creation_unexpectedly_already_exists() {
     let (test_env, workspace_root) = set_up();
 
     // Forget branch1 locally
-    test_env.jj_cmd_ok(&workspace_root, &["branch", "forget", "branch1"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["branch", "forget", "--include-remotes", "branch1"],
+    );
 
     // Create a new branh1
     test_env.jj_cmd_ok(&workspace_root, &["new", "root()", "-m=new branch1"]);
@@ -570,6 +1085,117 @@ fn test_git_push_multiple() {
     "###);
 }
 
+#[test]
+fn test_git_push_multiple_remotes() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "backup"]);
+    let backup_git_repo_path = test_env.env_root().join("backup/.jj/repo/store/git");
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "remote",
+            "add",
+            "backup",
+            backup_git_repo_path.to_str().unwrap(),
+        ],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my-branch"]);
+
+    // Each remote's updates are shown
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "-bmy-branch",
+            "-bbranch1",
+            "--remote=origin",
+            "--remote=backup",
+            "--dry-run",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch branch1@origin already matches branch1
+    Branch changes to push to origin:
+      Add branch my-branch to b0f827bb35de
+    Dry-run requested, not pushing.
+    Branch changes to push to backup:
+      Add branch my-branch to b0f827bb35de
+      Add branch branch1 to d13ecdbda2a2
+    Dry-run requested, not pushing.
+    Push results by remote:
+      origin: ok
+      backup: ok
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "-bmy-branch",
+            "--remote=origin",
+            "--remote=backup",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Add branch my-branch to b0f827bb35de
+    Branch changes to push to backup:
+      Add branch my-branch to b0f827bb35de
+    Push results by remote:
+      origin: ok
+      backup: ok
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1: xtvrqkyv d13ecdbd (empty) description 1
+      @origin: xtvrqkyv d13ecdbd (empty) description 1
+    branch2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    my-branch: yqosqzyt b0f827bb (empty) foo
+      @backup: yqosqzyt b0f827bb (empty) foo
+      @origin: yqosqzyt b0f827bb (empty) foo
+    "###);
+
+    // A failure on one remote doesn't prevent pushing to the others
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "bar"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "my-branch"]);
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "-bmy-branch",
+            "--remote=nonexistent",
+            "--remote=backup",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to nonexistent:
+      Add branch my-branch to 568be6a78c4e
+    Warning: Failed to push to nonexistent: No git remote named 'nonexistent'
+    Branch changes to push to backup:
+      Move forward branch my-branch from b0f827bb35de to 568be6a78c4e
+    Push results by remote:
+      nonexistent: failed
+      backup: ok
+    Error: Failed to push to nonexistent
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1: xtvrqkyv d13ecdbd (empty) description 1
+      @origin: xtvrqkyv d13ecdbd (empty) description 1
+    branch2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    my-branch: kxryzmor 568be6a7 (empty) bar
+      @backup: kxryzmor 568be6a7 (empty) bar
+      @origin (behind by 1 commits): yqosqzyt b0f827bb (empty) foo
+    "###);
+}
+
 #[test]
 fn test_git_push_changes() {
     let (test_env, workspace_root) = set_up();
@@ -680,6 +1306,127 @@ fn test_git_push_changes() {
     "###);
 }
 
+#[test]
+fn test_git_push_changes_with_prefix() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "foo"]);
+    std::fs::write(workspace_root.join("file"), "contents").unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--change=@", "--change-prefix=alice/push-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Creating branch alice/push-yqosqzytrlsw for revision yqosqzytrlsw
+    Branch changes to push to origin:
+      Add branch alice/push-yqosqzytrlsw to a050abf4ff07
+    "###);
+
+    // Pushing the same change again reuses the branch
+    std::fs::write(workspace_root.join("file"), "modified").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--change=@", "--change-prefix=alice/push-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move sideways branch alice/push-yqosqzytrlsw from a050abf4ff07 to 60a78ef9950f
+    "###);
+
+    // A branch of the same name pointing to another change isn't taken over.
+    // The full change ID is used instead.
+    test_env.jj_cmd_ok(&workspace_root, &["new", "root()", "-m", "other"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["branch", "create", "bob/push-yqosqzytrlsw"],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["edit", "description(foo)"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--change=@", "--change-prefix=bob/push-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Creating branch bob/push-19b790168e73f7a73a98deae21e807c0 for revision yqosqzytrlsw
+    Branch changes to push to origin:
+      Add branch bob/push-19b790168e73f7a73a98deae21e807c0 to 60a78ef9950f
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--change=@", "--change-prefix=bob/push-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch bob/push-19b790168e73f7a73a98deae21e807c0@origin already matches bob/push-19b790168e73f7a73a98deae21e807c0
+    Nothing changed.
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &workspace_root,
+        &["git", "push", "--change-prefix=alice/push-"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the following required arguments were not provided:
+      --change <CHANGE>
+
+    Usage: jj git push --change <CHANGE> --change-prefix <PREFIX>
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_git_push_descendants_of() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch1", "-m", "below"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "below"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "base"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "above 1"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "above-1"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["new", "description(base)", "-m", "above 2"],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "above-2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch2", "-m", "other"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "other"]);
+
+    // Only the branches on descendants of the base are pushed
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--descendants-of=description(base)"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Add branch above-1 to 73cac1b26983
+      Add branch above-2 to 39893a75d43c
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    above-1: kpqxywon 73cac1b2 (empty) above 1
+      @origin: kpqxywon 73cac1b2 (empty) above 1
+    above-2: wqnwkozp 39893a75 (empty) above 2
+      @origin: wqnwkozp 39893a75 (empty) above 2
+    below: vruxwmqv 85cdc07f (empty) below
+    branch1: xtvrqkyv d13ecdbd (empty) description 1
+      @origin: xtvrqkyv d13ecdbd (empty) description 1
+    branch2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    other: kxryzmor 2bc57b0e (empty) other
+    "###);
+
+    // Warns if there are no branches on the descendants
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--descendants-of=none()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: No branches point to descendants of the specified revisions: none()
+    Nothing changed.
+    "###);
+}
+
 #[test]
 fn test_git_push_revisions() {
     let (test_env, workspace_root) = set_up();
@@ -845,6 +1592,89 @@ fn test_git_push_no_description() {
     );
 }
 
+#[test]
+fn test_git_push_skips_branches_without_description() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch1", "-m="]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch1"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "branch2", "-m", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch2"]);
+
+    // The branch with the undescribed commit is skipped
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--all", "--dry-run"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Skipping branch branch1 with commits that have no description
+    Hint: Describe the commits, or use --allow-empty-description to push them anyway.
+    Branch changes to push to origin:
+      Move forward branch branch2 from 8476341eb395 to 74d0ad862df7
+    Dry-run requested, not pushing.
+    "###);
+
+    // With --strict, nothing is pushed
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--all", "--dry-run", "--strict"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Won't push commit 39250061290e since it has no description
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--all",
+            "--dry-run",
+            "--allow-empty-description",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Move forward branch branch1 from d13ecdbda2a2 to 39250061290e
+      Move forward branch branch2 from 8476341eb395 to 74d0ad862df7
+    Dry-run requested, not pushing.
+    "###);
+}
+
+#[test]
+fn test_git_push_reports_all_unready_commits() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"git.private-commits = "description(glob:'private*')""#);
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m="]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "private 1"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m="]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my-branch"]);
+
+    // All the commits in the push range are reported, also with --dry-run
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--branch=my-branch", "--dry-run"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: The following commits are not ready to be pushed:
+      2c8beb0efb8b: it has no description
+      db61437c5ff1: it is private
+      5b36783cd11c: it has no description
+    Error: Won't push 3 commits
+    "###);
+
+    // The flags exclude the corresponding reasons from the report
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--branch=my-branch", "--allow-private"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: The following commits are not ready to be pushed:
+      2c8beb0efb8b: it has no description
+      5b36783cd11c: it has no description
+    Error: Won't push 2 commits
+    "###);
+}
+
 #[test]
 fn test_git_push_no_description_in_immutable() {
     let (test_env, workspace_root) = set_up();
@@ -925,7 +1755,10 @@ fn test_git_push_missing_author_in_immutable() {
         &["git", "push", "--branch=my-branch", "--dry-run"],
     );
     insta::assert_snapshot!(stderr, @r###"
-    Error: Won't push commit 011f740bf8b5 since it has no author and/or committer set
+    Warning: The following commits are not ready to be pushed:
+      011f740bf8b5: it has no author and/or committer set
+      ef0b22ec1d8b: it has no author and/or committer set
+    Error: Won't push 2 commits
     "###);
 
     test_env.add_config(r#"revset-aliases."immutable_heads()" = "imm""#);
@@ -1000,7 +1833,10 @@ fn test_git_push_missing_committer_in_immutable() {
         &["git", "push", "--branch=my-branch", "--dry-run"],
     );
     insta::assert_snapshot!(stderr, @r###"
-    Error: Won't push commit 7e61dc727a8f since it has no author and/or committer set
+    Warning: The following commits are not ready to be pushed:
+      7e61dc727a8f: it has no author and/or committer set
+      b914de13e54f: it has no author and/or committer set
+    Error: Won't push 2 commits
     "###);
 
     test_env.add_config(r#"revset-aliases."immutable_heads()" = "imm""#);
@@ -1044,6 +1880,119 @@ fn test_git_push_deleted() {
     "###);
 }
 
+#[test]
+fn test_git_push_prune() {
+    let (test_env, workspace_root) = set_up();
+
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "delete", "branch1", "branch2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "untrack", "branch2@origin"]);
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my-branch"]);
+
+    // The deletion is shown with --dry-run, along with the non-tracking remote
+    // branch that can't be deleted
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--prune", "--branch=my-branch", "--dry-run"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Non-tracking remote branch branch2@origin exists
+    Hint: Run `jj branch track branch2@origin` to import the remote branch.
+    Branch changes to push to origin:
+      Add branch my-branch to b0f827bb35de
+      Delete branch branch1 from d13ecdbda2a2
+    Dry-run requested, not pushing.
+    "###);
+
+    // Declining the prompt pushes nothing
+    let assert = test_env
+        .jj_cmd_stdin(
+            &workspace_root,
+            &["git", "push", "--prune", "--branch=my-branch"],
+            "n\n",
+        )
+        .assert()
+        .code(1);
+    let stdout = test_env.normalize_output(&get_stdout_string(&assert));
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stdout, @r###"
+    Delete branch branch1 from origin? (yN):
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Non-tracking remote branch branch2@origin exists
+    Hint: Run `jj branch track branch2@origin` to import the remote branch.
+    Branch changes to push to origin:
+      Add branch my-branch to b0f827bb35de
+      Delete branch branch1 from d13ecdbda2a2
+    Error: Aborted, nothing was pushed
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch1 (deleted)
+      @origin: xtvrqkyv d13ecdbd (empty) description 1
+    branch2@origin: rlzusymt 8476341e (empty) description 2
+    my-branch: yqosqzyt b0f827bb (empty) foo
+    "###);
+
+    // Without a terminal, the deletion has to be confirmed with --yes
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--prune", "--branch=my-branch"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Non-tracking remote branch branch2@origin exists
+    Hint: Run `jj branch track branch2@origin` to import the remote branch.
+    Branch changes to push to origin:
+      Add branch my-branch to b0f827bb35de
+      Delete branch branch1 from d13ecdbda2a2
+    Error: Refusing to delete branch branch1 from origin without confirmation
+    Hint: Use --yes to delete them when not running in a terminal.
+    "###);
+
+    // Confirming the prompt deletes the tracked remote branch
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(
+        &workspace_root,
+        &["git", "push", "--prune", "--branch=my-branch"],
+        "y\n",
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Delete branch branch1 from origin? (yN):
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Non-tracking remote branch branch2@origin exists
+    Hint: Run `jj branch track branch2@origin` to import the remote branch.
+    Branch changes to push to origin:
+      Add branch my-branch to b0f827bb35de
+      Delete branch branch1 from d13ecdbda2a2
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch2@origin: rlzusymt 8476341e (empty) description 2
+    my-branch: yqosqzyt b0f827bb (empty) foo
+      @origin: yqosqzyt b0f827bb (empty) foo
+    "###);
+
+    // --yes deletes the remote branch without a terminal
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "delete", "my-branch"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "other-branch"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--prune", "--yes", "--branch=other-branch"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Non-tracking remote branch branch2@origin exists
+    Hint: Run `jj branch track branch2@origin` to import the remote branch.
+    Branch changes to push to origin:
+      Add branch other-branch to b0f827bb35de
+      Delete branch my-branch from b0f827bb35de
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &workspace_root), @r###"
+    branch2@origin: rlzusymt 8476341e (empty) description 2
+    other-branch: yqosqzyt b0f827bb (empty) foo
+      @origin: yqosqzyt b0f827bb (empty) foo
+    "###);
+}
+
 #[test]
 fn test_git_push_conflicting_branches() {
     let (test_env, workspace_root) = set_up();
@@ -781,6 +781,159 @@ fn test_log_reversed() {
     "###);
 }
 
+#[test]
+fn test_log_sorted() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "second"]);
+    // Rewriting "first" makes it the newest by committer date, but not by
+    // author date
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-r", "description(first)", "-m", "first edited"],
+    );
+
+    let template = "description";
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", template, "--no-graph", "-r", "sorted(~root())"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    first edited
+    second
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            template,
+            "--no-graph",
+            "-r",
+            "sorted(~root(), author_date)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    second
+    first edited
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            template,
+            "--no-graph",
+            "--reversed",
+            "-r",
+            "sorted(~root(), author_date)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    first edited
+    second
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            r#"commit_id.short() ++ "\n""#,
+            "--no-graph",
+            "-r",
+            "sorted(~root(), commit_id)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    79c0a4cad213
+    c66e4ef294ba
+    "###);
+
+    // The graph can't show the sorted order
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r", "sorted(~root())"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: sorted() can't be used with the graph
+    Hint: Use --no-graph to list the commits in the sorted order.
+    "###);
+
+    // The order would be lost if sorted() were combined with other sets
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "--no-graph", "-r", "sorted(~root()) | root()"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse revset: sorted() can only be used as the outermost function
+    Caused by:  --> 1:1
+      |
+    1 | sorted(~root()) | root()
+      | ^----------------------^
+      |
+      = sorted() can only be used as the outermost function
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "--no-graph", "-r", "heads(sorted(all(), commit_id))"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse revset: sorted() can only be used as the outermost function
+    Caused by:  --> 1:1
+      |
+    1 | heads(sorted(all(), commit_id))
+      | ^-----------------------------^
+      |
+      = sorted() can only be used as the outermost function
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "--no-graph", "-r", "sorted(~root())", "file"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: sorted() can only be used as the outermost function
+    "###);
+}
+
+#[test]
+fn test_log_reverse_alias_with_merge() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "left"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "right"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "description(left)",
+            "description(right)",
+            "-m",
+            "merge",
+        ],
+    );
+
+    // `--reverse` is the same as `--reversed`, and the fork and merge edges
+    // are drawn upside down
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--reverse"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ◆
+    ○    base
+    ├─╮
+    │ ○  left
+    ○ │  right
+    ├─╯
+    @  merge
+    "###);
+    assert_eq!(
+        stdout,
+        test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--reversed"])
+    );
+}
+
 #[test]
 fn test_log_filtered_by_path() {
     let test_env = TestEnvironment::default();
@@ -168,6 +168,83 @@ fn test_op_log_limit() {
     "###);
 }
 
+#[test]
+fn test_op_log_change() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    let change_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tchange_id"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "@-", "-m", "first again"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "second again"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  describe commit bdc35086ba62681076602a798b307be9afedd079
+    ○  describe commit fa15625b4a986997697639dfc2844138900c79f2
+    ○  new empty commit
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ○  add workspace 'default'
+    ○  initialize repo
+    ○
+    "###);
+
+    // Operations not touching the change are elided
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "-Tdescription", "--change", &change_id[..12]],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ○  describe commit fa15625b4a986997697639dfc2844138900c79f2
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ○  add workspace 'default'
+    "###);
+
+    // The limit applies to the matching operations
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "-Tdescription",
+            "--limit=2",
+            "--change",
+            &change_id[..12],
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ○  describe commit fa15625b4a986997697639dfc2844138900c79f2
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    "###);
+
+    // The full change ID can be used after the change was abandoned
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "@-"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "-Tdescription ++ \"\\n\"",
+            "--no-graph",
+            "--limit=2",
+            "--change",
+            &change_id,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    abandon commit b4f7a3233f7e85d85b5e6daf6f4b0b33495539c2
+    describe commit fa15625b4a986997697639dfc2844138900c79f2
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "--change", &change_id[..12]]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such change: qpvuntsmwlqt
+    "###);
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -586,6 +663,45 @@ fn test_op_abandon_multiple_heads() {
     "###);
 }
 
+#[test]
+fn test_op_restore_create_branch() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    let base_op_id = test_env.current_operation_id(&repo_path);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["op", "restore", &base_op_id, "--create-branch", "backup"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created branch backup pointing to zsuskuln 3dc07d5e backup | (empty) second
+    Working copy now at: qpvuntsm fa15625b (empty) first
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+    let template = r#"description.first_line() ++ " " ++ branches"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    ○  second backup
+    @  first
+    ◆
+    "###);
+
+    // The backup branch can't be overwritten
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["op", "restore", &base_op_id, "--create-branch", "backup"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch already exists: backup
+    Hint: Use a different name for the backup branch.
+    "###);
+}
+
 #[test]
 fn test_op_recover_from_bad_gc() {
     let test_env = TestEnvironment::default();
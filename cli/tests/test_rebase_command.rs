@@ -41,9 +41,9 @@ fn test_rebase_invalid() {
     let stderr = test_env.jj_cmd_cli_error(&repo_path, &["rebase"]);
     insta::assert_snapshot!(stderr, @r###"
     error: the following required arguments were not provided:
-      <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+      <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
-    Usage: jj rebase <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -54,7 +54,7 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(stderr, @r###"
     error: the argument '--revisions <REVISIONS>' cannot be used with '--source <SOURCE>'
 
-    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -65,7 +65,7 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(stderr, @r###"
     error: the argument '--branch <BRANCH>' cannot be used with '--source <SOURCE>'
 
-    Usage: jj rebase --branch <BRANCH> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase --branch <BRANCH> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -78,7 +78,7 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(stderr, @r###"
     error: the argument '--revisions <REVISIONS>' cannot be used with '--skip-empty'
 
-    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -91,7 +91,7 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(stderr, @r###"
     error: the argument '--destination <DESTINATION>' cannot be used with '--insert-after <INSERT_AFTER>'
 
-    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -101,17 +101,7 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(stderr, @r###"
     error: the argument '--source <SOURCE>' cannot be used with '--insert-after <INSERT_AFTER>'
 
-    Usage: jj rebase --source <SOURCE> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
-
-    For more information, try '--help'.
-    "###);
-
-    // -b with --after
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["rebase", "-b", "a", "--after", "b"]);
-    insta::assert_snapshot!(stderr, @r###"
-    error: the argument '--branch <BRANCH>' cannot be used with '--insert-after <INSERT_AFTER>'
-
-    Usage: jj rebase --branch <BRANCH> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase --source <SOURCE> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -124,7 +114,7 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(stderr, @r###"
     error: the argument '--destination <DESTINATION>' cannot be used with '--insert-before <INSERT_BEFORE>'
 
-    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -134,17 +124,7 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(stderr, @r###"
     error: the argument '--source <SOURCE>' cannot be used with '--insert-before <INSERT_BEFORE>'
 
-    Usage: jj rebase --source <SOURCE> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
-
-    For more information, try '--help'.
-    "###);
-
-    // -b with --before
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["rebase", "-b", "a", "--before", "b"]);
-    insta::assert_snapshot!(stderr, @r###"
-    error: the argument '--branch <BRANCH>' cannot be used with '--insert-before <INSERT_BEFORE>'
-
-    Usage: jj rebase --branch <BRANCH> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>>
+    Usage: jj rebase --source <SOURCE> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
 
     For more information, try '--help'.
     "###);
@@ -633,6 +613,123 @@ fn test_rebase_multiple_revisions() {
     "###);
 }
 
+#[test]
+fn test_rebase_retain_merge_parents_outside_set() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["a"]);
+    create_commit(&test_env, &repo_path, "d", &["b", "c"]);
+    create_commit(&test_env, &repo_path, "e", &["a"]);
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  e: a
+    │ ○    d: b c
+    │ ├─╮
+    │ │ ○  c: a
+    ├───╯
+    │ ○  b: a
+    ├─╯
+    ○  a
+    ◆
+    "###);
+
+    // By default, the merge "d" keeps its parent "b", which is outside the
+    // target set
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-r", "c::d", "-d", "e"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 commits onto destination
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○    d: b c
+    ├─╮
+    │ ○  c: e
+    │ @  e: a
+    ○ │  b: a
+    ├─╯
+    ○  a
+    ◆
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-r",
+            "c::d",
+            "-d",
+            "e",
+            "--retain-merge-parents-outside-set=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 commits onto destination
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○    d: b c
+    ├─╮
+    │ ○  c: e
+    │ @  e: a
+    ○ │  b: a
+    ├─╯
+    ○  a
+    ◆
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // The parent outside the target set can be dropped
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-r",
+            "c::d",
+            "-d",
+            "e",
+            "--retain-merge-parents-outside-set=false",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 commits onto destination
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  d: c
+    ○  c: e
+    @  e: a
+    │ ○  b: a
+    ├─╯
+    ○  a
+    ◆
+    "###);
+
+    // Only works with -r
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &[
+            "rebase",
+            "-s",
+            "c",
+            "-d",
+            "e",
+            "--retain-merge-parents-outside-set=false",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--source <SOURCE>' cannot be used with '--retain-merge-parents-outside-set <BOOL>'
+
+    Usage: jj rebase --source <SOURCE> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
+
+    For more information, try '--help'.
+    "###);
+}
+
 #[test]
 fn test_rebase_revision_onto_descendant() {
     let test_env = TestEnvironment::default();
@@ -904,19 +1001,22 @@ fn test_rebase_with_descendants() {
 
     // Same test as above, but with multiple commits per argument
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-s=b|d", "-d=a"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=b|d", "-d=a"]);
+    insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Error: Revset "b|d" resolved to more than one revision
-    Hint: The revset "b|d" resolved to these revisions:
-      vruxwmqv df54a9fd d | d
-      zsuskuln d370aee1 b | b
-    Hint: Prefix the expression with 'all:' to allow any number of revisions (i.e. 'all:b|d').
+    Rebased 3 commits
+    Working copy now at: vruxwmqv 1fa4ba97 d | d
+    Parent commit      : rlvkpnrz 2443ea76 a | a
+    Added 0 files, modified 0 files, removed 2 files
     "###);
+
+    // The 'all:' prefix is still accepted
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=all:b|d", "-d=a"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
     Rebased 3 commits
-    Working copy now at: vruxwmqv d17539f7 d | d
+    Working copy now at: vruxwmqv b3664c1c d | d
     Parent commit      : rlvkpnrz 2443ea76 a | a
     Added 0 files, modified 0 files, removed 2 files
     "###);
@@ -932,6 +1032,93 @@ fn test_rebase_with_descendants() {
     "###);
 }
 
+#[test]
+fn test_rebase_with_nested_sources() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &["c"]);
+    create_commit(&test_env, &repo_path, "e", &["d"]);
+    create_commit(&test_env, &repo_path, "z", &[]);
+    let setup_opid = test_env.current_operation_id(&repo_path);
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  z
+    │ ○  e: d
+    │ ○  d: c
+    │ ○  c: b
+    │ ○  b: a
+    │ ○  a
+    ├─╯
+    ◆
+    "###);
+
+    // `d` is a descendant of `b`. Both become children of `z`, and `d` is
+    // detached from `c`. The order of the arguments doesn't matter.
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=d", "-s=b", "-d=z"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 4 commits
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  e: d
+    ○  d: z
+    │ ○  c: b
+    │ ○  b: z
+    ├─╯
+    @  z
+    │ ○  a
+    ├─╯
+    ◆
+    "###);
+    let expected_log = get_log_output(&test_env, &repo_path);
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
+    test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=b", "-s=d", "-d=z"]);
+    assert_eq!(get_log_output(&test_env, &repo_path), expected_log);
+
+    // Overlapping arguments rebase each source once
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=b|d", "-s=d", "-d=z"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 4 commits
+    "###);
+    assert_eq!(get_log_output(&test_env, &repo_path), expected_log);
+
+    // A single argument can resolve to several roots
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-s", "roots((b | c)::) | e", "-d=z"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 4 commits
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  d: c
+    ○  c: b
+    ○  b: z
+    │ ○  e: z
+    ├─╯
+    @  z
+    │ ○  a
+    ├─╯
+    ◆
+    "###);
+
+    // An empty set of sources is an error
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-s=none()", "-d=z"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Empty revision set
+    "###);
+}
+
 #[test]
 fn test_rebase_error_revision_does_not_exist() {
     let test_env = TestEnvironment::default();
@@ -2217,36 +2404,16 @@ fn test_rebase_revisions_after_before() {
     "###);
     test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
 
-    // "c" has parents "b1" and "b2", so when it is rebased, its children "d" and
-    // "e" should have "b1" and "b2" as parents as well. "c" is then inserted in
-    // between "d" and "e", making "e" a merge commit with 3 parents "b1", "b2",
-    // and "c".
-    let (stdout, stderr) = test_env.jj_cmd_ok(
+    // "c" can't be inserted between "d" and "e", since "e" isn't a descendant
+    // of "d" and there's no slot to insert the commit into.
+    let stderr = test_env.jj_cmd_failure(
         &repo_path,
         &["rebase", "-r", "c", "--after", "d", "--before", "e"],
     );
-    insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Rebased 1 commits onto destination
-    Rebased 3 descendant commits
-    Working copy now at: lylxulpl e37682c5 f | f
-    Parent commit      : kmkuslsw 9bbc9e53 e | e
-    Added 1 files, modified 0 files, removed 0 files
+    Error: The --insert-before commits must be descendants of the --insert-after commits
+    Hint: Use --destination to rebase onto unrelated commits.
     "###);
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  f: e
-    ○      e: b1 b2 c
-    ├─┬─╮
-    │ │ ○  c: d
-    │ │ ○  d: b1 b2
-    ╭─┬─╯
-    │ ○  b2: a
-    ○ │  b1: a
-    ├─╯
-    ○  a
-    ◆
-    "###);
-    test_env.jj_cmd_ok(&repo_path, &["op", "restore", &setup_opid]);
 
     // Rebase multiple commits and preserve their ancestry. Apart from the heads of
     // the target commits ("d" and "e"), "f" also has commits "b1" and "b2" as
@@ -2262,11 +2429,11 @@ fn test_rebase_revisions_after_before() {
     insta::assert_snapshot!(stderr, @r###"
     Rebased 3 commits onto destination
     Rebased 1 descendant commits
-    Working copy now at: lylxulpl 868f6c61 f | f
+    Working copy now at: lylxulpl c1f9f9ab f | f
     Parent commit      : zsuskuln 072d5ae1 b1 | b1
     Parent commit      : royxmykx 903ab0d6 b2 | b2
-    Parent commit      : znkkpsqq ae6181e6 d | d
-    Parent commit      : kmkuslsw a55a6779 e | e
+    Parent commit      : znkkpsqq 5fdb8710 d | d
+    Parent commit      : kmkuslsw d04a05de e | e
     Added 1 files, modified 0 files, removed 0 files
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
@@ -2295,6 +2462,253 @@ fn test_rebase_revisions_after_before() {
     "###);
 }
 
+#[test]
+fn test_rebase_branch_after_before() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &["b"]);
+    create_commit(&test_env, &repo_path, "e", &["a"]);
+    create_commit(&test_env, &repo_path, "f", &["e"]);
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  f: e
+    ○  e: a
+    │ ○  d: b
+    │ │ ○  c: b
+    │ ├─╯
+    │ ○  b: a
+    ├─╯
+    ○  a
+    ◆
+    "###);
+
+    // The branch is inserted between "e" and its child, which is rebased onto
+    // the heads of the branch
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-b", "c", "--after", "e"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 3 commits onto destination
+    Rebased 1 descendant commits
+    Working copy now at: kmkuslsw 27026b48 f | f
+    Parent commit      : royxmykx 87e56999 c | c
+    Parent commit      : vruxwmqv 4d674834 d | d
+    Added 3 files, modified 0 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @    f: c d
+    ├─╮
+    │ ○  d: b
+    ○ │  c: b
+    ├─╯
+    ○  b: e
+    ○  e: a
+    ○  a
+    ◆
+    "###);
+
+    // The branch is computed relative to the parents of the --before commit
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-b", "c", "--before", "f"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 3 commits onto destination
+    Rebased 1 descendant commits
+    Working copy now at: kmkuslsw 09cb11c2 f | f
+    Parent commit      : royxmykx 17d62eb8 c | c
+    Parent commit      : vruxwmqv bb95cafb d | d
+    Added 3 files, modified 0 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @    f: c d
+    ├─╮
+    │ ○  d: b
+    ○ │  c: b
+    ├─╯
+    ○  b: e
+    ○  e: a
+    ○  a
+    ◆
+    "###);
+
+    // Defaults to `-b @`, and the --before commit must be a descendant of the
+    // --after commit
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "--after", "c", "--before", "d"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: The --insert-before commits must be descendants of the --insert-after commits
+    Hint: Use --destination to rebase onto unrelated commits.
+    "###);
+
+    // Loops are rejected
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["rebase", "-b", "f", "--after", "c", "--before", "b"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to create a loop: commit 7e4fbf4f2759 would be both an ancestor and a descendant of the rebased commits
+    "###);
+}
+
+#[test]
+fn test_rebase_onto_merge_base() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "x", &["c"]);
+    create_commit(&test_env, &repo_path, "y", &["b"]);
+    // Test the setup
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r###"
+    @  y  znkkpsqq  ede547ed:  b
+    │ ○  x  vruxwmqv  7e444e9e:  c
+    │ ○  c  royxmykx  7e4fbf4f:  b
+    ├─╯
+    ○  b  zsuskuln  1394f625:  a
+    ○  a  rlvkpnrz  2443ea76
+    ◆    zzzzzzzz  00000000
+    "###);
+
+    // x is rebased onto the merge base of x and y, which is b
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-s", "x", "-d", "y", "--onto-merge-base"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 commits
+    "###);
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r###"
+    ○  x  vruxwmqv  2d081d79:  b
+    │ @  y  znkkpsqq  ede547ed:  b
+    ├─╯
+    │ ○  c  royxmykx  7e4fbf4f:  b
+    ├─╯
+    ○  b  zsuskuln  1394f625:  a
+    ○  a  rlvkpnrz  2443ea76
+    ◆    zzzzzzzz  00000000
+    "###);
+
+    // Only works with -s
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["rebase", "-r", "x", "-d", "y", "--onto-merge-base"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--revisions <REVISIONS>' cannot be used with '--onto-merge-base'
+
+    Usage: jj rebase --revisions <REVISIONS> <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_rebase_merges_flatten() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &["b"]);
+    create_commit(&test_env, &repo_path, "e", &["c", "d"]);
+    create_commit(&test_env, &repo_path, "f", &["e"]);
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  f: e
+    ○    e: c d
+    ├─╮
+    │ ○  d: b
+    ○ │  c: b
+    ├─╯
+    ○  b: a
+    ○  a
+    ◆
+    "###);
+
+    // The diamond is collapsed into a line on top of the destination
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-s",
+            "b",
+            "-d",
+            "root()",
+            "--rebase-merges=flatten",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 5 commits onto destination
+    Working copy now at: kmkuslsw 4ee079c3 f | f
+    Parent commit      : znkkpsqq b627bbab e | e
+    Added 0 files, modified 0 files, removed 1 files
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  f: e
+    ○  e: d
+    ○  d: c
+    ○  c: b
+    ○  b
+    │ ○  a
+    ├─╯
+    ◆
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // With -r, descendants outside the set are rebased onto the remaining
+    // ancestors, as when merges are preserved
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-r",
+            "c|d|e",
+            "-d",
+            "a",
+            "--rebase-merges=flatten",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 3 commits onto destination
+    Rebased 1 descendant commits
+    Working copy now at: kmkuslsw 8be08628 f | f
+    Parent commit      : zsuskuln 1394f625 b | b
+    Added 0 files, modified 0 files, removed 3 files
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  f: b
+    ○  b: a
+    │ ○  e: d
+    │ ○  d: c
+    │ ○  c: a
+    ├─╯
+    ○  a
+    ◆
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // Only works with -d
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["rebase", "-r", "e", "-A", "a", "--rebase-merges=flatten"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: --rebase-merges=flatten cannot be used with --insert-after or --insert-before
+    "###);
+}
+
 #[test]
 fn test_rebase_skip_emptied() {
     let test_env = TestEnvironment::default();
@@ -2338,6 +2752,124 @@ fn test_rebase_skip_emptied() {
     "###);
 }
 
+#[test]
+fn test_rebase_abandon_emptied() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "a", "-m", "will become empty"]);
+    test_env.jj_cmd_ok(&repo_path, &["restore", "--from=b"]);
+    create_commit(&test_env, &repo_path, "c", &["@"]);
+    create_commit(&test_env, &repo_path, "d", &["c"]);
+    create_commit(&test_env, &repo_path, "e", &["a"]);
+
+    // Test the setup
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]), @r###"
+    @  e
+    │ ○  d
+    │ ○  c
+    │ ○  will become empty
+    ├─╯
+    │ ○  b
+    ├─╯
+    ○  a
+    ◆
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "rebase",
+            "-s=description(empty)",
+            "-d=b",
+            "--abandon-emptied",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 3 commits
+    "###);
+
+    // The commit that became empty was dropped along with its descendants
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]), @r###"
+    @  e
+    │ ○  b
+    ├─╯
+    ○  a
+    ◆
+    "###);
+
+    // Nothing is abandoned if no commit becomes empty
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=c", "-d=e", "--abandon-emptied"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 commits
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["rebase", "-d=b", "--abandon-emptied", "--skip-emptied"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--abandon-emptied' cannot be used with '--skip-emptied'
+
+    Usage: jj rebase --abandon-emptied <--destination <DESTINATION>|--insert-after <INSERT_AFTER>|--insert-before <INSERT_BEFORE>|--interactive>
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_rebase_warns_about_divergent_commits() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &[]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "a", "-m=a1"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "a", "-m=a2", "--at-op=@-"]);
+    let (a1_id, _) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["log", "--no-graph", "-r=description(a1)", "-T=commit_id"],
+    );
+
+    // Rebasing one of the commits keeps the change divergent
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-r", &a1_id, "-d=b"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 commits onto destination
+    Warning: The following rebased commits are divergent:
+      rlvkpnrz?? f883d4c6 a?? | a1
+    Hint: Abandon the unwanted commits, or use --keep-divergent to suppress this warning.
+    "###);
+
+    // The warning can be suppressed
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-r", &a1_id, "-d=b", "--keep-divergent"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 commits onto destination
+    "###);
+
+    // Once the divergence is resolved, there's nothing to warn about
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "description(a2)"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["rebase", "-r=description(a1)", "-d=root()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 commits onto destination
+    "###);
+}
+
 #[test]
 fn test_rebase_skip_if_on_destination() {
     let test_env = TestEnvironment::default();
@@ -2454,6 +2986,95 @@ fn test_rebase_skip_if_on_destination() {
     "###);
 }
 
+#[test]
+fn test_rebase_interactive() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let edit_script = test_env.set_up_fake_editor();
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &["c"]);
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  d: c
+    ○  c: b
+    ○  b: a
+    ○  a
+    ◆
+    "###);
+
+    // The todo list lists the stack oldest first; leaving it unchanged is a
+    // no-op
+    std::fs::write(&edit_script, "dump editor").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-s", "b", "-i"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Skipped rebase of 3 commits that were already in place
+    Nothing changed.
+    "###);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r###"
+    pick 1394f625cbbd b
+    pick 7e4fbf4f2759 c
+    pick 71bd904f7874 d
+
+    JJ: The commits are listed oldest first. Each line is applied in order:
+    JJ:   pick <commit> = rebase the commit onto the previous one
+    JJ:   drop <commit> = abandon the commit
+    JJ: The lines can be reordered. Every commit must be listed once.
+    JJ: Lines starting with "JJ: " (like this one) will be removed.
+    "###);
+
+    // Every commit must be listed
+    std::fs::write(
+        &edit_script,
+        "write\npick 1394f625cbbd\npick 7e4fbf4f2759\n",
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-s", "b", "-i"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Commit 71bd904f7874 is missing from the todo list (use `drop` to abandon it)
+    "###);
+
+    // Cannot rebase onto the stack itself
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-s", "b", "-d", "c", "-i"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot rebase 1394f625cbbd onto descendant 7e4fbf4f2759
+    "###);
+
+    // Reorder the commits and drop one of them
+    std::fs::write(
+        &edit_script,
+        "write\npick 71bd904f7874 d\ndrop 1394f625cbbd b\npick 7e4fbf4f2759 c\n",
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-s", "b", "-i"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 commits onto destination
+    Abandoned 1 commits
+    Working copy now at: vruxwmqv 92c2bc9a d | d
+    Parent commit      : rlvkpnrz 2443ea76 a b | a
+    Added 0 files, modified 0 files, removed 2 files
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  c: d
+    @  d: a b
+    ○  a b
+    ◆
+    "###);
+
+    // The stack must be linear
+    create_commit(&test_env, &repo_path, "e", &["a"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-s", "a", "-i"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot rebase interactively: 2443ea76b0b1:: is not a linear stack of commits
+    "###);
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     let template = "branches ++ surround(': ', '', parents.map(|c| c.branches()))";
     test_env.jj_cmd_success(repo_path, &["log", "-T", template])
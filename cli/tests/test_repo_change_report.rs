@@ -138,6 +138,10 @@ fn test_report_conflicts_with_divergent_commits() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+    Warning: The following rebased commits are divergent:
+      zsuskuln?? b535189c (conflict) C3
+      zsuskuln?? 97ce1783 (conflict) C2
+    Hint: Abandon the unwanted commits, or use --keep-divergent to suppress this warning.
     "###);
 
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-d=description(A)"]);
@@ -151,6 +155,10 @@ fn test_report_conflicts_with_divergent_commits() {
     Working copy now at: zsuskuln?? f2d7a228 C2
     Parent commit      : kkmpptxz db069a22 B
     Added 0 files, modified 1 files, removed 0 files
+    Warning: The following rebased commits are divergent:
+      zsuskuln?? d365906b C3
+      zsuskuln?? f2d7a228 C2
+    Hint: Abandon the unwanted commits, or use --keep-divergent to suppress this warning.
     "###);
 
     // Same thing when rebasing the divergent commits one at a time
@@ -171,6 +179,9 @@ fn test_report_conflicts_with_divergent_commits() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+    Warning: The following rebased commits are divergent:
+      zsuskuln?? b15416ac (conflict) C2
+    Hint: Abandon the unwanted commits, or use --keep-divergent to suppress this warning.
     "###);
 
     let (stdout, stderr) =
@@ -185,6 +196,9 @@ fn test_report_conflicts_with_divergent_commits() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
+    Warning: The following rebased commits are divergent:
+      zsuskuln?? 8cc7fde6 (conflict) C3
+    Hint: Abandon the unwanted commits, or use --keep-divergent to suppress this warning.
     "###);
 
     let (stdout, stderr) = test_env.jj_cmd_ok(
@@ -199,6 +213,9 @@ fn test_report_conflicts_with_divergent_commits() {
     Working copy now at: zsuskuln?? 1f9680bd C2
     Parent commit      : kkmpptxz db069a22 B
     Added 0 files, modified 1 files, removed 0 files
+    Warning: The following rebased commits are divergent:
+      zsuskuln?? 1f9680bd C2
+    Hint: Abandon the unwanted commits, or use --keep-divergent to suppress this warning.
     "###);
 
     let (stdout, stderr) = test_env.jj_cmd_ok(
@@ -210,5 +227,8 @@ fn test_report_conflicts_with_divergent_commits() {
     Rebased 1 commits
     Existing conflicts were resolved or abandoned from these commits:
       zsuskuln hidden 8cc7fde6 (conflict) C3
+    Warning: The following rebased commits are divergent:
+      zsuskuln?? 7754eeb3 C3
+    Hint: Abandon the unwanted commits, or use --keep-divergent to suppress this warning.
     "###);
 }
@@ -15,6 +15,7 @@
 use std::path::Path;
 
 use indoc::indoc;
+use itertools::Itertools as _;
 
 use crate::common::TestEnvironment;
 
@@ -347,9 +348,82 @@ fn test_resolution() {
     @r###"
     Error: No conflicts found at this revision
     "###);
+}
+
+#[test]
+fn test_resolve_non_working_copy_revision() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    create_commit(
+        &test_env,
+        &repo_path,
+        "child",
+        &["conflict"],
+        &[("other", "child\n")],
+    );
 
-    // TODO: Check that running `jj new` and then `jj resolve -r conflict` works
-    // correctly.
+    // The conflict is resolved in the parent, and the working-copy commit is
+    // rebased onto it
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(&editor_script, "write\nresolution\n").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "-r", "conflict"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Rebased 1 descendant commits
+    Existing conflicts were resolved or abandoned from these commits:
+      znkkpsqq hidden 595d822b (conflict) child
+    Working copy now at: znkkpsqq 9d5da590 child | child
+    Parent commit      : vruxwmqv e069f073 conflict | conflict
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["diff", "--git", "-r", "conflict"]), @r###"
+    diff --git a/file b/file
+    index 0000000000..88425ec521 100644
+    --- a/file
+    +++ b/file
+    @@ -1,7 +1,1 @@
+    -<<<<<<< Conflict 1 of 1
+    -%%%%%%% Changes from base to side #1
+    --base
+    -+a
+    -+++++++ Contents of side #2
+    -b
+    ->>>>>>> Conflict 1 of 1 ends
+    +resolution
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r###"
+    resolution
+    "###);
+
+    // New conflicts in the descendants are reported
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    std::fs::write(repo_path.join("file"), "child\n").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "-r", "conflict"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Rebased 1 descendant commits
+    New conflicts appeared in these commits:
+      znkkpsqq 633f4737 child | (conflict) child
+    To resolve the conflicts, start by updating to it:
+      jj new znkkpsqqskkl
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: znkkpsqq 633f4737 child | (conflict) child
+    Parent commit      : vruxwmqv 99b2f438 conflict | conflict
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file    3-sided conflict
+    "###);
 }
 
 fn check_resolve_produces_input_file(
@@ -391,104 +465,896 @@ fn test_normal_conflict_input_files() {
     create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
     create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
     create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
-    // Test the setup
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @    conflict
-    ├─╮
-    │ ○  b
-    ○ │  a
-    ├─╯
-    ○  base
-    ◆
-    "###);
-    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), 
-    @r###"
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @    conflict
+    ├─╮
+    │ ○  b
+    ○ │  a
+    ├─╯
+    ○  base
+    ◆
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), 
+    @r###"
+    file    2-sided conflict
+    "###);
+    insta::assert_snapshot!(
+    std::fs::read_to_string(repo_path.join("file")).unwrap()
+        , @r###"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+
+    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "base", "base\n");
+    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "left", "a\n");
+    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "right", "b\n");
+}
+
+#[test]
+fn test_baseless_conflict_input_files() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @    conflict
+    ├─╮
+    │ ○  b
+    ○ │  a
+    ├─╯
+    ○  base
+    ◆
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), 
+    @r###"
+    file    2-sided conflict
+    "###);
+    insta::assert_snapshot!(
+    std::fs::read_to_string(repo_path.join("file")).unwrap()
+        , @r###"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+
+    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "base", "");
+    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "left", "a\n");
+    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "right", "b\n");
+}
+
+#[test]
+fn test_too_many_parents() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "c", &["base"], &[("file", "c\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b", "c"], &[]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), 
+    @r###"
+    file    3-sided conflict
+    "###);
+    // Test warning color
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--color=always"]), 
+    @r###"
+    file    [38;5;1m3-sided[38;5;3m conflict[39m
+    "###);
+
+    let error = test_env.jj_cmd_failure(&repo_path, &["resolve"]);
+    insta::assert_snapshot!(error, @r###"
+    Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
+    Resolving conflicts in: file
+    Error: Failed to resolve conflicts
+    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported.
+    "###);
+
+    // "ours" and "theirs" aren't defined either
+    let error = test_env.jj_cmd_failure(&repo_path, &["resolve", "--accept-ours"]);
+    insta::assert_snapshot!(error, @r###"
+    Resolving conflicts in: file
+    Error: Failed to resolve conflicts
+    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported.
+    "###);
+}
+
+#[test]
+fn test_resolve_list_total_sides() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "base\n"), ("file2", "base\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a\n"), ("file2", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b\n"), ("file2", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "c", &["base"], &[("file2", "c\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b", "c"], &[]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    file1    2-sided conflict
+    file2    3-sided conflict
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--total-sides"]), @"5");
+
+    // Only the matching conflicts are counted
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--total-sides", "file1"]),
+        @"2");
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--total-sides"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the following required arguments were not provided:
+      --list
+
+    Usage: jj resolve --list --total-sides [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_resolve_list_include_resolved() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "base\n"), ("file2", "base\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a\n"), ("file2", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b\n"), ("file2", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    // Nothing is resolved yet
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--include-resolved"]),
+        @r###"
+    file1    2-sided conflict
+    file2    2-sided conflict
+    "###);
+
+    // Paths resolved in the working copy are listed after the conflicts
+    std::fs::write(repo_path.join("file1"), "resolved\n").unwrap();
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--include-resolved"]),
+        @r###"
+    file2    2-sided conflict
+    file1    resolved
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--include-resolved", "file2"]),
+        @r###"
+    file2    2-sided conflict
+    "###);
+
+    // No error when all conflicts are resolved
+    std::fs::write(repo_path.join("file2"), "resolved\n").unwrap();
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--include-resolved"]),
+        @r###"
+    file1    resolved
+    file2    resolved
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--list"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No conflicts found at this revision
+    "###);
+}
+
+#[test]
+fn test_resolve_print() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "base\n"), ("file2", "base\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a\n"), ("file2", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b\n"), ("file2", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // A single conflict is printed as is, same as in the working copy
+    let stdout = test_env.jj_cmd_success(&repo_path, &["resolve", "--print", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+    assert_eq!(
+        stdout,
+        std::fs::read_to_string(repo_path.join("file1")).unwrap()
+    );
+
+    // Multiple conflicts are printed with headers
+    let stdout = test_env.jj_cmd_success(&repo_path, &["resolve", "--print"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ==> file1 <==
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    ==> file2 <==
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+
+    // The working copy isn't touched
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "-n1", "--no-graph", "-Tdescription"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    create branch conflict pointing to commit 87aef77d40c527e459b05cae35fbb53503ee7d98
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--print", "--list"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--print' cannot be used with '--list'
+
+    Usage: jj resolve --print [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_resolve_accept_all_trivial() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file2", "base\n")]);
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "same\n"), ("file2", "a\n")],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["file", "chmod", "x", "file1"]);
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "same\n"), ("file2", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    // file1 was added with the same contents on both sides, but only one side
+    // made it executable
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    file1    2-sided conflict including an executable
+    file2    2-sided conflict
+    "###);
+
+    // Only the conflict in file1 is trivial when comparing the contents
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--accept-all-trivial"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolved 1 trivial conflicts, 1 conflicts remain
+    New conflicts appeared in these commits:
+      yostqsxw 8e97848f conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new yostqsxwqrlt
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: yostqsxw 8e97848f conflict | (conflict) conflict
+    Parent commit      : zsuskuln b1ae1777 a | a
+    Parent commit      : yqosqzyt 7b80f3b2 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file2    2-sided conflict
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    file2    2-sided conflict
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["debug", "tree", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1: Ok(Resolved(Some(File { id: FileId("1275430f1765c63e539cb0452565563bd6aef6a6"), executable: true })))
+    "###);
+
+    // The remaining conflict isn't trivial, so it's left untouched
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--accept-all-trivial"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    No trivial conflicts found, 1 conflicts remain
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    file2    2-sided conflict
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["resolve", "--accept-all-trivial", "--accept-ours"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--accept-all-trivial' cannot be used with '--accept-ours'
+
+    Usage: jj resolve --accept-all-trivial [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_resolve_accept_side() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "base\n"), ("file2", "base\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a\n"), ("file2", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b\n"), ("file2", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--accept-ours", "file1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file1
+    New conflicts appeared in these commits:
+      vruxwmqv 9f7fce9e conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqvtpmx
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: vruxwmqv 9f7fce9e conflict | (conflict) conflict
+    Parent commit      : zsuskuln bd4692d0 a | a
+    Parent commit      : royxmykx e4ed205c b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file2    2-sided conflict
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file1")).unwrap(), @"a");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--accept-theirs", "file2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file2
+    Existing conflicts were resolved or abandoned from these commits:
+      vruxwmqv hidden 9f7fce9e (conflict) conflict
+    Working copy now at: vruxwmqv 44ee9327 conflict | conflict
+    Parent commit      : zsuskuln bd4692d0 a | a
+    Parent commit      : royxmykx e4ed205c b | b
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file2")).unwrap(), @"b");
+
+    // Can't be combined with a merge tool
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["resolve", "--accept-ours", "--tool", "fake-editor"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--accept-ours' cannot be used with '--tool <NAME>'
+
+    Usage: jj resolve --accept-ours [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_resolve_accept_side_deleted() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[]);
+    std::fs::remove_file(repo_path.join("file")).unwrap();
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // Taking the side where the file was deleted removes it
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--accept-theirs"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Working copy now at: vruxwmqv 7674b97f conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx 5dc746f4 b | b
+    Added 0 files, modified 0 files, removed 1 files
+    "###);
+    assert!(!repo_path.join("file").exists());
+}
+
+#[test]
+fn test_resolve_accept_side_executable() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    test_env.jj_cmd_ok(&repo_path, &["file", "chmod", "x", "file"]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // The executable bit of the taken side is preserved
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--accept-ours"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["debug", "tree", "file"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file: Ok(Resolved(Some(File { id: FileId("78981922613b2afb6025042ff6bd878ac1994e85"), executable: true })))
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r###"
+    a
+    "###);
+}
+
+#[test]
+fn test_resolve_tool_timeout() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // The merge tool is killed if it hangs, and the file stays conflicted
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(
+        &editor_script,
+        ["sleep 30", "write\nresolution\n"].join("\0"),
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve", "--tool-timeout=1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Error: Failed to resolve conflicts
+    Caused by: Tool didn't finish within 1 seconds and was killed (run with --debug to see the exact invocation)
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    file    2-sided conflict
+    "###);
+
+    // Output written before the merge tool is killed is discarded
+    std::fs::write(
+        &editor_script,
+        ["write\npartial resolution\n", "sleep 30"].join("\0"),
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve", "--tool-timeout=1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Error: Failed to resolve conflicts
+    Caused by: Tool didn't finish within 1 seconds and was killed (run with --debug to see the exact invocation)
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    file    2-sided conflict
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["file", "show", "file"]), @r###"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+
+    // The timeout can be configured
+    std::fs::write(
+        &editor_script,
+        ["sleep 30", "write\nresolution\n"].join("\0"),
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["resolve", "--config-toml=ui.merge-tool-timeout=1"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Error: Failed to resolve conflicts
+    Caused by: Tool didn't finish within 1 seconds and was killed (run with --debug to see the exact invocation)
+    "###);
+
+    // The merge tool can finish within the deadline
+    std::fs::write(&editor_script, "write\nresolution\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "resolve",
+            "--config-toml=ui.merge-tool-timeout=1",
+            "--tool-timeout=30",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Working copy now at: vruxwmqv 1a70c7c6 conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+}
+
+#[test]
+fn test_resolve_marker_style() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    test_env.add_config(r#"merge-tools.fake-editor.merge-tool-edits-conflict-markers = true"#);
+    let editor_script = test_env.set_up_fake_editor();
+
+    // The markers can be written in diff3 style, and are parsed back
+    std::fs::write(
+        &editor_script,
+        [
+            "dump editor0",
+            indoc! {"
+                write
+                <<<<<<< Conflict 1 of 1
+                a
+                ||||||| base
+                base
+                =======
+                b2
+                >>>>>>> Conflict 1 of 1 ends
+            "},
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--marker-style=diff3"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    New conflicts appeared in these commits:
+      vruxwmqv a135b2fc conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqvtpmx
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: vruxwmqv a135b2fc conflict | (conflict) conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
     file    2-sided conflict
     "###);
     insta::assert_snapshot!(
-    std::fs::read_to_string(repo_path.join("file")).unwrap()
-        , @r###"
+        std::fs::read_to_string(test_env.env_root().join("editor0")).unwrap(), @r###"
+    <<<<<<< Conflict 1 of 1
+    a
+    ||||||| base
+    base
+    =======
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r###"
     <<<<<<< Conflict 1 of 1
     %%%%%%% Changes from base to side #1
     -base
     +a
     +++++++ Contents of side #2
+    b2
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+
+    // The style can be configured, and two-way markers take the base from the
+    // original conflict
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    std::fs::write(
+        &editor_script,
+        [
+            "dump editor1",
+            indoc! {"
+                write
+                <<<<<<< Conflict 1 of 1
+                a2
+                =======
+                b
+                >>>>>>> Conflict 1 of 1 ends
+            "},
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "resolve",
+            "--config-toml=ui.merge-tool-marker-style='two-way'",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    New conflicts appeared in these commits:
+      vruxwmqv 3d9d47a7 conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqvtpmx
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: vruxwmqv 3d9d47a7 conflict | (conflict) conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    "###);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor1")).unwrap(), @r###"
+    <<<<<<< Conflict 1 of 1
+    a
+    =======
     b
     >>>>>>> Conflict 1 of 1 ends
     "###);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r###"
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    a2
+    %%%%%%% Changes from base to side #2
+    -base
+    +b
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
 
-    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "base", "base\n");
-    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "left", "a\n");
-    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "right", "b\n");
+    // The flag conflicts with the modes that don't run a merge tool
+    let stderr =
+        test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--print", "--marker-style=diff3"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--print' cannot be used with '--marker-style <STYLE>'
+
+    Usage: jj resolve --print [PATHS]...
+
+    For more information, try '--help'.
+    "###);
 }
 
 #[test]
-fn test_baseless_conflict_input_files() {
+fn test_resolve_tool_by_extension() {
     let mut test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
     let repo_path = test_env.env_root().join("repo");
 
-    create_commit(&test_env, &repo_path, "base", &[], &[]);
-    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
-    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("notes.md", "base\n"), ("code.rs", "base\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("notes.md", "a\n"), ("code.rs", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("notes.md", "b\n"), ("code.rs", "b\n")],
+    );
     create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
-    // Test the setup
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @    conflict
-    ├─╮
-    │ ○  b
-    ○ │  a
-    ├─╯
-    ○  base
-    ◆
+
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(&editor_script, "write\nresolution\n").unwrap();
+    test_env.add_config(r#"ui.merge-editor-by-extension.md = "prose-tool""#);
+
+    // The tool configured for the extension is used
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve", "notes.md"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: The tool `prose-tool` cannot be used as a merge tool with `jj resolve`
+    Hint: To use `prose-tool` as a merge tool, the config `merge-tools.prose-tool.merge-args` must be defined (see docs for details)
     "###);
-    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), 
-    @r###"
-    file    2-sided conflict
+
+    // Other extensions fall back to the default tool
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "code.rs"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: code.rs
+    New conflicts appeared in these commits:
+      vruxwmqv 590e087b conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqvtpmx
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: vruxwmqv 590e087b conflict | (conflict) conflict
+    Parent commit      : zsuskuln ef33a2fa a | a
+    Parent commit      : royxmykx 84c5f54a b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    notes.md    2-sided conflict
     "###);
-    insta::assert_snapshot!(
-    std::fs::read_to_string(repo_path.join("file")).unwrap()
-        , @r###"
-    <<<<<<< Conflict 1 of 1
-    %%%%%%% Changes from base to side #1
-    +a
-    +++++++ Contents of side #2
-    b
-    >>>>>>> Conflict 1 of 1 ends
+
+    // --tool-for overrides the config
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool-for=md=fake-editor"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: notes.md
+    Existing conflicts were resolved or abandoned from these commits:
+      vruxwmqv hidden 590e087b (conflict) conflict
+    Working copy now at: vruxwmqv 33160781 conflict | conflict
+    Parent commit      : zsuskuln ef33a2fa a | a
+    Parent commit      : royxmykx 84c5f54a b | b
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--list"]), @r###"
+    Error: No conflicts found at this revision
     "###);
 
-    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "base", "");
-    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "left", "a\n");
-    check_resolve_produces_input_file(&mut test_env, &repo_path, "file", "right", "b\n");
+    // Bad --tool-for value
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--tool-for=md"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'md' for '--tool-for <EXT=NAME>': Expected EXT=NAME, got "md"
+
+    For more information, try '--help'.
+    "###);
 }
 
 #[test]
-fn test_too_many_parents() {
-    let test_env = TestEnvironment::default();
+fn test_resolve_print_command() {
+    let mut test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
     let repo_path = test_env.env_root().join("repo");
 
-    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
-    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
-    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
-    create_commit(&test_env, &repo_path, "c", &["base"], &[("file", "c\n")]);
-    create_commit(&test_env, &repo_path, "conflict", &["a", "b", "c"], &[]);
-    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), 
-    @r###"
-    file    3-sided conflict
-    "###);
-    // Test warning color
-    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--color=always"]), 
-    @r###"
-    file    [38;5;1m3-sided[38;5;3m conflict[39m
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "base1\n"), ("file2", "base2\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a1\n"), ("file2", "a2\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b1\n"), ("file2", "b2\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    test_env.set_up_fake_editor();
+    test_env.add_config(
+        r#"merge-tools.fake-editor.merge-args = ["--left", "$left", "$base", "$right", "$output"]"#,
+    );
+
+    // The variables are substituted with the paths of the input files, which
+    // are left in place
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["resolve", "--print-command", "--nth=2"]);
+    insta::assert_snapshot!(stderr, @"");
+    let args = stdout.split_whitespace().collect_vec();
+    assert_eq!(args.len(), 6, "{stdout}");
+    assert!(args[0].ends_with("fake-editor"), "{stdout}");
+    assert_eq!(args[1], "--left");
+    let contents = args[2..]
+        .iter()
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect_vec();
+    assert_eq!(contents, ["a2\n", "base2\n", "b2\n", ""]);
+
+    // Nothing was resolved
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    file1    2-sided conflict
+    file2    2-sided conflict
     "###);
 
-    let error = test_env.jj_cmd_failure(&repo_path, &["resolve"]);
-    insta::assert_snapshot!(error, @r###"
-    Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
-    Resolving conflicts in: file
-    Error: Failed to resolve conflicts
-    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported.
+    // The builtin merge tool has no command line
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["resolve", "--print-command", "--tool=:builtin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: The builtin merge tool doesn't have a command line to print
     "###);
 }
 
@@ -853,6 +1719,10 @@ fn test_multiple_conflicts() {
     another_file                        [38;5;3m2-sided conflict[39m
     this_file_has_a_very_long_name_to_test_padding [38;5;3m2-sided conflict[39m
     "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--stat"]), @r###"
+    2 conflicted paths
+      2-sided: 2
+    "###);
 
     let editor_script = test_env.set_up_fake_editor();
 
@@ -863,13 +1733,13 @@ fn test_multiple_conflicts() {
     insta::assert_snapshot!(stderr, @r###"
     Resolving conflicts in: another_file
     New conflicts appeared in these commits:
-      vruxwmqv 6a90e546 conflict | (conflict) conflict
+      vruxwmqv a4c1b6ca conflict | (conflict) conflict
     To resolve the conflicts, start by updating to it:
       jj new vruxwmqvtpmx
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    Working copy now at: vruxwmqv 6a90e546 conflict | (conflict) conflict
+    Working copy now at: vruxwmqv a4c1b6ca conflict | (conflict) conflict
     Parent commit      : zsuskuln de7553ef a | a
     Parent commit      : royxmykx f68bc2f0 b | b
     Added 0 files, modified 1 files, removed 0 files
@@ -904,11 +1774,88 @@ fn test_multiple_conflicts() {
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
 
-    // For the rest of the test, we call `jj resolve` several times in a row to
-    // resolve each conflict in the order it chooses.
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
     insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["diff", "--git"]), 
     @"");
+
+    // Conflicts can be picked by their position in the sorted list
+    std::fs::write(&editor_script, "expect\n\0write\nsecond conflict\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--nth", "2"]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    another_file    2-sided conflict
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    std::fs::write(&editor_script, "expect\n\0write\nfirst conflict\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--nth", "1"]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    this_file_has_a_very_long_name_to_test_padding 2-sided conflict
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    insta::assert_snapshot!(test_env.jj_cmd_failure(&repo_path, &["resolve", "--nth", "3"]), @r###"
+    Error: Can't resolve conflict #3; there are only 2 conflicts
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--nth", "0"]), @r###"
+    error: invalid value '0' for '--nth <N>': 0 is not in 1..=4294967295
+
+    For more information, try '--help'.
+    "###);
+
+    // With `--all`, each conflict is resolved in turn. The merge tool can skip a
+    // conflict by failing.
+    std::fs::write(
+        &editor_script,
+        [
+            "fail",
+            "next invocation\n",
+            "write\nresolution with --all\n",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--all"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: another_file
+    Warning: Skipping: Tool exited with exit status: 1 (run with --debug to see the exact invocation)
+    Resolving conflicts in: this_file_has_a_very_long_name_to_test_padding
+    Resolved conflicts in 1 paths:
+      this_file_has_a_very_long_name_to_test_padding
+    Skipped conflicts in 1 paths:
+      another_file
+    New conflicts appeared in these commits:
+      vruxwmqv 424e8ab1 conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqvtpmx
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: vruxwmqv 424e8ab1 conflict | (conflict) conflict
+    Parent commit      : zsuskuln de7553ef a | a
+    Parent commit      : royxmykx f68bc2f0 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    another_file    2-sided conflict
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r###"
+    another_file    2-sided conflict
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    // Nothing is changed if every conflict is skipped
+    std::fs::write(&editor_script, "fail").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--all"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: another_file
+    Warning: Skipping: Tool exited with exit status: 1 (run with --debug to see the exact invocation)
+    Resolving conflicts in: this_file_has_a_very_long_name_to_test_padding
+    Warning: Skipping: Tool exited with exit status: 1 (run with --debug to see the exact invocation)
+    Skipped conflicts in 2 paths:
+      another_file
+      this_file_has_a_very_long_name_to_test_padding
+    "###);
+
+    // For the rest of the test, we call `jj resolve` several times in a row to
+    // resolve each conflict in the order it chooses.
     std::fs::write(
         &editor_script,
         "expect\n\0write\nfirst resolution for auto-chosen file\n",
@@ -935,6 +1882,10 @@ fn test_multiple_conflicts() {
     @r###"
     this_file_has_a_very_long_name_to_test_padding 2-sided conflict
     "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--stat"]), @r###"
+    1 conflicted paths
+      2-sided: 1
+    "###);
     std::fs::write(
         &editor_script,
         "expect\n\0write\nsecond resolution for auto-chosen file\n",
@@ -980,4 +1931,8 @@ fn test_multiple_conflicts() {
     @r###"
     Error: No conflicts found at this revision
     "###);
+    // `--stat` succeeds even if there are no conflicts
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--stat"]), @r###"
+    0 conflicted paths
+    "###);
 }
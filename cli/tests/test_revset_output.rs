@@ -287,6 +287,51 @@ fn test_bad_function_call() {
     "###);
 }
 
+#[test]
+fn test_bad_date_pattern() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // The message of the underlying date parser isn't ours to snapshot
+    insta::with_settings!({filters => vec![(r"(?m)^3: .*$", "3: [date error]")]}, {
+        let stderr = test_env.jj_cmd_failure(
+            &repo_path,
+            &["log", "-r", "committer_date(after:'not a date')"],
+        );
+        insta::assert_snapshot!(stderr, @r###"
+        Error: Failed to parse revset: Invalid date pattern
+        Caused by:
+        1:  --> 1:16
+          |
+        1 | committer_date(after:'not a date')
+          |                ^----------------^
+          |
+          = Invalid date pattern
+        2: Failed to parse date "not a date"
+        3: [date error]
+        Hint: Dates can be specified as `2024-02-01`, `2024-02-01T12:00:00`, `2024-02-01T12:00:00-08:00`, `2 days ago`, `yesterday 5pm`, etc.
+        "###);
+    });
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "-r", "author_date(since:'2024-01-01')"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse revset: Invalid date pattern
+    Caused by:
+    1:  --> 1:13
+      |
+    1 | author_date(since:'2024-01-01')
+      |             ^----------------^
+      |
+      = Invalid date pattern
+    2: Invalid date pattern kind "since:"
+    Hint: Try prefixing with one of `after:` or `before:`
+    "###);
+}
+
 #[test]
 fn test_function_name_hint() {
     let test_env = TestEnvironment::default();
@@ -313,7 +358,7 @@ fn test_function_name_hint() {
       | ^----^
       |
       = Function "branch" doesn't exist
-    Hint: Did you mean "branches", "reachable"?
+    Hint: Did you mean "branches", "branches_behind", "reachable"?
     "###);
 
     // Both builtin function and function alias should be suggested
@@ -325,7 +370,7 @@ fn test_function_name_hint() {
       | ^-----^
       |
       = Function "author_" doesn't exist
-    Hint: Did you mean "author", "author_date", "my_author"?
+    Hint: Did you mean "author", "author_date", "author_email", "author_name", "my_author"?
     "###);
 
     insta::assert_snapshot!(evaluate_err("my_branches"), @r###"
@@ -343,7 +388,7 @@ fn test_function_name_hint() {
       | ^----^
       |
       = Function "branch" doesn't exist
-    Hint: Did you mean "branches", "reachable"?
+    Hint: Did you mean "branches", "branches_behind", "reachable"?
     "###);
 }
 
@@ -752,3 +797,70 @@ fn test_revset_committer_date_with_time_zone() {
     second 2023-01-25 12:30:00.000 -05:00
     "###);
 }
+
+#[test]
+fn test_revset_diff_contains_max_scan_bytes() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("small"), "TODO\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "small"]);
+    std::fs::write(
+        repo_path.join("large"),
+        format!("TODO\n{}\n", "x".repeat(100)),
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "large"]);
+
+    let template = r#"description.first_line() ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            template,
+            "-r",
+            "diff_contains(TODO)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    large
+    small
+    "###);
+
+    // Files above the configured size are skipped
+    test_env.add_config("diff.max-scan-bytes = 100");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            template,
+            "-r",
+            "diff_contains(TODO)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    small
+    "###);
+
+    // The function argument takes precedence over the config
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            template,
+            "-r",
+            "diff_contains(TODO, max_size=1000)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    large
+    small
+    "###);
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use indoc::indoc;
+
 use crate::common::TestEnvironment;
 
 #[test]
@@ -52,3 +54,69 @@ fn test_snapshot_large_file() {
         This will increase the maximum file size allowed for new files, for this command only.
     "###);
 }
+
+#[test]
+fn test_conflict_marker_style_diff3() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"ui.conflict-marker-style = "diff3""#);
+
+    std::fs::write(repo_path.join("file"), "base\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+    std::fs::write(repo_path.join("file"), "left\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "left"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "right"]);
+    std::fs::write(repo_path.join("file"), "right\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(left)", "description(right)"],
+    );
+
+    // The conflict is checked out in diff3 style
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r###"
+    <<<<<<< Conflict 1 of 1
+    left
+    ||||||| base
+    base
+    =======
+    right
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+
+    // Edits to the markers are parsed back in the same style
+    std::fs::write(
+        repo_path.join("file"),
+        indoc! {"
+            <<<<<<< Conflict 1 of 1
+            left
+            ||||||| base
+            base
+            =======
+            right edited
+            >>>>>>> Conflict 1 of 1 ends
+        "},
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "file",
+            "show",
+            "file",
+            "--ignore-working-copy",
+            "--config-toml",
+            r#"ui.conflict-marker-style = "jj""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +left
+    +++++++ Contents of side #2
+    right edited
+    >>>>>>> Conflict 1 of 1 ends
+    "###);
+}
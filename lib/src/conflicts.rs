@@ -17,6 +17,7 @@
 use std::io::Read;
 use std::io::Write;
 use std::iter::zip;
+use std::str;
 
 use futures::stream::BoxStream;
 use futures::try_join;
@@ -52,11 +53,15 @@ const CONFLICT_END_LINE: &[u8] = b">>>>>>>";
 const CONFLICT_DIFF_LINE: &[u8] = b"%%%%%%%";
 const CONFLICT_MINUS_LINE: &[u8] = b"-------";
 const CONFLICT_PLUS_LINE: &[u8] = b"+++++++";
+const CONFLICT_BASE_LINE: &[u8] = b"|||||||";
+const CONFLICT_SEPARATOR_LINE: &[u8] = b"=======";
 const CONFLICT_START_LINE_CHAR: u8 = CONFLICT_START_LINE[0];
 const CONFLICT_END_LINE_CHAR: u8 = CONFLICT_END_LINE[0];
 const CONFLICT_DIFF_LINE_CHAR: u8 = CONFLICT_DIFF_LINE[0];
 const CONFLICT_MINUS_LINE_CHAR: u8 = CONFLICT_MINUS_LINE[0];
 const CONFLICT_PLUS_LINE_CHAR: u8 = CONFLICT_PLUS_LINE[0];
+const CONFLICT_BASE_LINE_CHAR: u8 = CONFLICT_BASE_LINE[0];
+const CONFLICT_SEPARATOR_LINE_CHAR: u8 = CONFLICT_SEPARATOR_LINE[0];
 
 /// A conflict marker is one of the separators, optionally followed by a space
 /// and some text.
@@ -70,6 +75,37 @@ static CONFLICT_MARKER_REGEX: once_cell::sync::Lazy<Regex> = once_cell::sync::La
         .unwrap()
 });
 
+/// The base and separator markers of the diff3 and two-way styles. They are
+/// only recognized in content written in one of those styles, so lines like
+/// `=======` remain plain text in files written in the jj style.
+static SNAPSHOT_CONFLICT_MARKER_REGEX: once_cell::sync::Lazy<Regex> =
+    once_cell::sync::Lazy::new(|| {
+        RegexBuilder::new(r"^(\|{7}|={7})( .*)?$")
+            .multi_line(true)
+            .build()
+            .unwrap()
+    });
+
+/// The number of the conflict in the label of its start marker.
+static CONFLICT_START_LABEL_REGEX: once_cell::sync::Lazy<Regex> =
+    once_cell::sync::Lazy::new(|| Regex::new(r"^<{7} Conflict (\d+) of \d+").unwrap());
+
+/// How conflicts are written as text with conflict markers.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub enum ConflictMarkerStyle {
+    /// Each side is written as a diff from the base, or as a snapshot
+    /// (`%%%%%%%`, `-------`, and `+++++++` markers).
+    #[default]
+    Jj,
+    /// The sides and the base are written as snapshots like `diff3` does
+    /// (`|||||||` and `=======` markers).
+    Diff3,
+    /// Only the two sides are written as snapshots (`=======` marker). The base
+    /// is recovered from the conflict number when the markers are parsed.
+    TwoWay,
+}
+
 fn write_diff_hunks(hunks: &[DiffHunk], file: &mut dyn Write) -> std::io::Result<()> {
     for hunk in hunks {
         match hunk {
@@ -171,7 +207,18 @@ pub async fn materialize_tree_value(
     path: &RepoPath,
     value: MergedTreeValue,
 ) -> BackendResult<MaterializedTreeValue> {
-    match materialize_tree_value_no_access_denied(store, path, value).await {
+    materialize_tree_value_with_marker_style(store, path, value, ConflictMarkerStyle::Jj).await
+}
+
+/// Like `materialize_tree_value()`, but writes conflicts with the given marker
+/// style.
+pub async fn materialize_tree_value_with_marker_style(
+    store: &Store,
+    path: &RepoPath,
+    value: MergedTreeValue,
+    marker_style: ConflictMarkerStyle,
+) -> BackendResult<MaterializedTreeValue> {
+    match materialize_tree_value_no_access_denied(store, path, value, marker_style).await {
         Err(BackendError::ReadAccessDenied { source, .. }) => {
             Ok(MaterializedTreeValue::AccessDenied(source))
         }
@@ -183,6 +230,7 @@ async fn materialize_tree_value_no_access_denied(
     store: &Store,
     path: &RepoPath,
     value: MergedTreeValue,
+    marker_style: ConflictMarkerStyle,
 ) -> BackendResult<MaterializedTreeValue> {
     match value.into_resolved() {
         Ok(None) => Ok(MaterializedTreeValue::Absent),
@@ -208,7 +256,7 @@ async fn materialize_tree_value_no_access_denied(
             if let Some(file_merge) = conflict.to_file_merge() {
                 let file_merge = file_merge.simplify();
                 let content = extract_as_single_hunk(&file_merge, store, path).await?;
-                materialize_merge_result(&content, &mut contents)
+                materialize_merge_result(&content, marker_style, &mut contents)
                     .expect("Failed to materialize conflict to in-memory buffer");
             } else {
                 // Unless all terms are regular files, we can't do much better than to try to
@@ -233,6 +281,7 @@ async fn materialize_tree_value_no_access_denied(
 
 pub fn materialize_merge_result(
     single_hunk: &Merge<ContentHunk>,
+    marker_style: ConflictMarkerStyle,
     output: &mut dyn Write,
 ) -> std::io::Result<()> {
     let merge_result = files::merge(single_hunk);
@@ -255,71 +304,25 @@ pub fn materialize_merge_result(
                     output.write_all(
                         format!(" Conflict {conflict_index} of {num_conflicts}\n").as_bytes(),
                     )?;
-                    let mut add_index = 0;
-                    for (base_index, left) in hunk.removes().enumerate() {
-                        // The vast majority of conflicts one actually tries to
-                        // resolve manually have 1 base.
-                        let base_str = if hunk.removes().len() == 1 {
-                            "base".to_string()
-                        } else {
-                            format!("base #{}", base_index + 1)
-                        };
-
-                        let right1 = if let Some(right1) = hunk.get_add(add_index) {
-                            right1
-                        } else {
-                            // If we have no more positive terms, emit the remaining negative
-                            // terms as snapshots.
-                            output.write_all(CONFLICT_MINUS_LINE)?;
-                            output.write_all(format!(" Contents of {base_str}\n").as_bytes())?;
+                    // Only 2-sided conflicts can be written with the diff3 or
+                    // two-way markers.
+                    match (marker_style, hunk.iter().as_slice()) {
+                        (ConflictMarkerStyle::Diff3, [left, base, right]) => {
                             output.write_all(&left.0)?;
-                            continue;
-                        };
-                        let diff1 = Diff::by_line([&left.0, &right1.0]).hunks().collect_vec();
-                        // Check if the diff against the next positive term is better. Since
-                        // we want to preserve the order of the terms, we don't match against
-                        // any later positive terms.
-                        if let Some(right2) = hunk.get_add(add_index + 1) {
-                            let diff2 = Diff::by_line([&left.0, &right2.0]).hunks().collect_vec();
-                            if diff_size(&diff2) < diff_size(&diff1) {
-                                // If the next positive term is a better match, emit
-                                // the current positive term as a snapshot and the next
-                                // positive term as a diff.
-                                output.write_all(CONFLICT_PLUS_LINE)?;
-                                output.write_all(
-                                    format!(" Contents of side #{}\n", add_index + 1).as_bytes(),
-                                )?;
-                                output.write_all(&right1.0)?;
-                                output.write_all(CONFLICT_DIFF_LINE)?;
-                                output.write_all(
-                                    format!(
-                                        " Changes from {base_str} to side #{}\n",
-                                        add_index + 2
-                                    )
-                                    .as_bytes(),
-                                )?;
-                                write_diff_hunks(&diff2, output)?;
-                                add_index += 2;
-                                continue;
-                            }
+                            output.write_all(CONFLICT_BASE_LINE)?;
+                            output.write_all(b" base\n")?;
+                            output.write_all(&base.0)?;
+                            output.write_all(CONFLICT_SEPARATOR_LINE)?;
+                            output.write_all(b"\n")?;
+                            output.write_all(&right.0)?;
                         }
-
-                        output.write_all(CONFLICT_DIFF_LINE)?;
-                        output.write_all(
-                            format!(" Changes from {base_str} to side #{}\n", add_index + 1)
-                                .as_bytes(),
-                        )?;
-                        write_diff_hunks(&diff1, output)?;
-                        add_index += 1;
-                    }
-
-                    //  Emit the remaining positive terms as snapshots.
-                    for (add_index, slice) in hunk.adds().enumerate().skip(add_index) {
-                        output.write_all(CONFLICT_PLUS_LINE)?;
-                        output.write_all(
-                            format!(" Contents of side #{}\n", add_index + 1).as_bytes(),
-                        )?;
-                        output.write_all(&slice.0)?;
+                        (ConflictMarkerStyle::TwoWay, [left, _base, right]) => {
+                            output.write_all(&left.0)?;
+                            output.write_all(CONFLICT_SEPARATOR_LINE)?;
+                            output.write_all(b"\n")?;
+                            output.write_all(&right.0)?;
+                        }
+                        _ => materialize_jj_conflict_hunk(&hunk, output)?,
                     }
                     output.write_all(CONFLICT_END_LINE)?;
                     output.write_all(
@@ -332,6 +335,72 @@ pub fn materialize_merge_result(
     Ok(())
 }
 
+/// Writes the terms of a conflict hunk as diffs or snapshots, between the
+/// start and end markers.
+fn materialize_jj_conflict_hunk(
+    hunk: &Merge<ContentHunk>,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut add_index = 0;
+    for (base_index, left) in hunk.removes().enumerate() {
+        // The vast majority of conflicts one actually tries to
+        // resolve manually have 1 base.
+        let base_str = if hunk.removes().len() == 1 {
+            "base".to_string()
+        } else {
+            format!("base #{}", base_index + 1)
+        };
+
+        let right1 = if let Some(right1) = hunk.get_add(add_index) {
+            right1
+        } else {
+            // If we have no more positive terms, emit the remaining negative
+            // terms as snapshots.
+            output.write_all(CONFLICT_MINUS_LINE)?;
+            output.write_all(format!(" Contents of {base_str}\n").as_bytes())?;
+            output.write_all(&left.0)?;
+            continue;
+        };
+        let diff1 = Diff::by_line([&left.0, &right1.0]).hunks().collect_vec();
+        // Check if the diff against the next positive term is better. Since
+        // we want to preserve the order of the terms, we don't match against
+        // any later positive terms.
+        if let Some(right2) = hunk.get_add(add_index + 1) {
+            let diff2 = Diff::by_line([&left.0, &right2.0]).hunks().collect_vec();
+            if diff_size(&diff2) < diff_size(&diff1) {
+                // If the next positive term is a better match, emit
+                // the current positive term as a snapshot and the next
+                // positive term as a diff.
+                output.write_all(CONFLICT_PLUS_LINE)?;
+                output.write_all(format!(" Contents of side #{}\n", add_index + 1).as_bytes())?;
+                output.write_all(&right1.0)?;
+                output.write_all(CONFLICT_DIFF_LINE)?;
+                output.write_all(
+                    format!(" Changes from {base_str} to side #{}\n", add_index + 2).as_bytes(),
+                )?;
+                write_diff_hunks(&diff2, output)?;
+                add_index += 2;
+                continue;
+            }
+        }
+
+        output.write_all(CONFLICT_DIFF_LINE)?;
+        output.write_all(
+            format!(" Changes from {base_str} to side #{}\n", add_index + 1).as_bytes(),
+        )?;
+        write_diff_hunks(&diff1, output)?;
+        add_index += 1;
+    }
+
+    //  Emit the remaining positive terms as snapshots.
+    for (add_index, slice) in hunk.adds().enumerate().skip(add_index) {
+        output.write_all(CONFLICT_PLUS_LINE)?;
+        output.write_all(format!(" Contents of side #{}\n", add_index + 1).as_bytes())?;
+        output.write_all(&slice.0)?;
+    }
+    Ok(())
+}
+
 fn diff_size(hunks: &[DiffHunk]) -> usize {
     hunks
         .iter()
@@ -373,9 +442,26 @@ pub fn materialized_diff_stream<'a>(
 /// conflict markers. The caller has to provide the expected number of merge
 /// sides (adds). Conflict markers that are otherwise valid will be considered
 /// invalid if they don't have the expected arity.
+///
+/// Only markers in the [`ConflictMarkerStyle::Jj`] style are understood.
 // TODO: "parse" is not usually the opposite of "materialize", so maybe we
 // should rename them to "serialize" and "deserialize"?
 pub fn parse_conflict(input: &[u8], num_sides: usize) -> Option<Vec<Merge<ContentHunk>>> {
+    parse_conflict_with_bases(input, num_sides, ConflictMarkerStyle::Jj, &[])
+}
+
+/// Parses conflict markers like [`parse_conflict()`], but also understands the
+/// markers of the diff3 and two-way styles unless `marker_style` is the jj
+/// style. Conflicts with more than two sides are always written in the jj
+/// style, so its markers are understood in any case. The base of a two-way
+/// conflict is taken from the conflict with the same number in
+/// `original_conflicts`.
+fn parse_conflict_with_bases(
+    input: &[u8],
+    num_sides: usize,
+    marker_style: ConflictMarkerStyle,
+    original_conflicts: &[Merge<ContentHunk>],
+) -> Option<Vec<Merge<ContentHunk>>> {
     if input.is_empty() {
         return None;
     }
@@ -384,14 +470,19 @@ pub fn parse_conflict(input: &[u8], num_sides: usize) -> Option<Vec<Merge<Conten
     let mut resolved_start = 0;
     let mut conflict_start = None;
     let mut conflict_start_len = 0;
+    let mut original_conflict = None;
     for line in input.split_inclusive(|b| *b == b'\n') {
         if CONFLICT_MARKER_REGEX.is_match_at(line, 0) {
             if line[0] == CONFLICT_START_LINE_CHAR {
                 conflict_start = Some(pos);
                 conflict_start_len = line.len();
+                original_conflict = CONFLICT_START_LABEL_REGEX
+                    .captures(line)
+                    .and_then(|captures| str::from_utf8(&captures[1]).ok()?.parse().ok())
+                    .and_then(|number: usize| original_conflicts.get(number.checked_sub(1)?));
             } else if conflict_start.is_some() && line[0] == CONFLICT_END_LINE_CHAR {
                 let conflict_body = &input[conflict_start.unwrap() + conflict_start_len..pos];
-                let hunk = parse_conflict_hunk(conflict_body);
+                let hunk = parse_conflict_hunk(conflict_body, marker_style, original_conflict);
                 if hunk.num_sides() == num_sides {
                     let resolved_slice = &input[resolved_start..conflict_start.unwrap()];
                     if !resolved_slice.is_empty() {
@@ -418,7 +509,28 @@ pub fn parse_conflict(input: &[u8], num_sides: usize) -> Option<Vec<Merge<Conten
     }
 }
 
-fn parse_conflict_hunk(input: &[u8]) -> Merge<ContentHunk> {
+fn parse_conflict_hunk(
+    input: &[u8],
+    marker_style: ConflictMarkerStyle,
+    original_conflict: Option<&Merge<ContentHunk>>,
+) -> Merge<ContentHunk> {
+    // The jj style starts with one of its markers right away, whereas the
+    // diff3 and two-way styles start with the contents of the first side.
+    let first_line = input.split_inclusive(|b| *b == b'\n').next();
+    if marker_style != ConflictMarkerStyle::Jj
+        && !first_line.is_some_and(|line| {
+            CONFLICT_MARKER_REGEX.is_match_at(line, 0)
+                && [
+                    CONFLICT_DIFF_LINE_CHAR,
+                    CONFLICT_MINUS_LINE_CHAR,
+                    CONFLICT_PLUS_LINE_CHAR,
+                ]
+                .contains(&line[0])
+        })
+    {
+        return parse_snapshot_conflict_hunk(input, original_conflict);
+    }
+
     enum State {
         Diff,
         Minus,
@@ -480,14 +592,65 @@ fn parse_conflict_hunk(input: &[u8]) -> Merge<ContentHunk> {
     Merge::from_removes_adds(removes, adds)
 }
 
+/// Parses a conflict hunk written in the diff3 or two-way style.
+fn parse_snapshot_conflict_hunk(
+    input: &[u8],
+    original_conflict: Option<&Merge<ContentHunk>>,
+) -> Merge<ContentHunk> {
+    let mut left = vec![];
+    let mut base = None;
+    let mut right = None;
+    for line in input.split_inclusive(|b| *b == b'\n') {
+        let current = if SNAPSHOT_CONFLICT_MARKER_REGEX.is_match_at(line, 0) {
+            match line[0] {
+                CONFLICT_BASE_LINE_CHAR if base.is_none() && right.is_none() => {
+                    base = Some(vec![]);
+                    continue;
+                }
+                CONFLICT_SEPARATOR_LINE_CHAR if right.is_none() => {
+                    right = Some(vec![]);
+                    continue;
+                }
+                // Doesn't look like a conflict
+                _ => return Merge::resolved(ContentHunk(vec![])),
+            }
+        } else if let Some(right) = &mut right {
+            right
+        } else if let Some(base) = &mut base {
+            base
+        } else {
+            &mut left
+        };
+        current.extend_from_slice(line);
+    }
+
+    let Some(right) = right else {
+        // Doesn't look like a conflict
+        return Merge::resolved(ContentHunk(vec![]));
+    };
+    let base = match (base, original_conflict) {
+        (Some(base), _) => ContentHunk(base),
+        (None, Some(original_conflict)) if original_conflict.num_sides() == 2 => {
+            original_conflict.get_remove(0).unwrap().clone()
+        }
+        // The base of a two-way conflict isn't known
+        (None, _) => return Merge::resolved(ContentHunk(vec![])),
+    };
+    Merge::from_removes_adds([base], [ContentHunk(left), ContentHunk(right)])
+}
+
 /// Parses conflict markers in `content` and returns an updated version of
 /// `file_ids` with the new contents. If no (valid) conflict markers remain, a
 /// single resolves `FileId` will be returned.
+///
+/// `marker_style` is the style `content` was materialized in. The diff3 and
+/// two-way markers are only understood if it isn't the jj style.
 pub async fn update_from_content(
     file_ids: &Merge<Option<FileId>>,
     store: &Store,
     path: &RepoPath,
     content: &[u8],
+    marker_style: ConflictMarkerStyle,
 ) -> BackendResult<Merge<Option<FileId>>> {
     let simplified_file_ids = file_ids.clone().simplify();
     let simplified_file_ids = &simplified_file_ids;
@@ -499,17 +662,29 @@ pub async fn update_from_content(
     // copy.
     let mut old_content = Vec::with_capacity(content.len());
     let merge_hunk = extract_as_single_hunk(simplified_file_ids, store, path).await?;
-    materialize_merge_result(&merge_hunk, &mut old_content).unwrap();
+    materialize_merge_result(&merge_hunk, marker_style, &mut old_content).unwrap();
     if content == old_content {
         return Ok(file_ids.clone());
     }
+    let original_conflicts = match files::merge(&merge_hunk) {
+        MergeResult::Resolved(_) => vec![],
+        MergeResult::Conflict(hunks) => hunks
+            .into_iter()
+            .filter(|hunk| hunk.as_resolved().is_none())
+            .collect(),
+    };
 
     // Parse conflicts from the new content using the arity of the simplified
     // conflicts initially. If unsuccessful, attempt to parse conflicts from with
     // the arity of the unsimplified conflicts since such a conflict may be
     // present in the working copy if written by an earlier version of jj.
     let (used_file_ids, hunks) = 'hunks: {
-        if let Some(hunks) = parse_conflict(content, simplified_file_ids.num_sides()) {
+        if let Some(hunks) = parse_conflict_with_bases(
+            content,
+            simplified_file_ids.num_sides(),
+            marker_style,
+            &original_conflicts,
+        ) {
             break 'hunks (simplified_file_ids, hunks);
         };
         if simplified_file_ids.num_sides() != file_ids.num_sides() {
@@ -49,6 +49,7 @@ use crate::object_id::PrefixResolution;
 use crate::revset::ResolvedExpression;
 use crate::revset::Revset;
 use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetEvaluationOptions;
 use crate::store::Store;
 
 pub(super) trait IndexSegment: Send + Sync {
@@ -416,8 +417,9 @@ impl CompositeIndex {
         &self,
         expression: &ResolvedExpression,
         store: &Arc<Store>,
+        options: &RevsetEvaluationOptions,
     ) -> Result<Box<dyn Revset + '_>, RevsetEvaluationError> {
-        let revset_impl = revset_engine::evaluate(expression, store, self)?;
+        let revset_impl = revset_engine::evaluate(expression, store, self, options)?;
         Ok(Box::new(revset_impl))
     }
 }
@@ -502,8 +504,9 @@ impl Index for &CompositeIndex {
         &'index self,
         expression: &ResolvedExpression,
         store: &Arc<Store>,
+        options: &RevsetEvaluationOptions,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        CompositeIndex::evaluate_revset(self, expression, store)
+        CompositeIndex::evaluate_revset(self, expression, store, options)
     }
 }
 
@@ -59,6 +59,7 @@ use crate::object_id::PrefixResolution;
 use crate::revset::ResolvedExpression;
 use crate::revset::Revset;
 use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetEvaluationOptions;
 use crate::store::Store;
 
 #[derive(Debug)]
@@ -525,8 +526,10 @@ impl Index for DefaultMutableIndex {
         &'index self,
         expression: &ResolvedExpression,
         store: &Arc<Store>,
+        options: &RevsetEvaluationOptions,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        self.as_composite().evaluate_revset(expression, store)
+        self.as_composite()
+            .evaluate_revset(expression, store, options)
     }
 }
 
@@ -19,8 +19,11 @@ use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::collections::BTreeSet;
 use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
+use std::io;
+use std::io::Read as _;
 use std::iter;
 use std::ops::Range;
 use std::rc::Rc;
@@ -40,7 +43,10 @@ use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
+use crate::backend::FileId;
 use crate::backend::MillisSinceEpoch;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::conflicts::materialize_tree_value;
 use crate::conflicts::MaterializedTreeValue;
@@ -48,15 +54,22 @@ use crate::default_index::AsCompositeIndex;
 use crate::default_index::CompositeIndex;
 use crate::default_index::IndexPosition;
 use crate::graph::GraphEdge;
+use crate::matchers::EverythingMatcher;
+use crate::matchers::FilesMatcher;
 use crate::matchers::Matcher;
 use crate::matchers::Visit;
 use crate::merged_tree::resolve_file_values;
+use crate::merged_tree::MergedTree;
 use crate::repo_path::RepoPath;
+use crate::revset::FileChangeKind;
+use crate::revset::LatestSortKey;
 use crate::revset::ResolvedExpression;
 use crate::revset::ResolvedPredicateExpression;
 use crate::revset::Revset;
 use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetEvaluationOptions;
 use crate::revset::RevsetFilterPredicate;
+use crate::revset::SortKey;
 use crate::revset::GENERATION_RANGE_FULL;
 use crate::rewrite;
 use crate::store::Store;
@@ -114,11 +127,25 @@ impl<T: InternalRevset + ?Sized> InternalRevset for Box<T> {
 pub struct RevsetImpl<I> {
     inner: Box<dyn InternalRevset>,
     index: I,
+    /// Iteration order of the positions if the revset was sorted by
+    /// `sorted()`. The graph iteration is always topological.
+    sorted_positions: Option<Vec<IndexPosition>>,
 }
 
 impl<I: AsCompositeIndex + Clone> RevsetImpl<I> {
     fn new(inner: Box<dyn InternalRevset>, index: I) -> Self {
-        Self { inner, index }
+        Self {
+            inner,
+            index,
+            sorted_positions: None,
+        }
+    }
+
+    fn with_sorted_positions(self, positions: Vec<IndexPosition>) -> Self {
+        Self {
+            sorted_positions: Some(positions),
+            ..self
+        }
     }
 
     fn positions(&self) -> impl Iterator<Item = IndexPosition> + '_ {
@@ -150,6 +177,13 @@ impl<I: AsCompositeIndex + Clone> Revset for RevsetImpl<I> {
         Self: 'a,
     {
         let index = self.index.clone();
+        if let Some(positions) = &self.sorted_positions {
+            let mut positions = positions.clone().into_iter();
+            return Box::new(iter::from_fn(move || {
+                let pos = positions.next()?;
+                Some(index.as_composite().entry_by_pos(pos).commit_id())
+            }));
+        }
         let mut walk = self.inner.positions();
         Box::new(iter::from_fn(move || {
             let index = index.as_composite();
@@ -163,6 +197,14 @@ impl<I: AsCompositeIndex + Clone> Revset for RevsetImpl<I> {
         Self: 'a,
     {
         let index = self.index.clone();
+        if let Some(positions) = &self.sorted_positions {
+            let mut positions = positions.clone().into_iter();
+            return Box::new(iter::from_fn(move || {
+                let pos = positions.next()?;
+                let entry = index.as_composite().entry_by_pos(pos);
+                Some((entry.commit_id(), entry.change_id()))
+            }));
+        }
         let mut walk = self.inner.positions();
         Box::new(iter::from_fn(move || {
             let index = index.as_composite();
@@ -725,11 +767,18 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
     expression: &ResolvedExpression,
     store: &Arc<Store>,
     index: I,
+    options: &RevsetEvaluationOptions,
 ) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
     let context = EvaluationContext {
         store: store.clone(),
         index: index.as_composite(),
+        max_commits: options.max_commits,
     };
+    if let ResolvedExpression::Sorted { candidates, key } = expression {
+        let internal_revset = context.evaluate(candidates)?;
+        let positions = context.sort_positions(internal_revset.as_ref(), *key);
+        return Ok(RevsetImpl::new(internal_revset, index).with_sorted_positions(positions));
+    }
     let internal_revset = context.evaluate(expression)?;
     Ok(RevsetImpl::new(internal_revset, index))
 }
@@ -737,6 +786,7 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
 struct EvaluationContext<'index> {
     store: Arc<Store>,
     index: &'index CompositeIndex,
+    max_commits: Option<usize>,
 }
 
 fn to_u32_generation_range(range: &Range<u64>) -> Result<Range<u32>, RevsetEvaluationError> {
@@ -836,30 +886,125 @@ impl<'index> EvaluationContext<'index> {
                         predicate,
                     }))
                 } else if generation_from_roots == &GENERATION_RANGE_FULL {
-                    let mut positions = builder.descendants(root_positions).collect_vec();
+                    let mut positions =
+                        self.collect_positions(builder.descendants(root_positions))?;
                     positions.reverse();
                     Ok(Box::new(EagerRevset { positions }))
                 } else {
                     // For small generation range, it might be better to build a reachable map
                     // with generation bit set, which can be calculated incrementally from roots:
                     //   reachable[pos] = (reachable[parent_pos] | ...) << 1
-                    let mut positions = builder
-                        .descendants_filtered_by_generation(
-                            root_positions,
-                            to_u32_generation_range(generation_from_roots)?,
-                        )
-                        .map(|Reverse(pos)| pos)
-                        .collect_vec();
+                    let mut positions = self.collect_positions(
+                        builder
+                            .descendants_filtered_by_generation(
+                                root_positions,
+                                to_u32_generation_range(generation_from_roots)?,
+                            )
+                            .map(|Reverse(pos)| pos),
+                    )?;
                     positions.reverse();
                     Ok(Box::new(EagerRevset { positions }))
                 }
             }
+            ResolvedExpression::FirstParentDagRange {
+                roots,
+                heads,
+                generation_from_roots,
+            } => {
+                let root_set: HashSet<_> =
+                    self.evaluate(roots)?.positions().attach(index).collect();
+                let head_set = self.evaluate(heads)?;
+                let head_positions = head_set.positions().attach(index);
+                let candidates = self.collect_positions(
+                    RevWalkBuilder::new(index)
+                        .wanted_heads(head_positions)
+                        .descendants(root_set.iter().copied()),
+                )?;
+
+                // Parents are always at lower positions than their children, so
+                // the first-parent generations can be calculated incrementally
+                // from the oldest commit.
+                let mut generations: HashMap<IndexPosition, u64> = HashMap::new();
+                let mut positions = vec![];
+                for pos in candidates {
+                    let generation = if root_set.contains(&pos) {
+                        0
+                    } else {
+                        let entry = index.entry_by_pos(pos);
+                        let first_parent_generation = entry
+                            .parent_positions()
+                            .first()
+                            .and_then(|parent_pos| generations.get(parent_pos));
+                        match first_parent_generation {
+                            Some(generation) => generation + 1,
+                            None => continue,
+                        }
+                    };
+                    if generation >= generation_from_roots.end {
+                        continue;
+                    }
+                    generations.insert(pos, generation);
+                    if generation_from_roots.contains(&generation) {
+                        positions.push(pos);
+                    }
+                }
+                positions.reverse();
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::DagRangeWithin {
+                roots,
+                heads,
+                domain,
+            } => {
+                let domain_revset = self.evaluate(domain)?;
+                let domain_vec = self.collect_positions(domain_revset.positions().attach(index))?;
+                let domain_set: HashSet<_> = domain_vec.iter().copied().collect();
+                let root_set: HashSet<_> =
+                    self.evaluate(roots)?.positions().attach(index).collect();
+                let head_set: HashSet<_> =
+                    self.evaluate(heads)?.positions().attach(index).collect();
+
+                // Descendants of the roots within the domain. Parents are always
+                // at lower positions than their children, so walk the domain from
+                // the oldest commit.
+                let mut descendants = HashSet::new();
+                for &pos in domain_vec.iter().rev() {
+                    if root_set.contains(&pos)
+                        || index
+                            .entry_by_pos(pos)
+                            .parent_positions()
+                            .iter()
+                            .any(|parent_pos| descendants.contains(parent_pos))
+                    {
+                        descendants.insert(pos);
+                    }
+                }
+
+                // Ancestors of the heads within the domain, restricted to the
+                // descendants of the roots.
+                let mut ancestors = HashSet::new();
+                let mut positions = vec![];
+                for &pos in &domain_vec {
+                    if !head_set.contains(&pos) && !ancestors.contains(&pos) {
+                        continue;
+                    }
+                    for parent_pos in index.entry_by_pos(pos).parent_positions() {
+                        if domain_set.contains(&parent_pos) {
+                            ancestors.insert(parent_pos);
+                        }
+                    }
+                    if descendants.contains(&pos) {
+                        positions.push(pos);
+                    }
+                }
+                Ok(Box::new(EagerRevset { positions }))
+            }
             ResolvedExpression::Reachable { sources, domain } => {
                 let mut sets = union_find::UnionFind::<IndexPosition>::new();
 
                 // Compute all reachable subgraphs.
                 let domain_revset = self.evaluate(domain)?;
-                let domain_vec = domain_revset.positions().attach(index).collect_vec();
+                let domain_vec = self.collect_positions(domain_revset.positions().attach(index))?;
                 let domain_set: HashSet<_> = domain_vec.iter().copied().collect();
                 for pos in &domain_set {
                     for parent_pos in index.entry_by_pos(*pos).parent_positions() {
@@ -887,17 +1032,17 @@ impl<'index> EvaluationContext<'index> {
             }
             ResolvedExpression::Heads(candidates) => {
                 let candidate_set = self.evaluate(candidates)?;
+                let candidate_positions =
+                    self.collect_positions(candidate_set.positions().attach(index))?;
                 let head_positions: BTreeSet<_> =
-                    index.heads_pos(candidate_set.positions().attach(index).collect());
+                    index.heads_pos(candidate_positions.into_iter().collect());
                 let positions = head_positions.into_iter().rev().collect();
                 Ok(Box::new(EagerRevset { positions }))
             }
             ResolvedExpression::Roots(candidates) => {
-                let mut positions = self
-                    .evaluate(candidates)?
-                    .positions()
-                    .attach(index)
-                    .collect_vec();
+                let candidate_set = self.evaluate(candidates)?;
+                let mut positions =
+                    self.collect_positions(candidate_set.positions().attach(index))?;
                 let filled = RevWalkBuilder::new(index)
                     .wanted_heads(positions.iter().copied())
                     .descendants(positions.iter().copied())
@@ -911,10 +1056,26 @@ impl<'index> EvaluationContext<'index> {
                 });
                 Ok(Box::new(EagerRevset { positions }))
             }
-            ResolvedExpression::Latest { candidates, count } => {
+            ResolvedExpression::Latest {
+                candidates,
+                count,
+                key,
+            } => {
+                let candidate_set = self.evaluate(candidates)?;
+                Ok(Box::new(self.take_latest_revset(
+                    candidate_set.as_ref(),
+                    *count,
+                    *key,
+                )))
+            }
+            // The order can't be preserved once combined with other sets.
+            ResolvedExpression::Sorted { .. } => Err(RevsetEvaluationError::Other(
+                "sorted() can only be used as the outermost function".to_owned(),
+            )),
+            ResolvedExpression::DistinctChangeIds(candidates) => {
                 let candidate_set = self.evaluate(candidates)?;
                 Ok(Box::new(
-                    self.take_latest_revset(candidate_set.as_ref(), *count),
+                    self.take_distinct_change_ids_revset(candidate_set.as_ref()),
                 ))
             }
             ResolvedExpression::Union(expression1, expression2) => {
@@ -953,6 +1114,26 @@ impl<'index> EvaluationContext<'index> {
             ResolvedPredicateExpression::Set(expression) => {
                 Ok(self.evaluate(expression)?.into_predicate())
             }
+            ResolvedPredicateExpression::ChangedSince(reference) => {
+                let positions = self.evaluate(reference)?.positions().attach(self.index);
+                let [pos] = positions.take(2).collect_vec()[..] else {
+                    return Err(RevsetEvaluationError::Other(
+                        "The argument of changed_since() must be a single revision".to_owned(),
+                    ));
+                };
+                let entry = self.index.entry_by_pos(pos);
+                let reference_tree = self
+                    .store
+                    .get_commit(&entry.commit_id())
+                    .and_then(|commit| commit.tree())
+                    .map_err(RevsetEvaluationError::StoreError)?;
+                let store = self.store.clone();
+                Ok(box_pure_predicate_fn(move |index, pos| {
+                    let entry = index.entry_by_pos(pos);
+                    let commit = store.get_commit(&entry.commit_id()).unwrap();
+                    has_diff_since_reference(&store, index, &commit, &reference_tree).unwrap()
+                }))
+            }
             ResolvedPredicateExpression::NotIn(complement) => {
                 let set = self.evaluate_predicate(complement)?;
                 Ok(Box::new(NotInPredicate(set)))
@@ -965,6 +1146,24 @@ impl<'index> EvaluationContext<'index> {
         }
     }
 
+    /// Collects `positions` in memory, failing once the number of positions
+    /// exceeds the `max_commits` limit.
+    fn collect_positions(
+        &self,
+        positions: impl IntoIterator<Item = IndexPosition>,
+    ) -> Result<Vec<IndexPosition>, RevsetEvaluationError> {
+        let mut collected = vec![];
+        for pos in positions {
+            if let Some(max_commits) = self.max_commits {
+                if collected.len() >= max_commits {
+                    return Err(RevsetEvaluationError::LimitExceeded(max_commits));
+                }
+            }
+            collected.push(pos);
+        }
+        Ok(collected)
+    }
+
     fn revset_for_commit_ids(&self, commit_ids: &[CommitId]) -> EagerRevset {
         let mut positions = commit_ids
             .iter()
@@ -975,7 +1174,12 @@ impl<'index> EvaluationContext<'index> {
         EagerRevset { positions }
     }
 
-    fn take_latest_revset(&self, candidate_set: &dyn InternalRevset, count: usize) -> EagerRevset {
+    fn take_latest_revset(
+        &self,
+        candidate_set: &dyn InternalRevset,
+        count: usize,
+        key: LatestSortKey,
+    ) -> EagerRevset {
         if count == 0 {
             return EagerRevset::empty();
         }
@@ -989,8 +1193,12 @@ impl<'index> EvaluationContext<'index> {
         let make_rev_item = |pos| {
             let entry = self.index.entry_by_pos(pos);
             let commit = self.store.get_commit(&entry.commit_id()).unwrap();
+            let signature = match key {
+                LatestSortKey::CommitterDate => commit.committer(),
+                LatestSortKey::AuthorDate => commit.author(),
+            };
             Reverse(Item {
-                timestamp: commit.committer().timestamp.timestamp,
+                timestamp: signature.timestamp.timestamp,
                 pos: entry.position(),
             })
         };
@@ -1019,6 +1227,58 @@ impl<'index> EvaluationContext<'index> {
         positions.sort_unstable_by_key(|&pos| Reverse(pos));
         EagerRevset { positions }
     }
+
+    /// Picks the latest commit (by committer timestamp) of each change ID.
+    fn take_distinct_change_ids_revset(&self, candidate_set: &dyn InternalRevset) -> EagerRevset {
+        let mut latest_by_change_id: HashMap<ChangeId, (MillisSinceEpoch, IndexPosition)> =
+            HashMap::new();
+        for pos in candidate_set.positions().attach(self.index) {
+            let entry = self.index.entry_by_pos(pos);
+            let commit = self.store.get_commit(&entry.commit_id()).unwrap();
+            // Position is the tie-breaker
+            let item = (commit.committer().timestamp.timestamp, pos);
+            latest_by_change_id
+                .entry(entry.change_id())
+                .and_modify(|latest| *latest = (*latest).max(item))
+                .or_insert(item);
+        }
+        let mut positions = latest_by_change_id
+            .into_values()
+            .map(|(_, pos)| pos)
+            .collect_vec();
+        positions.sort_unstable_by_key(|&pos| Reverse(pos));
+        EagerRevset { positions }
+    }
+
+    /// Returns the positions of the set ordered by the `key`.
+    fn sort_positions(
+        &self,
+        candidate_set: &dyn InternalRevset,
+        key: SortKey,
+    ) -> Vec<IndexPosition> {
+        let positions = candidate_set.positions().attach(self.index);
+        let signature_key = match key {
+            SortKey::CommitterDate => Commit::committer,
+            SortKey::AuthorDate => Commit::author,
+            SortKey::CommitId => {
+                let mut items = positions
+                    .map(|pos| (self.index.entry_by_pos(pos).commit_id(), pos))
+                    .collect_vec();
+                items.sort_unstable();
+                return items.into_iter().map(|(_, pos)| pos).collect();
+            }
+        };
+        let mut items = positions
+            .map(|pos| {
+                let entry = self.index.entry_by_pos(pos);
+                let commit = self.store.get_commit(&entry.commit_id()).unwrap();
+                // Position is the tie-breaker
+                (signature_key(&commit).timestamp.timestamp, pos)
+            })
+            .collect_vec();
+        items.sort_unstable_by(|a, b| b.cmp(a));
+        items.into_iter().map(|(_, pos)| pos).collect()
+    }
 }
 
 struct PurePredicateFn<F>(F);
@@ -1085,6 +1345,22 @@ fn build_predicate_fn(
                 pattern.matches(&commit.author().name) || pattern.matches(&commit.author().email)
             })
         }
+        RevsetFilterPredicate::AuthorName(pattern) => {
+            let pattern = pattern.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                pattern.matches(&commit.author().name)
+            })
+        }
+        RevsetFilterPredicate::AuthorEmail(pattern) => {
+            let pattern = pattern.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                pattern.matches(&commit.author().email)
+            })
+        }
         RevsetFilterPredicate::Committer(pattern) => {
             let pattern = pattern.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1094,6 +1370,22 @@ fn build_predicate_fn(
                     || pattern.matches(&commit.committer().email)
             })
         }
+        RevsetFilterPredicate::CommitterName(pattern) => {
+            let pattern = pattern.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                pattern.matches(&commit.committer().name)
+            })
+        }
+        RevsetFilterPredicate::CommitterEmail(pattern) => {
+            let pattern = pattern.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                pattern.matches(&commit.committer().email)
+            })
+        }
         RevsetFilterPredicate::AuthorDate(expression) => {
             let expression = *expression;
             box_pure_predicate_fn(move |index, pos| {
@@ -1120,14 +1412,44 @@ fn build_predicate_fn(
                 has_diff_from_parent(&store, index, &commit, matcher.as_ref()).unwrap()
             })
         }
-        RevsetFilterPredicate::DiffContains { text, files } => {
+        RevsetFilterPredicate::FileChange { files, kind } => {
+            let matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            let kind = *kind;
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                has_file_change_from_parent(&store, index, &commit, matcher.as_ref(), kind).unwrap()
+            })
+        }
+        RevsetFilterPredicate::DiffContains {
+            text,
+            files,
+            max_file_size,
+        } => {
             let text_pattern = text.clone();
             let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            let max_file_size = *max_file_size;
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                matches_diff_from_parent(
+                    &store,
+                    index,
+                    &commit,
+                    &text_pattern,
+                    &*files_matcher,
+                    max_file_size,
+                )
+                .unwrap()
+            })
+        }
+        RevsetFilterPredicate::ChangedFileCount(count_range) => {
+            let count_range = count_range.clone();
             box_pure_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id()).unwrap();
-                matches_diff_from_parent(&store, index, &commit, &text_pattern, &*files_matcher)
-                    .unwrap()
+                let count = count_changed_files(&store, index, &commit, count_range.end).unwrap();
+                count_range.contains(&count)
             })
         }
         RevsetFilterPredicate::HasConflict => box_pure_predicate_fn(move |index, pos| {
@@ -1135,6 +1457,49 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id()).unwrap();
             commit.has_conflict().unwrap()
         }),
+        RevsetFilterPredicate::ConflictInFiles(expr) => {
+            let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                // Fast path: no need to load the tree of resolved commits
+                commit.has_conflict().unwrap()
+                    && commit
+                        .tree()
+                        .unwrap()
+                        .conflicts()
+                        .any(|(path, _)| matcher.matches(&path))
+            })
+        }
+        RevsetFilterPredicate::TreeSize(threshold) => {
+            let threshold = *threshold;
+            // Counting one byte over the threshold is enough to tell that the
+            // tree is larger, so the rest of the tree doesn't have to be read.
+            let counter = Rc::new(RefCell::new(TreeSizeCounter::new(
+                store.clone(),
+                threshold.saturating_add(1),
+            )));
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                let size = counter.borrow_mut().commit_tree_size(&commit).unwrap();
+                size > threshold
+            })
+        }
+        RevsetFilterPredicate::SameTreeAsParent(parent_index) => {
+            let parent_index = *parent_index;
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id()).unwrap();
+                commit
+                    .parent_ids()
+                    .get(parent_index)
+                    .map_or(false, |parent_id| {
+                        let parent = store.get_commit(parent_id).unwrap();
+                        parent.tree_id() == commit.tree_id()
+                    })
+            })
+        }
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1182,12 +1547,143 @@ fn has_diff_from_parent(
     .block_on()
 }
 
+/// Checks whether the commit changes any file that differs between its parents
+/// and the `reference_tree`.
+fn has_diff_since_reference(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+    reference_tree: &MergedTree,
+) -> BackendResult<bool> {
+    let parents: Vec<_> = commit.parents().try_collect()?;
+    if let [parent] = parents.as_slice() {
+        // Fast path: no need to load the root tree
+        if commit.tree_id() == parent.tree_id() {
+            return Ok(false);
+        }
+    }
+
+    let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
+    let to_tree = commit.tree()?;
+    let changed_paths = async {
+        let mut changed_paths = vec![];
+        let mut tree_diff = from_tree.diff_stream(&to_tree, &EverythingMatcher);
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value)?;
+            if from_value != to_value {
+                changed_paths.push(entry.path);
+            }
+        }
+        Ok::<_, BackendError>(changed_paths)
+    }
+    .block_on()?;
+    if changed_paths.is_empty() {
+        return Ok(false);
+    }
+    let matcher = FilesMatcher::new(changed_paths);
+    let mut tree_diff = from_tree.diff_stream(reference_tree, &matcher);
+    async {
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value)?;
+            if from_value != to_value {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    .block_on()
+}
+
+/// Checks whether the commit makes the given `kind` of change to any of the
+/// matching files. For merge commits, the changes are relative to the
+/// auto-merged parents, so a file that was added in only one of the parents
+/// isn't considered added by the merge.
+fn has_file_change_from_parent(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+    kind: FileChangeKind,
+) -> BackendResult<bool> {
+    let parents: Vec<_> = commit.parents().try_collect()?;
+    if let [parent] = parents.as_slice() {
+        // Fast path: no need to load the root tree
+        if commit.tree_id() == parent.tree_id() {
+            return Ok(false);
+        }
+    }
+
+    // Conflict resolution is expensive, try that only for matched files.
+    let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
+    let to_tree = commit.tree()?;
+    // TODO: handle copy tracking
+    let mut tree_diff = from_tree.diff_stream(&to_tree, matcher);
+    async {
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value)?;
+            if from_value == to_value {
+                continue;
+            }
+            let matches = match kind {
+                FileChangeKind::Added => from_value.is_absent(),
+                FileChangeKind::Removed => to_value.is_absent(),
+                FileChangeKind::Modified => from_value.is_present() && to_value.is_present(),
+            };
+            if matches {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    .block_on()
+}
+
+/// Counts files changed from the parents, stopping once `limit` is reached.
+fn count_changed_files(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+    limit: u32,
+) -> BackendResult<u32> {
+    let parents: Vec<_> = commit.parents().try_collect()?;
+    if let [parent] = parents.as_slice() {
+        // Fast path: no need to load the root tree
+        if commit.tree_id() == parent.tree_id() {
+            return Ok(0);
+        }
+    }
+
+    let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
+    let to_tree = commit.tree()?;
+    let mut tree_diff = from_tree.diff_stream(&to_tree, &EverythingMatcher);
+    async {
+        let mut count = 0;
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value)?;
+            if from_value == to_value {
+                continue;
+            }
+            count += 1;
+            if count >= limit {
+                break;
+            }
+        }
+        Ok(count)
+    }
+    .block_on()
+}
+
 fn matches_diff_from_parent(
     store: &Arc<Store>,
     index: &CompositeIndex,
     commit: &Commit,
     text_pattern: &StringPattern,
     files_matcher: &dyn Matcher,
+    max_file_size: Option<u64>,
 ) -> BackendResult<bool> {
     let parents: Vec<_> = commit.parents().try_collect()?;
     // Conflict resolution is expensive, try that only for matched files.
@@ -1207,8 +1703,12 @@ fn matches_diff_from_parent(
             let left_future = materialize_tree_value(store, &entry.path, left_value);
             let right_future = materialize_tree_value(store, &entry.path, right_value);
             let (left_value, right_value) = futures::try_join!(left_future, right_future)?;
-            let left_content = to_file_content(&entry.path, left_value)?;
-            let right_content = to_file_content(&entry.path, right_value)?;
+            let left_content = to_file_content(&entry.path, left_value, max_file_size)?;
+            let right_content = to_file_content(&entry.path, right_value, max_file_size)?;
+            // Files too large to scan are treated as if they didn't match.
+            let (Some(left_content), Some(right_content)) = (left_content, right_content) else {
+                continue;
+            };
             // Filter lines prior to comparison. This might produce inferior
             // hunks due to lack of contexts, but is way faster than full diff.
             let left_lines = match_lines(&left_content, text_pattern);
@@ -1235,27 +1735,114 @@ fn match_lines<'a: 'b, 'b>(
     })
 }
 
-fn to_file_content(path: &RepoPath, value: MaterializedTreeValue) -> BackendResult<Vec<u8>> {
-    match value {
-        MaterializedTreeValue::Absent => Ok(vec![]),
-        MaterializedTreeValue::AccessDenied(_) => Ok(vec![]),
-        MaterializedTreeValue::File { id, mut reader, .. } => {
+/// Counts the total size of files in trees, up to `limit` bytes. Larger sizes
+/// are reported as `limit`, so big trees don't have to be read entirely. Most
+/// subtrees and files are shared between commits, so their sizes are
+/// remembered.
+struct TreeSizeCounter {
+    store: Arc<Store>,
+    limit: u64,
+    tree_sizes: HashMap<TreeId, u64>,
+    file_sizes: HashMap<FileId, u64>,
+}
+
+impl TreeSizeCounter {
+    fn new(store: Arc<Store>, limit: u64) -> Self {
+        TreeSizeCounter {
+            store,
+            limit,
+            tree_sizes: HashMap::new(),
+            file_sizes: HashMap::new(),
+        }
+    }
+
+    /// Calculates the total size of files in the commit's tree. If the tree is
+    /// conflicted, the size of the largest side is returned.
+    fn commit_tree_size(&mut self, commit: &Commit) -> BackendResult<u64> {
+        let mut max_size = 0;
+        for tree_id in commit.tree_id().to_merge().adds() {
+            let size = self.tree_size(RepoPath::root(), tree_id)?;
+            max_size = max_size.max(size);
+        }
+        Ok(max_size)
+    }
+
+    fn tree_size(&mut self, dir: &RepoPath, tree_id: &TreeId) -> BackendResult<u64> {
+        if let Some(&size) = self.tree_sizes.get(tree_id) {
+            return Ok(size);
+        }
+        let tree = self.store.get_tree(dir, tree_id)?;
+        let mut size: u64 = 0;
+        for entry in tree.entries_non_recursive() {
+            let path = dir.join(entry.name());
+            let entry_size = match entry.value() {
+                TreeValue::File { id, .. } => self.file_size(&path, id)?,
+                TreeValue::Tree(id) => self.tree_size(&path, id)?,
+                TreeValue::Symlink(_) | TreeValue::GitSubmodule(_) | TreeValue::Conflict(_) => 0,
+            };
+            size = size.saturating_add(entry_size);
+            if size >= self.limit {
+                break;
+            }
+        }
+        let size = size.min(self.limit);
+        self.tree_sizes.insert(tree_id.clone(), size);
+        Ok(size)
+    }
+
+    fn file_size(&mut self, path: &RepoPath, id: &FileId) -> BackendResult<u64> {
+        if let Some(&size) = self.file_sizes.get(id) {
+            return Ok(size);
+        }
+        let reader = self.store.read_file(path, id)?;
+        let size = io::copy(&mut reader.take(self.limit), &mut io::sink()).map_err(|err| {
+            BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            }
+        })?;
+        self.file_sizes.insert(id.clone(), size);
+        Ok(size)
+    }
+}
+
+/// Reads the file content, or returns `None` if the content is larger than
+/// `max_size`.
+fn to_file_content(
+    path: &RepoPath,
+    value: MaterializedTreeValue,
+    max_size: Option<u64>,
+) -> BackendResult<Option<Vec<u8>>> {
+    let content = match value {
+        MaterializedTreeValue::Absent => vec![],
+        MaterializedTreeValue::AccessDenied(_) => vec![],
+        MaterializedTreeValue::File { id, reader, .. } => {
+            // Read one more byte than the limit to detect oversized files
+            // without loading them entirely.
+            let limit = max_size.map_or(u64::MAX, |size| size.saturating_add(1));
             let mut content = vec![];
             reader
+                .take(limit)
                 .read_to_end(&mut content)
                 .map_err(|err| BackendError::ReadFile {
                     path: path.to_owned(),
                     id: id.clone(),
                     source: err.into(),
                 })?;
-            Ok(content)
+            content
         }
-        MaterializedTreeValue::Symlink { id: _, target } => Ok(target.into_bytes()),
-        MaterializedTreeValue::GitSubmodule(_) => Ok(vec![]),
-        MaterializedTreeValue::Conflict { contents, .. } => Ok(contents),
+        MaterializedTreeValue::Symlink { id: _, target } => target.into_bytes(),
+        MaterializedTreeValue::GitSubmodule(_) => vec![],
+        MaterializedTreeValue::Conflict { contents, .. } => contents,
         MaterializedTreeValue::Tree(id) => {
             panic!("Unexpected tree with id {id:?} in diff at path {path:?}");
         }
+    };
+    if max_size.is_some_and(|size| content.len() as u64 > size) {
+        Ok(None)
+    } else {
+        Ok(Some(content))
     }
 }
 
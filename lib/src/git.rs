@@ -45,6 +45,7 @@ use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::revset::RevsetExpression;
 use crate::settings::GitSettings;
+use crate::settings::LocalBranchUpdate;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::view::View;
@@ -319,7 +320,13 @@ pub fn import_some_refs(
             }
             RefName::RemoteBranch { branch, remote } => {
                 if new_remote_ref.is_tracking() {
-                    mut_repo.merge_local_branch(branch, base_target, &new_remote_ref.target);
+                    update_local_branch(
+                        mut_repo,
+                        branch,
+                        base_target,
+                        &new_remote_ref.target,
+                        git_settings.local_branch_update,
+                    );
                 }
                 // Remote-tracking branch is the last known state of the branch in the remote.
                 // It shouldn't diverge even if we had inconsistent view.
@@ -346,6 +353,35 @@ pub fn import_some_refs(
     Ok(stats)
 }
 
+/// Updates the local branch tracking a changed remote branch according to the
+/// `policy`.
+fn update_local_branch(
+    mut_repo: &mut MutableRepo,
+    branch: &str,
+    base_target: &RefTarget,
+    new_remote_target: &RefTarget,
+    policy: LocalBranchUpdate,
+) {
+    match policy {
+        LocalBranchUpdate::Merge => {
+            mut_repo.merge_local_branch(branch, base_target, new_remote_target);
+        }
+        LocalBranchUpdate::FastForward => {
+            let local_target = mut_repo.get_local_branch(branch);
+            let is_fast_forward = match (local_target.as_normal(), new_remote_target.as_normal()) {
+                _ if local_target == *new_remote_target => false,
+                (None, Some(_)) => local_target.is_absent(),
+                (Some(local_id), Some(new_id)) => mut_repo.index().is_ancestor(local_id, new_id),
+                _ => false,
+            };
+            if is_fast_forward {
+                mut_repo.set_local_branch_target(branch, new_remote_target.clone());
+            }
+        }
+        LocalBranchUpdate::Skip => {}
+    }
+}
+
 /// Finds commits that used to be reachable in git that no longer are reachable.
 /// Those commits will be recorded as abandoned in the `MutableRepo`.
 fn abandon_unreachable_commits(
@@ -1327,6 +1363,8 @@ pub enum GitPushError {
     RemoteReservedForLocalGitRepo,
     #[error("Refs in unexpected location: {0:?}")]
     RefInUnexpectedLocation(Vec<String>),
+    #[error("Refs moved on the remote since they were last fetched: {0:?}")]
+    RefLeaseBroken(Vec<String>),
     #[error("Remote rejected the update of some refs (do you have permission to push to {0:?}?)")]
     RefUpdateRejected(Vec<String>),
     // TODO: I'm sure there are other errors possible, such as transport-level errors,
@@ -1338,6 +1376,15 @@ pub enum GitPushError {
 #[derive(Clone, Debug)]
 pub struct GitBranchPushTargets {
     pub branch_updates: Vec<(String, BranchPushUpdate)>,
+    /// Refuse to update a remote branch unless it is exactly at the position
+    /// of the local remote-tracking branch. Without this, pushes are allowed
+    /// if the remote branch moved in a way that fetching would resolve
+    /// cleanly.
+    pub force_with_lease: bool,
+    /// Branches to update regardless of their position on the remote. The
+    /// other branches are still checked against their remote-tracking
+    /// branches.
+    pub forced_branches: HashSet<String>,
 }
 
 pub struct GitRefUpdate {
@@ -1367,7 +1414,20 @@ pub fn push_branches(
             new_target: update.new_target.clone(),
         })
         .collect_vec();
-    push_updates(mut_repo, git_repo, remote_name, &ref_updates, callbacks)?;
+    let forced_refs = targets
+        .forced_branches
+        .iter()
+        .map(|branch_name| format!("refs/heads/{branch_name}"))
+        .collect();
+    push_ref_updates(
+        mut_repo,
+        git_repo,
+        remote_name,
+        &ref_updates,
+        targets.force_with_lease,
+        &forced_refs,
+        callbacks,
+    )?;
 
     // TODO: add support for partially pushed refs? we could update the view
     // excluding rejected refs, but the transaction would be aborted anyway
@@ -1392,6 +1452,26 @@ pub fn push_updates(
     remote_name: &str,
     updates: &[GitRefUpdate],
     callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
+    push_ref_updates(
+        repo,
+        git_repo,
+        remote_name,
+        updates,
+        false,
+        &HashSet::new(),
+        callbacks,
+    )
+}
+
+fn push_ref_updates(
+    repo: &dyn Repo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    updates: &[GitRefUpdate],
+    force_with_lease: bool,
+    forced_refs: &HashSet<String>,
+    callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
     let mut qualified_remote_refs_expected_locations = HashMap::new();
     let mut refspecs = vec![];
@@ -1420,16 +1500,21 @@ pub fn push_updates(
         remote_name,
         &qualified_remote_refs_expected_locations,
         &refspecs,
+        force_with_lease,
+        forced_refs,
         callbacks,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn push_refs(
     repo: &dyn Repo,
     git_repo: &git2::Repository,
     remote_name: &str,
     qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
     refspecs: &[String],
+    force_with_lease: bool,
+    forced_refs: &HashSet<String>,
     callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
     if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
@@ -1447,6 +1532,7 @@ fn push_refs(
         .copied()
         .collect();
     let mut failed_push_negotiations = vec![];
+    let mut broken_leases = vec![];
     let push_result = {
         let mut push_options = git2::PushOptions::new();
         let mut proxy_options = git2::ProxyOptions::new();
@@ -1466,6 +1552,22 @@ fn push_refs(
                 let actual_remote_location = oid_to_maybe_commitid(update.src());
                 let local_location = oid_to_maybe_commitid(update.dst());
 
+                if forced_refs.contains(dst_refname) {
+                    tracing::info!(
+                        "Force-pushing {dst_refname} to {local_location:?}; it is at \
+                         {actual_remote_location:?} on the server",
+                    );
+                    continue;
+                }
+                if force_with_lease && actual_remote_location.as_ref() != expected_remote_location {
+                    tracing::info!(
+                        "Cannot push {dst_refname} to {local_location:?}; the lease expected it \
+                         at {expected_remote_location:?}, but it is at \
+                         {actual_remote_location:?} on the server",
+                    );
+                    broken_leases.push(dst_refname.to_string());
+                    continue;
+                }
                 match allow_push(
                     repo.index(),
                     actual_remote_location.as_ref(),
@@ -1507,7 +1609,7 @@ fn push_refs(
                     }
                 }
             }
-            if failed_push_negotiations.is_empty() {
+            if failed_push_negotiations.is_empty() && broken_leases.is_empty() {
                 Ok(())
             } else {
                 Err(git2::Error::from_str("failed push negotiation"))
@@ -1523,7 +1625,11 @@ fn push_refs(
         push_options.remote_callbacks(callbacks);
         remote.push(refspecs, Some(&mut push_options))
     };
-    if !failed_push_negotiations.is_empty() {
+    if !broken_leases.is_empty() {
+        assert!(push_result.is_err());
+        broken_leases.sort();
+        Err(GitPushError::RefLeaseBroken(broken_leases))
+    } else if !failed_push_negotiations.is_empty() {
         // If the push negotiation returned an error, `remote.push` would not
         // have pushed anything and would have returned an error, as expected.
         // However, the error it returns is not necessarily the error we'd
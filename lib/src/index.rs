@@ -29,6 +29,7 @@ use crate::operation::Operation;
 use crate::revset::ResolvedExpression;
 use crate::revset::Revset;
 use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetEvaluationOptions;
 use crate::store::Store;
 
 /// Returned if an error occurs while reading an index from the [`IndexStore`].
@@ -125,6 +126,7 @@ pub trait Index: Send + Sync {
         &'index self,
         expression: &ResolvedExpression,
         store: &Arc<Store>,
+        options: &RevsetEvaluationOptions,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError>;
 }
 
@@ -15,6 +15,7 @@
 #![allow(missing_docs)]
 
 use std::collections::hash_map::Entry;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -103,6 +104,11 @@ pub trait Repo {
 
     fn view(&self) -> &View;
 
+    /// The operation the repo was loaded at. For a `MutableRepo`, this is the
+    /// operation the transaction started from, so pending changes are only
+    /// reflected in `view()`.
+    fn base_operation(&self) -> &Operation;
+
     fn submodule_store(&self) -> &Arc<dyn SubmoduleStore>;
 
     fn resolve_change_id(&self, change_id: &ChangeId) -> Option<Vec<CommitId>> {
@@ -118,6 +124,32 @@ pub trait Repo {
     fn resolve_change_id_prefix(&self, prefix: &HexPrefix) -> PrefixResolution<Vec<CommitId>>;
 
     fn shortest_unique_change_id_prefix_len(&self, target_id_bytes: &ChangeId) -> usize;
+
+    /// Returns names that exist as more than one kind of ref, in
+    /// lexicographical order.
+    ///
+    /// A symbol resolves to a tag first, then to a local branch, then to a Git
+    /// ref. If a tag and a local branch share the same name, for example, the
+    /// branch can't be referred to by that name.
+    fn shadowed_ref_names(&self) -> Vec<String> {
+        let view = self.view();
+        let is_git_ref = |name: &str| {
+            view.get_git_ref(name).is_present()
+                || view.get_git_ref(&format!("refs/{name}")).is_present()
+        };
+        let mut names = BTreeSet::new();
+        for (name, target) in view.local_branches() {
+            if target.is_present() && (view.get_tag(name).is_present() || is_git_ref(name)) {
+                names.insert(name.to_owned());
+            }
+        }
+        for (name, target) in view.tags() {
+            if target.is_present() && is_git_ref(name) {
+                names.insert(name.clone());
+            }
+        }
+        names.into_iter().collect()
+    }
 }
 
 pub struct ReadonlyRepo {
@@ -348,6 +380,10 @@ impl Repo for ReadonlyRepo {
         &self.view
     }
 
+    fn base_operation(&self) -> &Operation {
+        &self.operation
+    }
+
     fn submodule_store(&self) -> &Arc<dyn SubmoduleStore> {
         &self.submodule_store
     }
@@ -1192,7 +1228,9 @@ impl MutableRepo {
             .evaluate_programmatic(self)
             .map_err(|err| match err {
                 RevsetEvaluationError::StoreError(err) => err,
-                RevsetEvaluationError::Other(_) => panic!("Unexpected revset error: {err}"),
+                RevsetEvaluationError::LimitExceeded(_) | RevsetEvaluationError::Other(_) => {
+                    panic!("Unexpected revset error: {err}")
+                }
             })?;
         let to_visit: Vec<_> = to_visit_revset.iter().commits(store).try_collect()?;
         drop(to_visit_revset);
@@ -1806,6 +1844,10 @@ impl Repo for MutableRepo {
             .get_or_ensure_clean(|v| self.enforce_view_invariants(v))
     }
 
+    fn base_operation(&self) -> &Operation {
+        self.base_repo.operation()
+    }
+
     fn submodule_store(&self) -> &Arc<dyn SubmoduleStore> {
         self.base_repo.submodule_store()
     }
@@ -42,6 +42,8 @@ use crate::hex_util::to_forward_hex;
 use crate::id_prefix::IdPrefixContext;
 use crate::object_id::HexPrefix;
 use crate::object_id::PrefixResolution;
+use crate::op_store::OpStoreError;
+use crate::op_store::RefTarget;
 use crate::op_store::RemoteRefState;
 use crate::op_store::WorkspaceId;
 use crate::repo::Repo;
@@ -60,6 +62,7 @@ use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::time_util::DatePattern;
 use crate::time_util::DatePatternContext;
+use crate::view::View;
 
 /// Error occurred during symbol resolution.
 #[derive(Debug, Error)]
@@ -88,10 +91,23 @@ pub enum RevsetResolutionError {
 pub enum RevsetEvaluationError {
     #[error("Unexpected error from store")]
     StoreError(#[source] BackendError),
+    #[error("Revset evaluation exceeded the limit of {0} commits")]
+    LimitExceeded(usize),
     #[error("{0}")]
     Other(String),
 }
 
+/// Options to control revset evaluation.
+#[derive(Clone, Debug, Default)]
+pub struct RevsetEvaluationOptions {
+    /// Maximum number of commits collected while evaluating the expression.
+    ///
+    /// Lazily evaluated sets aren't bounded, but sets that have to be
+    /// collected in memory (e.g. `connected(x)`) fail with `LimitExceeded`
+    /// once they grow past this number.
+    pub max_commits: Option<usize>,
+}
+
 // assumes index has less than u64::MAX entries.
 pub const GENERATION_RANGE_FULL: Range<u64> = 0..u64::MAX;
 pub const GENERATION_RANGE_EMPTY: Range<u64> = 0..0;
@@ -111,7 +127,7 @@ pub enum RevsetModifier {
 #[derive(Clone, Debug)]
 pub enum RevsetCommitRef {
     WorkingCopy(WorkspaceId),
-    WorkingCopies,
+    WorkingCopies(StringPattern),
     Symbol(String),
     RemoteSymbol {
         name: String,
@@ -125,9 +141,11 @@ pub enum RevsetCommitRef {
         remote_pattern: StringPattern,
         remote_ref_state: Option<RemoteRefState>,
     },
-    Tags,
-    GitRefs,
+    Tags(StringPattern),
+    GitRefs(StringPattern),
     GitHead,
+    FetchCreated,
+    FetchUpdated,
 }
 
 /// A custom revset filter expression, defined by an extension.
@@ -138,6 +156,40 @@ pub trait RevsetFilterExtension: std::fmt::Debug + Any {
     fn matches_commit(&self, commit: &Commit) -> bool;
 }
 
+/// Timestamp by which `latest()` orders the candidates.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LatestSortKey {
+    /// Committer timestamp.
+    #[default]
+    CommitterDate,
+    /// Author timestamp.
+    AuthorDate,
+}
+
+/// Key by which `sorted()` orders the candidates.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SortKey {
+    /// Committer timestamp, newest first.
+    #[default]
+    CommitterDate,
+    /// Author timestamp, newest first.
+    AuthorDate,
+    /// Commit ID in ascending order. Unlike the timestamps, this doesn't
+    /// depend on the index positions to break ties.
+    CommitId,
+}
+
+/// Kind of change to a file that `file(_, kind=_)` matches.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileChangeKind {
+    /// The file didn't exist in the parents.
+    Added,
+    /// The file was deleted from the parents.
+    Removed,
+    /// The file existed in the parents and was changed.
+    Modified,
+}
+
 #[derive(Clone, Debug)]
 pub enum RevsetFilterPredicate {
     /// Commits with number of parents in the range.
@@ -146,21 +198,48 @@ pub enum RevsetFilterPredicate {
     Description(StringPattern),
     /// Commits with author name or email matching the pattern.
     Author(StringPattern),
+    /// Commits with author name matching the pattern.
+    AuthorName(StringPattern),
+    /// Commits with author email matching the pattern.
+    AuthorEmail(StringPattern),
     /// Commits with committer name or email matching the pattern.
     Committer(StringPattern),
+    /// Commits with committer name matching the pattern.
+    CommitterName(StringPattern),
+    /// Commits with committer email matching the pattern.
+    CommitterEmail(StringPattern),
     /// Commits with author dates matching the given date pattern.
     AuthorDate(DatePattern),
     /// Commits with committer dates matching the given date pattern.
     CommitterDate(DatePattern),
     /// Commits modifying the paths specified by the fileset.
     File(FilesetExpression),
+    /// Commits making the given kind of change to the paths specified by the
+    /// fileset.
+    FileChange {
+        files: FilesetExpression,
+        kind: FileChangeKind,
+    },
     /// Commits containing diffs matching the `text` pattern within the `files`.
     DiffContains {
         text: StringPattern,
         files: FilesetExpression,
+        /// Files larger than this are skipped.
+        max_file_size: Option<u64>,
     },
+    /// Commits changing a number of files in the range. Merge commits are
+    /// compared against the merge of their parents.
+    ChangedFileCount(Range<u32>),
     /// Commits with conflicts
     HasConflict,
+    /// Commits with conflicts in the paths specified by the fileset.
+    ConflictInFiles(FilesetExpression),
+    /// Commits whose total size of files in the tree is larger than the given
+    /// number of bytes.
+    TreeSize(u64),
+    /// Commits whose tree is the same as the tree of the parent at the given
+    /// (0-based) index.
+    SameTreeAsParent(usize),
     /// Custom predicates provided by extensions
     Extension(Rc<dyn RevsetFilterExtension>),
 }
@@ -179,6 +258,12 @@ pub enum RevsetExpression {
         roots: Rc<RevsetExpression>,
         generation: Range<u64>,
     },
+    // Descendants of "roots" whose generation is counted along the first-parent
+    // edges only
+    FirstParentDescendants {
+        roots: Rc<RevsetExpression>,
+        generation: Range<u64>,
+    },
     // Commits that are ancestors of "heads" but not ancestors of "roots"
     Range {
         roots: Rc<RevsetExpression>,
@@ -191,6 +276,13 @@ pub enum RevsetExpression {
         heads: Rc<RevsetExpression>,
         // TODO: maybe add generation_from_roots/heads?
     },
+    // Commits that are descendants of "roots" and ancestors of "heads", only
+    // following paths within "domain"
+    DagRangeWithin {
+        roots: Rc<RevsetExpression>,
+        heads: Rc<RevsetExpression>,
+        domain: Rc<RevsetExpression>,
+    },
     // Commits reachable from "sources" within "domain"
     Reachable {
         sources: Rc<RevsetExpression>,
@@ -201,8 +293,20 @@ pub enum RevsetExpression {
     Latest {
         candidates: Rc<RevsetExpression>,
         count: usize,
+        key: LatestSortKey,
+    },
+    /// Candidates ordered by the `key`. Only allowed as the outermost
+    /// expression.
+    Sorted {
+        candidates: Rc<RevsetExpression>,
+        key: SortKey,
     },
+    /// The latest candidate of each change ID, by committer timestamp.
+    DistinctChangeIds(Rc<RevsetExpression>),
     Filter(RevsetFilterPredicate),
+    /// Commits changing files that differ between their parents and the
+    /// `reference` commit. Evaluated as a filter.
+    ChangedSince(Rc<RevsetExpression>),
     /// Marker for subtree that should be intersected as filter.
     AsFilter(Rc<RevsetExpression>),
     Present(Rc<RevsetExpression>),
@@ -227,8 +331,10 @@ impl RevsetExpression {
         )))
     }
 
-    pub fn working_copies() -> Rc<RevsetExpression> {
-        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::WorkingCopies))
+    pub fn working_copies(pattern: StringPattern) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::WorkingCopies(
+            pattern,
+        )))
     }
 
     pub fn symbol(value: String) -> Rc<RevsetExpression> {
@@ -276,29 +382,66 @@ impl RevsetExpression {
         ))
     }
 
-    pub fn tags() -> Rc<RevsetExpression> {
-        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::Tags))
+    pub fn tags(pattern: StringPattern) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::Tags(pattern)))
     }
 
-    pub fn git_refs() -> Rc<RevsetExpression> {
-        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::GitRefs))
+    pub fn git_refs(pattern: StringPattern) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::GitRefs(
+            pattern,
+        )))
     }
 
     pub fn git_head() -> Rc<RevsetExpression> {
         Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::GitHead))
     }
 
+    pub fn fetch_created() -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::FetchCreated))
+    }
+
+    pub fn fetch_updated() -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::CommitRef(RevsetCommitRef::FetchUpdated))
+    }
+
     pub fn latest(self: &Rc<RevsetExpression>, count: usize) -> Rc<RevsetExpression> {
+        self.latest_by(count, LatestSortKey::default())
+    }
+
+    pub fn latest_by(
+        self: &Rc<RevsetExpression>,
+        count: usize,
+        key: LatestSortKey,
+    ) -> Rc<RevsetExpression> {
         Rc::new(RevsetExpression::Latest {
             candidates: self.clone(),
             count,
+            key,
+        })
+    }
+
+    pub fn sorted_by(self: &Rc<RevsetExpression>, key: SortKey) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::Sorted {
+            candidates: self.clone(),
+            key,
         })
     }
 
+    /// Commits in `self`, keeping only the latest commit of each change ID.
+    pub fn distinct_change_ids(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::DistinctChangeIds(self.clone()))
+    }
+
     pub fn filter(predicate: RevsetFilterPredicate) -> Rc<RevsetExpression> {
         Rc::new(RevsetExpression::Filter(predicate))
     }
 
+    /// Commits changing files that differ between their parents and the
+    /// `reference` commit.
+    pub fn changed_since(reference: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::ChangedSince(reference.clone()))
+    }
+
     /// Find any empty commits.
     pub fn is_empty() -> Rc<RevsetExpression> {
         Self::filter(RevsetFilterPredicate::File(FilesetExpression::all())).negated()
@@ -309,11 +452,32 @@ impl RevsetExpression {
         Rc::new(RevsetExpression::Heads(self.clone()))
     }
 
+    /// Visible commits in `self` that don't have visible descendants in
+    /// `self`.
+    pub fn visible_heads_within(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
+        self.intersection(&RevsetExpression::visible_heads().ancestors())
+            .heads()
+    }
+
     /// Commits in `self` that don't have ancestors in `self`.
     pub fn roots(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
         Rc::new(RevsetExpression::Roots(self.clone()))
     }
 
+    /// Commits in `self` that don't have ancestors in `self`, only following
+    /// the paths within `domain`.
+    pub fn roots_within(
+        self: &Rc<RevsetExpression>,
+        domain: &Rc<RevsetExpression>,
+    ) -> Rc<RevsetExpression> {
+        let non_roots = self
+            .intersection(domain)
+            .children()
+            .intersection(domain)
+            .dag_range_within(self, domain);
+        self.minus(&non_roots)
+    }
+
     /// Parents of `self`.
     pub fn parents(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
         self.ancestors_at(1)
@@ -324,6 +488,16 @@ impl RevsetExpression {
         self.ancestors_range(GENERATION_RANGE_FULL)
     }
 
+    /// Ancestors of `self`, excluding `self`.
+    ///
+    /// This is just `::self ~ self`, which `optimize()` doesn't rewrite. It
+    /// isn't the same as the ancestors at generation 1 and later (`::self-`),
+    /// which would include commits in `self` that are ancestors of other
+    /// commits in `self`.
+    pub fn strict_ancestors(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
+        self.ancestors().minus(self)
+    }
+
     /// Ancestors of `self` at an offset of `generation` behind `self`.
     /// The `generation` offset is zero-based starting from `self`.
     pub fn ancestors_at(self: &Rc<RevsetExpression>, generation: u64) -> Rc<RevsetExpression> {
@@ -341,16 +515,51 @@ impl RevsetExpression {
         })
     }
 
+    /// Ancestors of `self` in the given range, stopping before any ancestor in
+    /// `stop`. The commits in `stop` and their ancestors are excluded, but the
+    /// commits in `self` are included even if they are in `stop`.
+    pub fn ancestors_until(
+        self: &Rc<RevsetExpression>,
+        stop: &Rc<RevsetExpression>,
+        generation_range: Range<u64>,
+    ) -> Rc<RevsetExpression> {
+        let heads = if generation_range.contains(&0) {
+            self.clone()
+        } else {
+            RevsetExpression::none()
+        };
+        Rc::new(RevsetExpression::Range {
+            roots: stop.clone(),
+            heads: self.clone(),
+            generation: generation_range,
+        })
+        .union(&heads)
+    }
+
     /// Children of `self`.
     pub fn children(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
         self.descendants_at(1)
     }
 
+    /// Other children of the parents of `self`, excluding `self`. For merge
+    /// commits, the children of all parents are included.
+    pub fn siblings(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
+        self.parents().children().minus(self)
+    }
+
     /// Descendants of `self`, including `self`.
     pub fn descendants(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
         self.descendants_range(GENERATION_RANGE_FULL)
     }
 
+    /// Descendants of `self`, excluding `self`.
+    ///
+    /// This is just `self:: ~ self`, which `optimize()` doesn't rewrite, for the
+    /// same reason as [`RevsetExpression::strict_ancestors()`].
+    pub fn strict_descendants(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
+        self.descendants().minus(self)
+    }
+
     /// Descendants of `self` at an offset of `generation` ahead of `self`.
     /// The `generation` offset is zero-based starting from `self`.
     pub fn descendants_at(self: &Rc<RevsetExpression>, generation: u64) -> Rc<RevsetExpression> {
@@ -368,6 +577,19 @@ impl RevsetExpression {
         })
     }
 
+    /// Descendants of `self` in the given range, counting generations only
+    /// along the first-parent edges. Commits which reach `self` only through
+    /// merged-in parents aren't included.
+    pub fn first_parent_descendants_range(
+        self: &Rc<RevsetExpression>,
+        generation_range: Range<u64>,
+    ) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::FirstParentDescendants {
+            roots: self.clone(),
+            generation: generation_range,
+        })
+    }
+
     /// Filter all commits by `predicate` in `self`.
     pub fn filtered(
         self: &Rc<RevsetExpression>,
@@ -387,12 +609,36 @@ impl RevsetExpression {
         })
     }
 
+    /// Commits that are descendants of `self` and ancestors of `heads`, both
+    /// inclusive, connected by paths that stay within `domain`.
+    pub fn dag_range_within(
+        self: &Rc<RevsetExpression>,
+        heads: &Rc<RevsetExpression>,
+        domain: &Rc<RevsetExpression>,
+    ) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::DagRangeWithin {
+            roots: self.clone(),
+            heads: heads.clone(),
+            domain: domain.clone(),
+        })
+    }
+
     /// Connects any ancestors and descendants in the set by adding the commits
     /// between them.
     pub fn connected(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
         self.dag_range_to(self)
     }
 
+    /// Like `connected()`, but only adds the commits in between if they are
+    /// connected by paths within `domain`.
+    pub fn connected_within(
+        self: &Rc<RevsetExpression>,
+        domain: &Rc<RevsetExpression>,
+    ) -> Rc<RevsetExpression> {
+        let candidates = self.intersection(domain);
+        candidates.dag_range_within(&candidates, domain)
+    }
+
     /// All commits within `domain` reachable from this set of commits, by
     /// traversing either parent or child edges.
     pub fn reachable(
@@ -459,6 +705,28 @@ impl RevsetExpression {
         Rc::new(RevsetExpression::Difference(self.clone(), other.clone()))
     }
 
+    /// Returns the symbols referenced by this expression, in order of first
+    /// appearance. Remote symbols are formatted as `name@remote`.
+    ///
+    /// This can be used to inspect a parsed expression before resolving the
+    /// symbols to commits.
+    pub fn referenced_symbols(self: &Rc<Self>) -> Vec<String> {
+        let mut symbols = vec![];
+        transform_expression_bottom_up(self, |expression| {
+            match expression.as_ref() {
+                RevsetExpression::CommitRef(RevsetCommitRef::Symbol(name)) => {
+                    symbols.push(name.clone());
+                }
+                RevsetExpression::CommitRef(RevsetCommitRef::RemoteSymbol { name, remote }) => {
+                    symbols.push(format!("{name}@{remote}"));
+                }
+                _ => {}
+            }
+            None
+        });
+        symbols.into_iter().unique().collect()
+    }
+
     /// Resolve a programmatically created revset expression. In particular, the
     /// expression must not contain any symbols (branches, tags, change/commit
     /// prefixes). Callers must not include `RevsetExpression::symbol()` in
@@ -501,6 +769,9 @@ pub enum ResolvedPredicateExpression {
     /// Set expression to be evaluated as filter. This is typically a subtree
     /// node of `Union` with a pure filter predicate.
     Set(Box<ResolvedExpression>),
+    /// Commits changing files that differ between their parents and the
+    /// single commit of the `reference` set.
+    ChangedSince(Box<ResolvedExpression>),
     NotIn(Box<ResolvedPredicateExpression>),
     Union(
         Box<ResolvedPredicateExpression>,
@@ -533,6 +804,20 @@ pub enum ResolvedExpression {
         heads: Box<ResolvedExpression>,
         generation_from_roots: Range<u64>,
     },
+    /// Descendants of `roots` which are ancestors of `heads`, reached by
+    /// following only the first-parent edges.
+    FirstParentDagRange {
+        roots: Box<ResolvedExpression>,
+        heads: Box<ResolvedExpression>,
+        generation_from_roots: Range<u64>,
+    },
+    /// Commits that are descendants of `roots` and ancestors of `heads`,
+    /// following only the paths within `domain`.
+    DagRangeWithin {
+        roots: Box<ResolvedExpression>,
+        heads: Box<ResolvedExpression>,
+        domain: Box<ResolvedExpression>,
+    },
     /// Commits reachable from `sources` within `domain`.
     Reachable {
         sources: Box<ResolvedExpression>,
@@ -543,7 +828,13 @@ pub enum ResolvedExpression {
     Latest {
         candidates: Box<ResolvedExpression>,
         count: usize,
+        key: LatestSortKey,
+    },
+    Sorted {
+        candidates: Box<ResolvedExpression>,
+        key: SortKey,
     },
+    DistinctChangeIds(Box<ResolvedExpression>),
     Union(Box<ResolvedExpression>, Box<ResolvedExpression>),
     /// Intersects `candidates` with `predicate` by filtering.
     FilterWithin {
@@ -560,7 +851,15 @@ impl ResolvedExpression {
         &self,
         repo: &'index dyn Repo,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        repo.index().evaluate_revset(self, repo.store())
+        self.evaluate_with_options(repo, &RevsetEvaluationOptions::default())
+    }
+
+    pub fn evaluate_with_options<'index>(
+        &self,
+        repo: &'index dyn Repo,
+        options: &RevsetEvaluationOptions,
+    ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
+        repo.index().evaluate_revset(self, repo.store(), options)
     }
 }
 
@@ -581,8 +880,20 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         let expression = lower_expression(arg, context)?;
         Ok(expression.children())
     });
+    map.insert("siblings", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = lower_expression(arg, context)?;
+        Ok(expression.siblings())
+    });
+    map.insert("merge_parents", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = lower_expression(arg, context)?;
+        let merges = RevsetExpression::filter(RevsetFilterPredicate::ParentCount(2..u32::MAX));
+        Ok(expression.intersection(&merges).parents())
+    });
     map.insert("ancestors", |function, context| {
-        let ([heads_arg], [depth_opt_arg]) = function.expect_arguments()?;
+        let ([heads_arg], [depth_opt_arg, until_opt_arg]) =
+            function.expect_named_arguments(&["", "depth", "until"])?;
         let heads = lower_expression(heads_arg, context)?;
         let generation = if let Some(depth_arg) = depth_opt_arg {
             let depth = expect_literal("integer", depth_arg)?;
@@ -590,10 +901,16 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         } else {
             GENERATION_RANGE_FULL
         };
-        Ok(heads.ancestors_range(generation))
+        if let Some(until_arg) = until_opt_arg {
+            let stop = lower_expression(until_arg, context)?;
+            Ok(heads.ancestors_until(&stop, generation))
+        } else {
+            Ok(heads.ancestors_range(generation))
+        }
     });
     map.insert("descendants", |function, context| {
-        let ([roots_arg], [depth_opt_arg]) = function.expect_arguments()?;
+        let ([roots_arg], [depth_opt_arg, first_parent_opt_arg]) =
+            function.expect_named_arguments(&["", "depth", "first_parent"])?;
         let roots = lower_expression(roots_arg, context)?;
         let generation = if let Some(depth_arg) = depth_opt_arg {
             let depth = expect_literal("integer", depth_arg)?;
@@ -601,12 +918,59 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         } else {
             GENERATION_RANGE_FULL
         };
-        Ok(roots.descendants_range(generation))
+        let first_parent = if let Some(first_parent_arg) = first_parent_opt_arg {
+            expect_literal("boolean", first_parent_arg)?
+        } else {
+            false
+        };
+        if first_parent {
+            Ok(roots.first_parent_descendants_range(generation))
+        } else {
+            Ok(roots.descendants_range(generation))
+        }
     });
-    map.insert("connected", |function, context| {
+    map.insert("strict_ancestors", |function, context| {
         let [arg] = function.expect_exact_arguments()?;
-        let candidates = lower_expression(arg, context)?;
-        Ok(candidates.connected())
+        let heads = lower_expression(arg, context)?;
+        Ok(heads.strict_ancestors())
+    });
+    map.insert("strict_descendants", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let roots = lower_expression(arg, context)?;
+        Ok(roots.strict_descendants())
+    });
+    map.insert("changed_since", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let reference = lower_expression(arg, context)?;
+        Ok(RevsetExpression::changed_since(&reference))
+    });
+    map.insert("only_in", |function, context| {
+        let [candidates_arg, excluded_arg] = function.expect_exact_arguments()?;
+        let candidates = lower_expression(candidates_arg, context)?;
+        let excluded = lower_expression(excluded_arg, context)?;
+        Ok(candidates.minus(&excluded.ancestors()))
+    });
+    map.insert("descendants_until", |function, context| {
+        let ([roots_arg], [stop_opt_arg]) = function.expect_arguments()?;
+        let roots = lower_expression(roots_arg, context)?;
+        let stop = if let Some(stop_arg) = stop_opt_arg {
+            lower_expression(stop_arg, context)?
+        } else {
+            RevsetExpression::branches(StringPattern::everything())
+        };
+        // Commits in `roots` are included even if they are in `stop`.
+        let stop_descendants = roots.strict_descendants().intersection(&stop).descendants();
+        Ok(roots.descendants().minus(&stop_descendants))
+    });
+    map.insert("connected", |function, context| {
+        let ([candidates_arg], [domain_opt_arg]) = function.expect_arguments()?;
+        let candidates = lower_expression(candidates_arg, context)?;
+        if let Some(domain_arg) = domain_opt_arg {
+            let domain = lower_expression(domain_arg, context)?;
+            Ok(candidates.connected_within(&domain))
+        } else {
+            Ok(candidates.connected())
+        }
     });
     map.insert("reachable", |function, context| {
         let [source_arg, domain_arg] = function.expect_exact_arguments()?;
@@ -623,8 +987,13 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         Ok(RevsetExpression::all())
     });
     map.insert("working_copies", |function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::working_copies())
+        let ([], [opt_arg]) = function.expect_arguments()?;
+        let pattern = if let Some(arg) = opt_arg {
+            expect_string_pattern(arg)?
+        } else {
+            StringPattern::everything()
+        };
+        Ok(RevsetExpression::working_copies(pattern))
     });
     map.insert("heads", |function, context| {
         let [arg] = function.expect_exact_arguments()?;
@@ -632,13 +1001,23 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         Ok(candidates.heads())
     });
     map.insert("roots", |function, context| {
-        let [arg] = function.expect_exact_arguments()?;
+        let ([arg], [domain_opt_arg]) = function.expect_arguments()?;
         let candidates = lower_expression(arg, context)?;
-        Ok(candidates.roots())
+        if let Some(domain_arg) = domain_opt_arg {
+            let domain = lower_expression(domain_arg, context)?;
+            Ok(candidates.roots_within(&domain))
+        } else {
+            Ok(candidates.roots())
+        }
     });
-    map.insert("visible_heads", |function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::visible_heads())
+    map.insert("visible_heads", |function, context| {
+        let ([], [domain_opt_arg]) = function.expect_arguments()?;
+        if let Some(domain_arg) = domain_opt_arg {
+            let domain = lower_expression(domain_arg, context)?;
+            Ok(domain.visible_heads_within())
+        } else {
+            Ok(RevsetExpression::visible_heads())
+        }
     });
     map.insert("root", |function, _context| {
         function.expect_no_arguments()?;
@@ -653,6 +1032,12 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         };
         Ok(RevsetExpression::branches(pattern))
     });
+    map.insert("branches_behind", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = lower_expression(arg, context)?;
+        Ok(RevsetExpression::branches(StringPattern::everything())
+            .intersection(&expression.ancestors()))
+    });
     map.insert("remote_branches", |function, _context| {
         parse_remote_branches_arguments(function, None)
     });
@@ -663,31 +1048,74 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         parse_remote_branches_arguments(function, Some(RemoteRefState::New))
     });
     map.insert("tags", |function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::tags())
+        let ([], [opt_arg]) = function.expect_arguments()?;
+        let pattern = if let Some(arg) = opt_arg {
+            expect_string_pattern(arg)?
+        } else {
+            StringPattern::everything()
+        };
+        Ok(RevsetExpression::tags(pattern))
     });
     map.insert("git_refs", |function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::git_refs())
+        let ([], [opt_arg]) = function.expect_arguments()?;
+        let pattern = if let Some(arg) = opt_arg {
+            expect_string_pattern(arg)?
+        } else {
+            StringPattern::everything()
+        };
+        Ok(RevsetExpression::git_refs(pattern))
     });
     map.insert("git_head", |function, _context| {
         function.expect_no_arguments()?;
         Ok(RevsetExpression::git_head())
     });
+    map.insert("fetch_created", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::fetch_created())
+    });
+    map.insert("fetch_updated", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::fetch_updated())
+    });
     map.insert("latest", |function, context| {
-        let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
+        let ([candidates_arg], [count_opt_arg, key_opt_arg]) = function.expect_arguments()?;
         let candidates = lower_expression(candidates_arg, context)?;
         let count = if let Some(count_arg) = count_opt_arg {
             expect_literal("integer", count_arg)?
         } else {
             1
         };
-        Ok(candidates.latest(count))
+        let key = if let Some(key_arg) = key_opt_arg {
+            expect_latest_sort_key(key_arg)?
+        } else {
+            LatestSortKey::default()
+        };
+        Ok(candidates.latest_by(count, key))
+    });
+    map.insert("sorted", |function, context| {
+        let ([candidates_arg], [key_opt_arg]) = function.expect_arguments()?;
+        let candidates = lower_expression(candidates_arg, context)?;
+        let key = if let Some(key_arg) = key_opt_arg {
+            expect_sort_key(key_arg)?
+        } else {
+            SortKey::default()
+        };
+        Ok(candidates.sorted_by(key))
+    });
+    map.insert("distinct_change_ids", |function, context| {
+        let [candidates_arg] = function.expect_exact_arguments()?;
+        let candidates = lower_expression(candidates_arg, context)?;
+        Ok(candidates.distinct_change_ids())
     });
     map.insert("merges", |function, _context| {
-        function.expect_no_arguments()?;
+        let ([], [count_opt_arg]) = function.expect_arguments()?;
+        let parent_count_range = if let Some(count_arg) = count_opt_arg {
+            expect_parent_count_range(count_arg)?
+        } else {
+            2..u32::MAX
+        };
         Ok(RevsetExpression::filter(
-            RevsetFilterPredicate::ParentCount(2..u32::MAX),
+            RevsetFilterPredicate::ParentCount(parent_count_range),
         ))
     });
     map.insert("description", |function, _context| {
@@ -704,6 +1132,20 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
             pattern,
         )))
     });
+    map.insert("author_name", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::AuthorName(
+            pattern,
+        )))
+    });
+    map.insert("author_email", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::AuthorEmail(pattern),
+        ))
+    });
     map.insert("author_date", |function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_date_pattern(arg, context.date_pattern_context())?;
@@ -716,9 +1158,16 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         // Email address domains are inherently case‐insensitive, and the local‐parts
         // are generally (although not universally) treated as case‐insensitive too, so
         // we use a case‐insensitive match here.
-        Ok(RevsetExpression::filter(RevsetFilterPredicate::Author(
-            StringPattern::exact_i(&context.user_email),
-        )))
+        let expressions = context
+            .user_emails
+            .iter()
+            .map(|email| {
+                RevsetExpression::filter(RevsetFilterPredicate::Author(StringPattern::exact_i(
+                    email,
+                )))
+            })
+            .collect_vec();
+        Ok(RevsetExpression::union_all(&expressions))
     });
     map.insert("committer", |function, _context| {
         let [arg] = function.expect_exact_arguments()?;
@@ -727,6 +1176,20 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
             pattern,
         )))
     });
+    map.insert("committer_name", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::CommitterName(pattern),
+        ))
+    });
+    map.insert("committer_email", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::CommitterEmail(pattern),
+        ))
+    });
     map.insert("committer_date", |function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_date_pattern(arg, context.date_pattern_context())?;
@@ -735,8 +1198,20 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         ))
     });
     map.insert("empty", |function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::is_empty())
+        let ([], [parent_opt_arg]) = function.expect_named_arguments(&["parent"])?;
+        let Some(parent_arg) = parent_opt_arg else {
+            return Ok(RevsetExpression::is_empty());
+        };
+        let parent: usize = expect_literal("integer", parent_arg)?;
+        if parent == 0 {
+            return Err(RevsetParseError::expression(
+                "Parent index must be 1 or greater",
+                parent_arg.span,
+            ));
+        }
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::SameTreeAsParent(parent - 1),
+        ))
     });
     map.insert("file", |function, context| {
         let ctx = context.workspace.as_ref().ok_or_else(|| {
@@ -745,6 +1220,14 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
                 function.args_span, // TODO: better to use name_span?
             )
         })?;
+        if !function.keyword_args.is_empty() {
+            let ([files_arg], [kind_opt_arg]) = function.expect_named_arguments(&["", "kind"])?;
+            let files = expect_fileset_expression(files_arg, ctx.path_converter)?;
+            let kind = expect_file_change_kind(kind_opt_arg.unwrap())?;
+            return Ok(RevsetExpression::filter(
+                RevsetFilterPredicate::FileChange { files, kind },
+            ));
+        }
         // TODO: emit deprecation warning if multiple arguments are passed
         let ([arg], args) = function.expect_some_arguments()?;
         let file_expressions = itertools::chain([arg], args)
@@ -754,7 +1237,8 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         Ok(RevsetExpression::filter(RevsetFilterPredicate::File(expr)))
     });
     map.insert("diff_contains", |function, context| {
-        let ([text_arg], [files_opt_arg]) = function.expect_arguments()?;
+        let ([text_arg], [files_opt_arg, max_size_opt_arg]) =
+            function.expect_named_arguments(&["", "", "max_size"])?;
         let text = expect_string_pattern(text_arg)?;
         let files = if let Some(files_arg) = files_opt_arg {
             let ctx = context.workspace.as_ref().ok_or_else(|| {
@@ -769,13 +1253,48 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
             // https://github.com/martinvonz/jj/issues/2933#issuecomment-1925870731
             FilesetExpression::all()
         };
+        let max_file_size = if let Some(max_size_arg) = max_size_opt_arg {
+            Some(expect_literal("integer", max_size_arg)?)
+        } else {
+            context.diff_max_scan_bytes
+        };
         Ok(RevsetExpression::filter(
-            RevsetFilterPredicate::DiffContains { text, files },
+            RevsetFilterPredicate::DiffContains {
+                text,
+                files,
+                max_file_size,
+            },
         ))
     });
-    map.insert("conflict", |function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::filter(RevsetFilterPredicate::HasConflict))
+    map.insert("conflict", |function, context| {
+        let ([], [files_opt_arg]) = function.expect_arguments()?;
+        let Some(files_arg) = files_opt_arg else {
+            return Ok(RevsetExpression::filter(RevsetFilterPredicate::HasConflict));
+        };
+        let ctx = context.workspace.as_ref().ok_or_else(|| {
+            RevsetParseError::with_span(
+                RevsetParseErrorKind::FsPathWithoutWorkspace,
+                files_arg.span,
+            )
+        })?;
+        let files = expect_fileset_expression(files_arg, ctx.path_converter)?;
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::ConflictInFiles(files),
+        ))
+    });
+    map.insert("changed_files", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let count_range = revset_parser::expect_pattern_with("file count", arg, parse_count_range)?;
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::ChangedFileCount(count_range),
+        ))
+    });
+    map.insert("tree_size", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let size = expect_literal("integer", arg)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::TreeSize(
+            size,
+        )))
     });
     map.insert("present", |function, context| {
         let [arg] = function.expect_exact_arguments()?;
@@ -808,6 +1327,73 @@ pub fn expect_string_pattern(node: &ExpressionNode) -> Result<StringPattern, Rev
     revset_parser::expect_pattern_with("string pattern", node, parse_pattern)
 }
 
+/// Parses `<n>` (or `exact:<n>`), `atleast:<n>`, or `atmost:<n>` into a range
+/// of counts.
+fn parse_count_range(
+    value: &str,
+    kind: Option<&str>,
+) -> Result<Range<u32>, Box<dyn std::error::Error + Send + Sync>> {
+    let count: u32 = value.parse()?;
+    match kind {
+        None | Some("exact") => Ok(count..count.saturating_add(1)),
+        Some("atleast") => Ok(count..u32::MAX),
+        Some("atmost") => Ok(0..count.saturating_add(1)),
+        Some(kind) => Err(format!("Invalid count pattern kind \"{kind}:\"").into()),
+    }
+}
+
+/// Parses the number of merge parents, which is either `<n>` (or `exact:<n>`)
+/// or `atleast:<n>`.
+fn expect_parent_count_range(node: &ExpressionNode) -> Result<Range<u32>, RevsetParseError> {
+    let parse_pattern =
+        |value: &str, kind: Option<&str>| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+            let range = parse_count_range(value, kind)?;
+            if range.start < 2 {
+                return Err("Merges have at least 2 parents".into());
+            }
+            Ok(range)
+        };
+    revset_parser::expect_pattern_with("parent count", node, parse_pattern)
+}
+
+fn expect_file_change_kind(node: &ExpressionNode) -> Result<FileChangeKind, RevsetParseError> {
+    let name: String = expect_literal("string", node)?;
+    match name.as_str() {
+        "added" => Ok(FileChangeKind::Added),
+        "removed" => Ok(FileChangeKind::Removed),
+        "modified" => Ok(FileChangeKind::Modified),
+        _ => Err(RevsetParseError::expression(
+            r#"Expected change kind "added", "removed", or "modified""#,
+            node.span,
+        )),
+    }
+}
+
+fn expect_latest_sort_key(node: &ExpressionNode) -> Result<LatestSortKey, RevsetParseError> {
+    let name: String = expect_literal("string", node)?;
+    match name.as_str() {
+        "committer" | "committer_date" => Ok(LatestSortKey::CommitterDate),
+        "author" | "author_date" => Ok(LatestSortKey::AuthorDate),
+        _ => Err(RevsetParseError::expression(
+            r#"Expected sort key "author" or "committer""#,
+            node.span,
+        )),
+    }
+}
+
+fn expect_sort_key(node: &ExpressionNode) -> Result<SortKey, RevsetParseError> {
+    let name: String = expect_literal("string", node)?;
+    match name.as_str() {
+        "committer" | "committer_date" => Ok(SortKey::CommitterDate),
+        "author" | "author_date" => Ok(SortKey::AuthorDate),
+        "commit_id" => Ok(SortKey::CommitId),
+        _ => Err(RevsetParseError::expression(
+            r#"Expected sort key "author", "committer", or "commit_id""#,
+            node.span,
+        )),
+    }
+}
+
 pub fn expect_date_pattern(
     node: &ExpressionNode,
     context: &DatePatternContext,
@@ -819,7 +1405,16 @@ pub fn expect_date_pattern(
                 Some(kind) => Ok(context.parse_relative(value, kind)?),
             }
         };
-    revset_parser::expect_pattern_with("date pattern", node, parse_pattern)
+    revset_parser::expect_expression_with(node, |node| match &node.kind {
+        ExpressionKind::Binary(BinaryOp::Range, start_node, end_node) => {
+            let start: String = expect_literal("string", start_node)?;
+            let end: String = expect_literal("string", end_node)?;
+            context.parse_range(&start, &end).map_err(|err| {
+                RevsetParseError::expression("Invalid date range", node.span).with_source(err)
+            })
+        }
+        _ => revset_parser::expect_pattern_with("date pattern", node, parse_pattern),
+    })
 }
 
 fn parse_remote_branches_arguments(
@@ -944,13 +1539,34 @@ pub fn lower_expression(
     }
 }
 
+/// Transforms the top-level AST `node` into expression. Unlike
+/// `lower_expression()`, this rejects `sorted()` unless it is the outermost
+/// function, because the order can't be preserved in sub expressions.
+fn lower_program_expression(
+    node: &ExpressionNode,
+    context: &RevsetParseContext,
+) -> Result<Rc<RevsetExpression>, RevsetParseError> {
+    let expression = lower_expression(node, context)?;
+    let inner = match expression.as_ref() {
+        RevsetExpression::Sorted { candidates, .. } => candidates,
+        _ => &expression,
+    };
+    if contains_sorted(inner) {
+        return Err(RevsetParseError::expression(
+            "sorted() can only be used as the outermost function",
+            node.span,
+        ));
+    }
+    Ok(expression)
+}
+
 pub fn parse(
     revset_str: &str,
     context: &RevsetParseContext,
 ) -> Result<Rc<RevsetExpression>, RevsetParseError> {
     let node = revset_parser::parse_program(revset_str)?;
     let node = dsl_util::expand_aliases(node, context.aliases_map)?;
-    lower_expression(&node, context)
+    lower_program_expression(&node, context)
         .map_err(|err| err.extend_function_candidates(context.aliases_map.function_names()))
 }
 
@@ -962,7 +1578,7 @@ pub fn parse_with_modifier(
     let node = dsl_util::expand_aliases(node, context.aliases_map)?;
     revset_parser::expect_program_with(
         &node,
-        |node| lower_expression(node, context),
+        |node| lower_program_expression(node, context),
         |name, span| match name {
             "all" => Ok(RevsetModifier::All),
             _ => Err(RevsetParseError::with_span(
@@ -986,6 +1602,19 @@ fn transform_expression_bottom_up(
         .unwrap()
 }
 
+/// Returns true if `expression` has a `sorted()` node anywhere in its tree.
+fn contains_sorted(expression: &Rc<RevsetExpression>) -> bool {
+    try_transform_expression(
+        expression,
+        |expression| match expression.as_ref() {
+            RevsetExpression::Sorted { .. } => Err(()),
+            _ => Ok(None),
+        },
+        |_| Ok(None),
+    )
+    .is_err()
+}
+
 /// Walks `expression` tree and applies transformation recursively.
 ///
 /// `pre` is the callback to rewrite subtree including children. It is
@@ -1023,6 +1652,14 @@ fn try_transform_expression<E>(
                     roots,
                     generation: generation.clone(),
                 }),
+            RevsetExpression::FirstParentDescendants { roots, generation } => {
+                transform_rec(roots, pre, post)?.map(|roots| {
+                    RevsetExpression::FirstParentDescendants {
+                        roots,
+                        generation: generation.clone(),
+                    }
+                })
+            }
             RevsetExpression::Range {
                 roots,
                 heads,
@@ -1038,6 +1675,25 @@ fn try_transform_expression<E>(
                 transform_rec_pair((roots, heads), pre, post)?
                     .map(|(roots, heads)| RevsetExpression::DagRange { roots, heads })
             }
+            RevsetExpression::DagRangeWithin {
+                roots,
+                heads,
+                domain,
+            } => {
+                let new_pair = transform_rec_pair((roots, heads), pre, post)?;
+                let new_domain = transform_rec(domain, pre, post)?;
+                if new_pair.is_none() && new_domain.is_none() {
+                    None
+                } else {
+                    let (roots, heads) = new_pair.unwrap_or_else(|| (roots.clone(), heads.clone()));
+                    let domain = new_domain.unwrap_or_else(|| domain.clone());
+                    Some(RevsetExpression::DagRangeWithin {
+                        roots,
+                        heads,
+                        domain,
+                    })
+                }
+            }
             RevsetExpression::Reachable { sources, domain } => {
                 transform_rec_pair((sources, domain), pre, post)?
                     .map(|(sources, domain)| RevsetExpression::Reachable { sources, domain })
@@ -1048,12 +1704,27 @@ fn try_transform_expression<E>(
             RevsetExpression::Roots(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::Roots)
             }
-            RevsetExpression::Latest { candidates, count } => transform_rec(candidates, pre, post)?
-                .map(|candidates| RevsetExpression::Latest {
+            RevsetExpression::Latest {
+                candidates,
+                count,
+                key,
+            } => transform_rec(candidates, pre, post)?.map(|candidates| RevsetExpression::Latest {
+                candidates,
+                count: *count,
+                key: *key,
+            }),
+            RevsetExpression::Sorted { candidates, key } => transform_rec(candidates, pre, post)?
+                .map(|candidates| RevsetExpression::Sorted {
                     candidates,
-                    count: *count,
+                    key: *key,
                 }),
+            RevsetExpression::DistinctChangeIds(candidates) => {
+                transform_rec(candidates, pre, post)?.map(RevsetExpression::DistinctChangeIds)
+            }
             RevsetExpression::Filter(_) => None,
+            RevsetExpression::ChangedSince(reference) => {
+                transform_rec(reference, pre, post)?.map(RevsetExpression::ChangedSince)
+            }
             RevsetExpression::AsFilter(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::AsFilter)
             }
@@ -1136,7 +1807,9 @@ fn internalize_filter(expression: &Rc<RevsetExpression>) -> TransformedExpressio
     fn is_filter(expression: &RevsetExpression) -> bool {
         matches!(
             expression,
-            RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_)
+            RevsetExpression::Filter(_)
+                | RevsetExpression::ChangedSince(_)
+                | RevsetExpression::AsFilter(_)
         )
     }
 
@@ -1275,7 +1948,12 @@ fn fold_difference(expression: &Rc<RevsetExpression>) -> TransformedExpression {
         RevsetExpression::Intersection(expression1, expression2) => {
             match (expression1.as_ref(), expression2.as_ref()) {
                 // For '~x & f', don't move filter node 'f' left
-                (_, RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_)) => None,
+                (
+                    _,
+                    RevsetExpression::Filter(_)
+                    | RevsetExpression::ChangedSince(_)
+                    | RevsetExpression::AsFilter(_),
+                ) => None,
                 (_, RevsetExpression::NotIn(complement)) => {
                     Some(to_difference(expression1, complement))
                 }
@@ -1684,8 +2362,14 @@ fn resolve_commit_ref(
                 })
             }
         }
-        RevsetCommitRef::WorkingCopies => {
-            let wc_commits = repo.view().wc_commit_ids().values().cloned().collect_vec();
+        RevsetCommitRef::WorkingCopies(pattern) => {
+            let wc_commits = repo
+                .view()
+                .wc_commit_ids()
+                .iter()
+                .filter(|(workspace_id, _)| pattern.matches(workspace_id.as_str()))
+                .map(|(_, commit_id)| commit_id.clone())
+                .collect_vec();
             Ok(wc_commits)
         }
         RevsetCommitRef::VisibleHeads => Ok(repo.view().heads().iter().cloned().collect_vec()),
@@ -1711,37 +2395,111 @@ fn resolve_commit_ref(
                 .filter(|(_, remote_ref)| {
                     remote_ref_state.map_or(true, |state| remote_ref.state == state)
                 })
-                .filter(|&((_, remote_name), _)| {
-                    #[cfg(feature = "git")]
-                    {
-                        remote_name != crate::git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
-                    }
-                    #[cfg(not(feature = "git"))]
-                    {
-                        let _ = remote_name;
-                        true
-                    }
-                })
+                .filter(|&((_, remote_name), _)| !is_local_git_remote(remote_name))
                 .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
                 .cloned()
                 .collect();
             Ok(commit_ids)
         }
-        RevsetCommitRef::Tags => {
-            let mut commit_ids = vec![];
-            for ref_target in repo.view().tags().values() {
-                commit_ids.extend(ref_target.added_ids().cloned());
-            }
+        RevsetCommitRef::Tags(pattern) => {
+            let commit_ids = repo
+                .view()
+                .tags_matching(pattern)
+                .flat_map(|(_, target)| target.added_ids())
+                .cloned()
+                .collect();
             Ok(commit_ids)
         }
-        RevsetCommitRef::GitRefs => {
-            let mut commit_ids = vec![];
-            for ref_target in repo.view().git_refs().values() {
-                commit_ids.extend(ref_target.added_ids().cloned());
-            }
+        RevsetCommitRef::GitRefs(pattern) => {
+            let commit_ids = repo
+                .view()
+                .git_refs_matching(pattern)
+                .flat_map(|(_, target)| target.added_ids())
+                .cloned()
+                .collect();
             Ok(commit_ids)
         }
         RevsetCommitRef::GitHead => Ok(repo.view().git_head().added_ids().cloned().collect()),
+        RevsetCommitRef::FetchCreated => {
+            let (created_ids, _) = resolve_last_fetched_remote_branches(repo)?;
+            Ok(created_ids)
+        }
+        RevsetCommitRef::FetchUpdated => {
+            let (_, updated_ids) = resolve_last_fetched_remote_branches(repo)?;
+            Ok(updated_ids)
+        }
+    }
+}
+
+/// Returns the targets of the remote branches created and updated by the most
+/// recent operation which changed any remote branch target, such as
+/// `jj git fetch`.
+///
+/// The operation is found by walking back the first parents from the current
+/// operation and comparing each view with its parent's. Pending changes of a
+/// `MutableRepo` count as the most recent operation.
+fn resolve_last_fetched_remote_branches(
+    repo: &dyn Repo,
+) -> Result<(Vec<CommitId>, Vec<CommitId>), RevsetResolutionError> {
+    let to_resolution_error = |err: OpStoreError| RevsetResolutionError::Other(err.into());
+    let mut new_view = repo.view().clone();
+    let mut op = repo.base_operation().clone();
+    loop {
+        let old_view = op.view().map_err(to_resolution_error)?;
+        if let Some(ids) = diff_remote_branch_targets(&old_view, &new_view) {
+            return Ok(ids);
+        }
+        let Some(parent_op) = op.parents().next() else {
+            return Ok((vec![], vec![]));
+        };
+        op = parent_op.map_err(to_resolution_error)?;
+        new_view = old_view;
+    }
+}
+
+/// Returns the targets of the created and updated remote branches, or `None`
+/// if no remote branch target changed between the views. Remote branches which
+/// were only deleted don't contribute any target.
+fn diff_remote_branch_targets(
+    old_view: &View,
+    new_view: &View,
+) -> Option<(Vec<CommitId>, Vec<CommitId>)> {
+    fn remote_targets(view: &View) -> Vec<((&str, &str), &RefTarget)> {
+        view.all_remote_branches()
+            .filter(|&((_, remote_name), _)| !is_local_git_remote(remote_name))
+            .map(|(symbol, remote_ref)| (symbol, &remote_ref.target))
+            .collect()
+    }
+    let new_targets = remote_targets(new_view);
+    if remote_targets(old_view) == new_targets {
+        return None;
+    }
+    let mut created_ids = vec![];
+    let mut updated_ids = vec![];
+    for ((name, remote_name), new_target) in new_targets {
+        let old_target = &old_view.get_remote_branch(name, remote_name).target;
+        if old_target == new_target {
+            continue;
+        }
+        let ids = if old_target.is_present() {
+            &mut updated_ids
+        } else {
+            &mut created_ids
+        };
+        ids.extend(new_target.added_ids().cloned());
+    }
+    Some((created_ids, updated_ids))
+}
+
+fn is_local_git_remote(remote_name: &str) -> bool {
+    #[cfg(feature = "git")]
+    {
+        remote_name == crate::git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+    }
+    #[cfg(not(feature = "git"))]
+    {
+        let _ = remote_name;
+        false
     }
 }
 
@@ -1828,6 +2586,13 @@ impl VisibilityResolutionContext<'_> {
                 heads: self.resolve_visible_heads().into(),
                 generation_from_roots: generation.clone(),
             },
+            RevsetExpression::FirstParentDescendants { roots, generation } => {
+                ResolvedExpression::FirstParentDagRange {
+                    roots: self.resolve(roots).into(),
+                    heads: self.resolve_visible_heads().into(),
+                    generation_from_roots: generation.clone(),
+                }
+            }
             RevsetExpression::Range {
                 roots,
                 heads,
@@ -1842,6 +2607,15 @@ impl VisibilityResolutionContext<'_> {
                 heads: self.resolve(heads).into(),
                 generation_from_roots: GENERATION_RANGE_FULL,
             },
+            RevsetExpression::DagRangeWithin {
+                roots,
+                heads,
+                domain,
+            } => ResolvedExpression::DagRangeWithin {
+                roots: self.resolve(roots).into(),
+                heads: self.resolve(heads).into(),
+                domain: self.resolve(domain).into(),
+            },
             RevsetExpression::Reachable { sources, domain } => ResolvedExpression::Reachable {
                 sources: self.resolve(sources).into(),
                 domain: self.resolve(domain).into(),
@@ -1852,11 +2626,25 @@ impl VisibilityResolutionContext<'_> {
             RevsetExpression::Roots(candidates) => {
                 ResolvedExpression::Roots(self.resolve(candidates).into())
             }
-            RevsetExpression::Latest { candidates, count } => ResolvedExpression::Latest {
+            RevsetExpression::Latest {
+                candidates,
+                count,
+                key,
+            } => ResolvedExpression::Latest {
                 candidates: self.resolve(candidates).into(),
                 count: *count,
+                key: *key,
             },
-            RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_) => {
+            RevsetExpression::Sorted { candidates, key } => ResolvedExpression::Sorted {
+                candidates: self.resolve(candidates).into(),
+                key: *key,
+            },
+            RevsetExpression::DistinctChangeIds(candidates) => {
+                ResolvedExpression::DistinctChangeIds(self.resolve(candidates).into())
+            }
+            RevsetExpression::Filter(_)
+            | RevsetExpression::ChangedSince(_)
+            | RevsetExpression::AsFilter(_) => {
                 // Top-level filter without intersection: e.g. "~author(_)" is represented as
                 // `AsFilter(NotIn(Filter(Author(_))))`.
                 ResolvedExpression::FilterWithin {
@@ -1877,12 +2665,12 @@ impl VisibilityResolutionContext<'_> {
             ),
             RevsetExpression::Intersection(expression1, expression2) => {
                 match expression2.as_ref() {
-                    RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_) => {
-                        ResolvedExpression::FilterWithin {
-                            candidates: self.resolve(expression1).into(),
-                            predicate: self.resolve_predicate(expression2),
-                        }
-                    }
+                    RevsetExpression::Filter(_)
+                    | RevsetExpression::ChangedSince(_)
+                    | RevsetExpression::AsFilter(_) => ResolvedExpression::FilterWithin {
+                        candidates: self.resolve(expression1).into(),
+                        predicate: self.resolve_predicate(expression2),
+                    },
                     _ => ResolvedExpression::Intersection(
                         self.resolve(expression1).into(),
                         self.resolve(expression2).into(),
@@ -1928,17 +2716,24 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::CommitRef(_)
             | RevsetExpression::Ancestors { .. }
             | RevsetExpression::Descendants { .. }
+            | RevsetExpression::FirstParentDescendants { .. }
             | RevsetExpression::Range { .. }
             | RevsetExpression::DagRange { .. }
+            | RevsetExpression::DagRangeWithin { .. }
             | RevsetExpression::Reachable { .. }
             | RevsetExpression::Heads(_)
             | RevsetExpression::Roots(_)
-            | RevsetExpression::Latest { .. } => {
+            | RevsetExpression::Latest { .. }
+            | RevsetExpression::Sorted { .. }
+            | RevsetExpression::DistinctChangeIds(_) => {
                 ResolvedPredicateExpression::Set(self.resolve(expression).into())
             }
             RevsetExpression::Filter(predicate) => {
                 ResolvedPredicateExpression::Filter(predicate.clone())
             }
+            RevsetExpression::ChangedSince(reference) => {
+                ResolvedPredicateExpression::ChangedSince(self.resolve(reference).into())
+            }
             RevsetExpression::AsFilter(candidates) => self.resolve_predicate(candidates),
             RevsetExpression::Present(_) => {
                 panic!("Expression '{expression:?}' should have been resolved by caller")
@@ -2082,8 +2877,9 @@ impl RevsetExtensions {
 #[derive(Clone)]
 pub struct RevsetParseContext<'a> {
     aliases_map: &'a RevsetAliasesMap,
-    user_email: String,
+    user_emails: Vec<String>,
     date_pattern_context: DatePatternContext,
+    diff_max_scan_bytes: Option<u64>,
     extensions: &'a RevsetExtensions,
     workspace: Option<RevsetWorkspaceContext<'a>>,
 }
@@ -2091,15 +2887,17 @@ pub struct RevsetParseContext<'a> {
 impl<'a> RevsetParseContext<'a> {
     pub fn new(
         aliases_map: &'a RevsetAliasesMap,
-        user_email: String,
+        user_emails: Vec<String>,
         date_pattern_context: DatePatternContext,
+        diff_max_scan_bytes: Option<u64>,
         extensions: &'a RevsetExtensions,
         workspace: Option<RevsetWorkspaceContext<'a>>,
     ) -> Self {
         Self {
             aliases_map,
-            user_email,
+            user_emails,
             date_pattern_context,
+            diff_max_scan_bytes,
             extensions,
             workspace,
         }
@@ -2109,14 +2907,19 @@ impl<'a> RevsetParseContext<'a> {
         self.aliases_map
     }
 
-    pub fn user_email(&self) -> &str {
-        &self.user_email
+    pub fn user_emails(&self) -> &[String] {
+        &self.user_emails
     }
 
     pub fn date_pattern_context(&self) -> &DatePatternContext {
         &self.date_pattern_context
     }
 
+    /// Default size limit of files to be scanned by `diff_contains()`.
+    pub fn diff_max_scan_bytes(&self) -> Option<u64> {
+        self.diff_max_scan_bytes
+    }
+
     pub fn symbol_resolvers(&self) -> &[impl AsRef<dyn SymbolResolverExtension>] {
         self.extensions.symbol_resolvers()
     }
@@ -2159,8 +2962,9 @@ mod tests {
         let extensions = RevsetExtensions::default();
         let context = RevsetParseContext::new(
             &aliases_map,
-            "test.user@example.com".to_string(),
+            vec!["test.user@example.com".to_string()],
             chrono::Utc::now().fixed_offset().into(),
+            None,
             &extensions,
             None,
         );
@@ -2188,8 +2992,9 @@ mod tests {
         let extensions = RevsetExtensions::default();
         let context = RevsetParseContext::new(
             &aliases_map,
-            "test.user@example.com".to_string(),
+            vec!["test.user@example.com".to_string()],
             chrono::Utc::now().fixed_offset().into(),
+            None,
             &extensions,
             Some(workspace_ctx),
         );
@@ -2213,8 +3018,9 @@ mod tests {
         let extensions = RevsetExtensions::default();
         let context = RevsetParseContext::new(
             &aliases_map,
-            "test.user@example.com".to_string(),
+            vec!["test.user@example.com".to_string()],
             chrono::Utc::now().fixed_offset().into(),
+            None,
             &extensions,
             None,
         );
@@ -2298,6 +3104,20 @@ mod tests {
             heads: CommitRef(Symbol("foo")),
         }
         "###);
+        insta::assert_debug_snapshot!(
+            foo_symbol.connected_within(&current_wc), @r###"
+        DagRangeWithin {
+            roots: Intersection(
+                CommitRef(Symbol("foo")),
+                CommitRef(WorkingCopy(WorkspaceId("default"))),
+            ),
+            heads: Intersection(
+                CommitRef(Symbol("foo")),
+                CommitRef(WorkingCopy(WorkspaceId("default"))),
+            ),
+            domain: CommitRef(WorkingCopy(WorkspaceId("default"))),
+        }
+        "###);
         insta::assert_debug_snapshot!(
             foo_symbol.range(&current_wc), @r###"
         Range {
@@ -2573,6 +3393,12 @@ mod tests {
         insta::assert_debug_snapshot!(
             parse(r#"branches(substring:"foo")"#).unwrap(),
             @r###"CommitRef(Branches(Substring("foo")))"###);
+        insta::assert_debug_snapshot!(
+            parse(r#"working_copies(exact:"ws1")"#).unwrap(),
+            @r###"CommitRef(WorkingCopies(Exact("ws1")))"###);
+        insta::assert_debug_snapshot!(
+            parse(r#"tags(exact:"v1.0")"#).unwrap(),
+            @r###"CommitRef(Tags(Exact("v1.0")))"###);
         insta::assert_debug_snapshot!(
             parse(r#"branches(bad:"foo")"#).unwrap_err().kind(),
             @r###"Expression("Invalid string pattern")"###);
@@ -2619,6 +3445,28 @@ mod tests {
             generation: 0..18446744073709551615,
         }
         "###);
+        insta::assert_debug_snapshot!(
+            parse("ancestors(foo, until=bar)").unwrap(), @r###"
+        Union(
+            Range {
+                roots: CommitRef(Symbol("bar")),
+                heads: CommitRef(Symbol("foo")),
+                generation: 0..18446744073709551615,
+            },
+            CommitRef(Symbol("foo")),
+        )
+        "###);
+        insta::assert_debug_snapshot!(
+            parse("ancestors(foo, 2, until=bar)").unwrap(), @r###"
+        Union(
+            Range {
+                roots: CommitRef(Symbol("bar")),
+                heads: CommitRef(Symbol("foo")),
+                generation: 0..2,
+            },
+            CommitRef(Symbol("foo")),
+        )
+        "###);
         insta::assert_debug_snapshot!(
             parse("parents(foo,foo)").unwrap_err().kind(), @r###"
         InvalidFunctionArguments {
@@ -2630,6 +3478,45 @@ mod tests {
             parse("root()").unwrap(),
             @"CommitRef(Root)");
         assert!(parse("root(a)").is_err());
+        insta::assert_debug_snapshot!(
+            parse("latest(foo, 1, size)").unwrap_err().kind(),
+            @r###"Expression("Expected sort key \"author\" or \"committer\"")"###);
+        insta::assert_debug_snapshot!(
+            parse("sorted(foo)").unwrap(), @r###"
+        Sorted {
+            candidates: CommitRef(Symbol("foo")),
+            key: CommitterDate,
+        }
+        "###);
+        insta::assert_debug_snapshot!(
+            parse("sorted(foo) | bar").unwrap_err().kind(),
+            @r###"Expression("sorted() can only be used as the outermost function")"###);
+        insta::assert_debug_snapshot!(
+            parse("heads(sorted(foo))").unwrap_err().kind(),
+            @r###"Expression("sorted() can only be used as the outermost function")"###);
+        insta::assert_debug_snapshot!(
+            parse("sorted(sorted(foo))").unwrap_err().kind(),
+            @r###"Expression("sorted() can only be used as the outermost function")"###);
+        insta::assert_debug_snapshot!(
+            parse("heads(sorted(foo, commit_id))").unwrap_err().kind(),
+            @r###"Expression("sorted() can only be used as the outermost function")"###);
+        insta::assert_debug_snapshot!(
+            parse("sorted(foo, commit_id) ~ bar").unwrap_err().kind(),
+            @r###"Expression("sorted() can only be used as the outermost function")"###);
+        insta::assert_debug_snapshot!(
+            parse("merges(3)").unwrap(), @"Filter(ParentCount(3..4))");
+        insta::assert_debug_snapshot!(
+            parse("merges(atleast:3)").unwrap(), @"Filter(ParentCount(3..4294967295))");
+        insta::assert_debug_snapshot!(
+            parse("merges(1)").unwrap_err().kind(), @r###"Expression("Invalid parent count")"###);
+        insta::assert_debug_snapshot!(
+            parse("merges(atmost:3)").unwrap_err().kind(), @r###"Expression("Invalid parent count")"###);
+        insta::assert_debug_snapshot!(
+            parse("changed_files(atleast:50)").unwrap(), @"Filter(ChangedFileCount(50..4294967295))");
+        insta::assert_debug_snapshot!(
+            parse("changed_files(atmost:1)").unwrap(), @"Filter(ChangedFileCount(0..2))");
+        insta::assert_debug_snapshot!(
+            parse("changed_files(more:1)").unwrap_err().kind(), @r###"Expression("Invalid file count")"###);
         insta::assert_debug_snapshot!(
             parse(r#"description("")"#).unwrap(),
             @r###"Filter(Description(Substring("")))"###);
@@ -2690,6 +3577,26 @@ mod tests {
             ),
         )
         "###);
+        insta::assert_debug_snapshot!(
+            parse_with_workspace("file(foo, kind=removed)", &WorkspaceId::default()).unwrap(),
+            @r###"
+        Filter(
+            FileChange {
+                files: Pattern(PrefixPath("foo")),
+                kind: Removed,
+            },
+        )
+        "###);
+        insta::assert_debug_snapshot!(
+            parse_with_workspace("file(foo, kind=renamed)", &WorkspaceId::default()).unwrap_err().kind(),
+            @r###"
+        Expression(
+            "Expected change kind \"added\", \"removed\", or \"modified\"",
+        )
+        "###);
+        assert!(
+            parse_with_workspace("file(foo, bar, kind=added)", &WorkspaceId::default()).is_err()
+        );
     }
 
     #[test]
@@ -2824,6 +3731,35 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_referenced_symbols() {
+        assert_eq!(
+            parse("main | (feature-1 & ~feature-2)..release@origin")
+                .unwrap()
+                .referenced_symbols(),
+            ["main", "feature-1", "feature-2", "release@origin"]
+        );
+        // Duplicated symbols are reported once
+        assert_eq!(
+            parse("main::feature | feature+ | main-")
+                .unwrap()
+                .referenced_symbols(),
+            ["main", "feature"]
+        );
+        // Symbols in function arguments are included, but string patterns
+        // aren't symbols
+        assert_eq!(
+            parse("ancestors(main, 2) | branches(feature) | author(main)")
+                .unwrap()
+                .referenced_symbols(),
+            ["main"]
+        );
+        assert!(parse("all() ~ root()")
+            .unwrap()
+            .referenced_symbols()
+            .is_empty());
+    }
+
     #[test]
     fn test_optimize_subtree() {
         let settings = insta_settings();
@@ -2865,7 +3801,7 @@ mod tests {
             optimize(parse("(branches() & all())..(all() & tags())").unwrap()), @r###"
         Range {
             roots: CommitRef(Branches(Substring(""))),
-            heads: CommitRef(Tags),
+            heads: CommitRef(Tags(Substring(""))),
             generation: 0..18446744073709551615,
         }
         "###);
@@ -2873,7 +3809,7 @@ mod tests {
             optimize(parse("(branches() & all())::(all() & tags())").unwrap()), @r###"
         DagRange {
             roots: CommitRef(Branches(Substring(""))),
-            heads: CommitRef(Tags),
+            heads: CommitRef(Tags(Substring(""))),
         }
         "###);
 
@@ -2889,8 +3825,36 @@ mod tests {
         Latest {
             candidates: CommitRef(Branches(Substring(""))),
             count: 2,
+            key: CommitterDate,
         }
         "###);
+        insta::assert_debug_snapshot!(
+            optimize(parse("latest(branches() & all(), 2, author)").unwrap()), @r###"
+        Latest {
+            candidates: CommitRef(Branches(Substring(""))),
+            count: 2,
+            key: AuthorDate,
+        }
+        "###);
+        insta::assert_debug_snapshot!(
+            optimize(parse("sorted(branches() & all(), author_date)").unwrap()), @r###"
+        Sorted {
+            candidates: CommitRef(Branches(Substring(""))),
+            key: AuthorDate,
+        }
+        "###);
+
+        insta::assert_debug_snapshot!(
+            optimize(parse("sorted(heads(all()), commit_id)").unwrap()), @r###"
+        Sorted {
+            candidates: Heads(All),
+            key: CommitId,
+        }
+        "###);
+
+        insta::assert_debug_snapshot!(
+            optimize(parse("distinct_change_ids(branches() & all())").unwrap()),
+            @r###"DistinctChangeIds(CommitRef(Branches(Substring(""))))"###);
 
         insta::assert_debug_snapshot!(
             optimize(parse("present(foo ~ bar)").unwrap()), @r###"
@@ -2912,21 +3876,21 @@ mod tests {
             optimize(parse("(branches() & all()) | (all() & tags())").unwrap()), @r###"
         Union(
             CommitRef(Branches(Substring(""))),
-            CommitRef(Tags),
+            CommitRef(Tags(Substring(""))),
         )
         "###);
         insta::assert_debug_snapshot!(
             optimize(parse("(branches() & all()) & (all() & tags())").unwrap()), @r###"
         Intersection(
             CommitRef(Branches(Substring(""))),
-            CommitRef(Tags),
+            CommitRef(Tags(Substring(""))),
         )
         "###);
         insta::assert_debug_snapshot!(
             optimize(parse("(branches() & all()) ~ (all() & tags())").unwrap()), @r###"
         Difference(
             CommitRef(Branches(Substring(""))),
-            CommitRef(Tags),
+            CommitRef(Tags(Substring(""))),
         )
         "###);
     }
@@ -2976,7 +3940,7 @@ mod tests {
         ));
         assert_matches!(
             unwrap_union(&optimized).1.as_ref(),
-            RevsetExpression::CommitRef(RevsetCommitRef::Tags)
+            RevsetExpression::CommitRef(RevsetCommitRef::Tags(_))
         );
     }
 
@@ -3013,6 +3977,28 @@ mod tests {
         )
         "###);
 
+        // Strict ancestors/descendants aren't rewritten to generation ranges,
+        // which would keep the input commits that are ancestors/descendants of
+        // other input commits
+        insta::assert_debug_snapshot!(optimize(parse("strict_ancestors(foo)").unwrap()), @r###"
+        Difference(
+            Ancestors {
+                heads: CommitRef(Symbol("foo")),
+                generation: 0..18446744073709551615,
+            },
+            CommitRef(Symbol("foo")),
+        )
+        "###);
+        insta::assert_debug_snapshot!(optimize(parse("strict_descendants(foo)").unwrap()), @r###"
+        Difference(
+            Descendants {
+                roots: CommitRef(Symbol("foo")),
+                generation: 0..18446744073709551615,
+            },
+            CommitRef(Symbol("foo")),
+        )
+        "###);
+
         // Binary difference operation should go through the same optimization passes.
         insta::assert_debug_snapshot!(
             optimize(parse("all() ~ foo").unwrap()),
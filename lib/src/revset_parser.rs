@@ -606,7 +606,13 @@ fn parse_primary_node(pair: Pair<Rule>) -> Result<ExpressionNode, RevsetParseErr
     let mut pairs = pair.into_inner();
     let first = pairs.next().unwrap();
     let expr = match first.as_rule() {
-        Rule::expression => return parse_expression_node(first.into_inner()),
+        // Include the parentheses in the span so the source text of the
+        // enclosing expression can be reparsed (e.g. as a fileset.)
+        Rule::expression => {
+            let mut node = parse_expression_node(first.into_inner())?;
+            node.span = span;
+            return Ok(node);
+        }
         Rule::function => {
             let function = Box::new(parse_function_call_node(first)?);
             ExpressionKind::FunctionCall(function)
@@ -1240,6 +1246,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_parenthesized_span() {
+        // Enclosing expressions cover the parentheses of their operands
+        let node = parse_program("~(x | y)").unwrap();
+        assert_eq!(node.span.as_str(), "~(x | y)");
+        let node = parse_program("(x) & (y)").unwrap();
+        assert_eq!(node.span.as_str(), "(x) & (y)");
+        let node = parse_program("((x))").unwrap();
+        assert_eq!(node.span.as_str(), "((x))");
+    }
+
     #[test]
     fn test_parse_string_literal() {
         // "\<char>" escapes
@@ -452,6 +452,23 @@ impl<'settings, 'repo> DescendantRebaser<'settings, 'repo> {
     }
 }
 
+/// Options for `move_commits()`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MoveCommitsOptions {
+    /// If a commit in the target set which isn't a root of the set has parents
+    /// outside the set (e.g. a merge with a branch outside the set), keep those
+    /// parents. Otherwise, only the parents within the set are kept.
+    pub retain_external_parents: bool,
+}
+
+impl Default for MoveCommitsOptions {
+    fn default() -> Self {
+        MoveCommitsOptions {
+            retain_external_parents: true,
+        }
+    }
+}
+
 pub struct MoveCommitsStats {
     /// The number of commits in the target set which were rebased.
     pub num_rebased_targets: u32,
@@ -475,6 +492,7 @@ pub fn move_commits(
     new_parent_ids: &[CommitId],
     new_children: &[Commit],
     target_commits: &[Commit],
+    options: &MoveCommitsOptions,
 ) -> BackendResult<MoveCommitsStats> {
     if target_commits.is_empty() {
         return Ok(MoveCommitsStats {
@@ -492,7 +510,9 @@ pub fn move_commits(
             .evaluate_programmatic(mut_repo)
             .map_err(|err| match err {
                 RevsetEvaluationError::StoreError(err) => err,
-                RevsetEvaluationError::Other(_) => panic!("Unexpected revset error: {err}"),
+                RevsetEvaluationError::LimitExceeded(_) | RevsetEvaluationError::Other(_) => {
+                    panic!("Unexpected revset error: {err}")
+                }
             })?
             .iter()
             .commits(mut_repo.store())
@@ -574,7 +594,9 @@ pub fn move_commits(
                 .evaluate_programmatic(mut_repo)
                 .map_err(|err| match err {
                     RevsetEvaluationError::StoreError(err) => err,
-                    RevsetEvaluationError::Other(_) => panic!("Unexpected revset error: {err}"),
+                    RevsetEvaluationError::LimitExceeded(_) | RevsetEvaluationError::Other(_) => {
+                        panic!("Unexpected revset error: {err}")
+                    }
                 })?
                 .iter()
                 .commits(mut_repo.store())
@@ -703,7 +725,9 @@ pub fn move_commits(
         .evaluate_programmatic(mut_repo)
         .map_err(|err| match err {
             RevsetEvaluationError::StoreError(err) => err,
-            RevsetEvaluationError::Other(_) => panic!("Unexpected revset error: {err}"),
+            RevsetEvaluationError::LimitExceeded(_) | RevsetEvaluationError::Other(_) => {
+                panic!("Unexpected revset error: {err}")
+            }
         })?
         .iter()
         .commits(mut_repo.store())
@@ -733,7 +757,7 @@ pub fn move_commits(
                 //    connected target set with their ancestor commits which are in the target
                 //    set.
                 // 3. Keep other parents outside the target set if they are not descendants of the
-                //    new children of the target set.
+                //    new children of the target set, unless they should be dropped.
                 else {
                     let mut new_parents = vec![];
                     for parent_id in commit.parent_ids() {
@@ -742,7 +766,8 @@ pub fn move_commits(
                         } else if let Some(parents) =
                                 connected_target_commits_internal_parents.get(parent_id) {
                             new_parents.extend(parents.iter().cloned());
-                        } else if !new_children.iter().any(|new_child| {
+                        } else if options.retain_external_parents
+                            && !new_children.iter().any(|new_child| {
                                 mut_repo.index().is_ancestor(new_child.id(), parent_id) }) {
                             new_parents.push(parent_id.clone());
                         }
@@ -26,6 +26,7 @@ use crate::backend::ChangeId;
 use crate::backend::Commit;
 use crate::backend::Signature;
 use crate::backend::Timestamp;
+use crate::conflicts::ConflictMarkerStyle;
 use crate::fmt_util::binary_prefix;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::signing::SignBehavior;
@@ -46,6 +47,22 @@ pub struct RepoSettings {
 pub struct GitSettings {
     pub auto_local_branch: bool,
     pub abandon_unreachable_commits: bool,
+    pub local_branch_update: LocalBranchUpdate,
+}
+
+/// How local branches are updated when the remote branches they track are
+/// imported.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LocalBranchUpdate {
+    /// Merge remote changes into local branches. Diverged branches become
+    /// conflicted.
+    #[default]
+    Merge,
+    /// Move local branches only if the new remote target is a descendant of
+    /// the local target.
+    FastForward,
+    /// Leave local branches untouched.
+    Skip,
 }
 
 impl GitSettings {
@@ -55,6 +72,7 @@ impl GitSettings {
             abandon_unreachable_commits: config
                 .get_bool("git.abandon-unreachable-commits")
                 .unwrap_or(true),
+            local_branch_update: LocalBranchUpdate::default(),
         }
     }
 }
@@ -64,6 +82,7 @@ impl Default for GitSettings {
         GitSettings {
             auto_local_branch: false,
             abandon_unreachable_commits: true,
+            local_branch_update: LocalBranchUpdate::default(),
         }
     }
 }
@@ -162,6 +181,24 @@ impl UserSettings {
         self.config.get_string("user.email").unwrap_or_default()
     }
 
+    /// Email addresses identifying the current user, e.g. for the `mine()`
+    /// revset. Falls back to `user.email` if `user.emails` isn't set.
+    pub fn user_emails(&self) -> Vec<String> {
+        match self.config.get::<Vec<String>>("user.emails") {
+            Ok(emails) if !emails.is_empty() => emails,
+            _ => vec![self.user_email()],
+        }
+    }
+
+    /// Size limit of files to be scanned by the `diff_contains()` revset.
+    /// Larger files are skipped. Returns `None` if unlimited.
+    pub fn diff_max_scan_bytes(&self) -> Option<u64> {
+        match self.config.get::<HumanByteSize>("diff.max-scan-bytes") {
+            Ok(HumanByteSize(0)) | Err(_) => None,
+            Ok(HumanByteSize(size)) => Some(size),
+        }
+    }
+
     pub fn fsmonitor_settings(&self) -> Result<FsmonitorSettings, config::ConfigError> {
         FsmonitorSettings::from_config(&self.config)
     }
@@ -264,6 +301,15 @@ impl UserSettings {
         }
     }
 
+    /// The style of the conflict markers written to the working copy.
+    pub fn conflict_marker_style(&self) -> Result<ConflictMarkerStyle, config::ConfigError> {
+        Ok(self
+            .config
+            .get("ui.conflict-marker-style")
+            .optional()?
+            .unwrap_or_default())
+    }
+
     // separate from sign_settings as those two are needed in pretty different
     // places
     pub fn signing_backend(&self) -> Option<String> {
@@ -247,6 +247,72 @@ impl fmt::Display for StringPattern {
     }
 }
 
+/// [`StringPattern`] which may be prefixed with `~` to exclude the matching
+/// strings instead of selecting them.
+#[derive(Clone, Debug)]
+pub struct NegatableStringPattern {
+    pattern: StringPattern,
+    negated: bool,
+}
+
+impl NegatableStringPattern {
+    /// Parses the given string as a [`StringPattern`], which is negated if the
+    /// string starts with `~`.
+    pub fn parse(src: &str) -> Result<Self, StringPatternParseError> {
+        let (negated, src) = match src.strip_prefix('~') {
+            Some(rest) => (true, rest),
+            None => (false, src),
+        };
+        let pattern = StringPattern::parse(src)?;
+        Ok(NegatableStringPattern { pattern, negated })
+    }
+
+    /// Returns the underlying pattern without negation.
+    pub fn pattern(&self) -> &StringPattern {
+        &self.pattern
+    }
+
+    /// Returns true if this pattern excludes the matching strings.
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Returns true if the `haystack` is selected by the given `patterns`.
+    ///
+    /// A string is selected if it matches any of the non-negated patterns and
+    /// none of the negated patterns. If all patterns are negated, any string
+    /// that isn't excluded is selected.
+    pub fn select(patterns: &[Self], haystack: &str) -> bool {
+        let (excluded, included): (Vec<_>, Vec<_>) =
+            patterns.iter().partition(|pattern| pattern.negated);
+        (included.is_empty() || included.iter().any(|p| p.pattern.matches(haystack)))
+            && !excluded.iter().any(|p| p.pattern.matches(haystack))
+    }
+}
+
+impl fmt::Display for NegatableStringPattern {
+    /// Shows this pattern with its kind prefix, so it can be parsed back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            write!(f, "~")?;
+        }
+        let kind = match &self.pattern {
+            StringPattern::Exact(literal) if !literal.contains(':') => None,
+            StringPattern::Exact(_) => Some("exact"),
+            StringPattern::ExactI(_) => Some("exact-i"),
+            StringPattern::Substring(_) => Some("substring"),
+            StringPattern::SubstringI(_) => Some("substring-i"),
+            StringPattern::Glob(_) => Some("glob"),
+            StringPattern::GlobI(_) => Some("glob-i"),
+            StringPattern::Regex(_) => Some("regex"),
+        };
+        if let Some(kind) = kind {
+            write!(f, "{kind}:")?;
+        }
+        write!(f, "{}", self.pattern)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
@@ -334,4 +400,44 @@ mod tests {
             Err(StringPatternParseError::InvalidKind(_))
         );
     }
+
+    #[test]
+    fn test_negatable_pattern() {
+        let parse = |src| NegatableStringPattern::parse(src).unwrap();
+        assert_matches!(
+            NegatableStringPattern::parse("~glob:tmp/*"),
+            Ok(NegatableStringPattern { pattern: StringPattern::Glob(p), negated: true })
+                if p.as_str() == "tmp/*"
+        );
+        assert_matches!(
+            NegatableStringPattern::parse("foo"),
+            Ok(NegatableStringPattern { pattern: StringPattern::Exact(s), negated: false })
+                if s == "foo"
+        );
+        assert_matches!(
+            NegatableStringPattern::parse("~unknown-prefix:foo"),
+            Err(StringPatternParseError::InvalidKind(_))
+        );
+        assert_eq!(parse("~glob:tmp/*").to_string(), "~glob:tmp/*");
+        assert_eq!(parse("foo").to_string(), "foo");
+        assert_eq!(parse("~exact:foo").to_string(), "~foo");
+        assert_eq!(parse("exact:foo:bar").to_string(), "exact:foo:bar");
+        assert_eq!(parse("substring-i:Foo").to_string(), "substring-i:Foo");
+
+        // Included patterns are OR-ed, and excluded patterns are subtracted.
+        let patterns = [parse("glob:*"), parse("~glob:tmp/*"), parse("~main")];
+        assert!(NegatableStringPattern::select(&patterns, "feature"));
+        assert!(!NegatableStringPattern::select(&patterns, "tmp/foo"));
+        assert!(!NegatableStringPattern::select(&patterns, "main"));
+        let patterns = [parse("glob:f*"), parse("glob:tmp/*"), parse("~tmp/bar")];
+        assert!(NegatableStringPattern::select(&patterns, "feature"));
+        assert!(NegatableStringPattern::select(&patterns, "tmp/foo"));
+        assert!(!NegatableStringPattern::select(&patterns, "tmp/bar"));
+        assert!(!NegatableStringPattern::select(&patterns, "main"));
+
+        // Only negated patterns select everything else.
+        let patterns = [parse("~glob:tmp/*")];
+        assert!(NegatableStringPattern::select(&patterns, "main"));
+        assert!(!NegatableStringPattern::select(&patterns, "tmp/foo"));
+    }
 }
@@ -47,6 +47,19 @@ impl DatePatternContext {
             DatePatternContext::Fixed(dt) => DatePattern::from_str_kind(s, kind, dt),
         }
     }
+
+    /// Parses a DatePattern matching the dates from `start` (inclusive) to
+    /// `end` (exclusive).
+    pub fn parse_range(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<DatePattern, DatePatternParseError> {
+        match *self {
+            DatePatternContext::Local(dt) => DatePattern::from_str_range(start, end, dt),
+            DatePatternContext::Fixed(dt) => DatePattern::from_str_range(start, end, dt),
+        }
+    }
 }
 
 impl From<DateTime<Local>> for DatePatternContext {
@@ -68,8 +81,14 @@ pub enum DatePatternParseError {
     #[error(r#"Invalid date pattern kind "{0}:""#)]
     InvalidKind(String),
     /// Failed to parse timestamp.
-    #[error(transparent)]
-    ParseError(#[from] DateError),
+    #[error(r#"Failed to parse date "{input}""#)]
+    ParseError {
+        /// The date string that couldn't be parsed.
+        input: String,
+        /// The underlying parse error.
+        #[source]
+        source: DateError,
+    },
 }
 
 /// Represents an range of dates that may be matched against.
@@ -79,6 +98,9 @@ pub enum DatePattern {
     AtOrAfter(MillisSinceEpoch),
     /// Represents all dates before, but not including, the given instant.
     Before(MillisSinceEpoch),
+    /// Represents all dates at or after the first instant, and before, but not
+    /// including, the second instant.
+    Between(MillisSinceEpoch, MillisSinceEpoch),
 }
 
 impl DatePattern {
@@ -103,9 +125,7 @@ impl DatePattern {
     where
         Tz::Offset: Copy,
     {
-        let d =
-            parse_date_string(s, now, Dialect::Us).map_err(DatePatternParseError::ParseError)?;
-        let millis_since_epoch = MillisSinceEpoch(d.timestamp_millis());
+        let millis_since_epoch = parse_millis_since_epoch(s, now)?;
         match kind {
             "after" => Ok(DatePattern::AtOrAfter(millis_since_epoch)),
             "before" => Ok(DatePattern::Before(millis_since_epoch)),
@@ -113,15 +133,49 @@ impl DatePattern {
         }
     }
 
+    /// Parses two strings into a DatePattern matching the dates in the
+    /// half-open range from `start` to `end`.
+    pub fn from_str_range<Tz: TimeZone>(
+        start: &str,
+        end: &str,
+        now: DateTime<Tz>,
+    ) -> Result<DatePattern, DatePatternParseError>
+    where
+        Tz::Offset: Copy,
+    {
+        let earliest = parse_millis_since_epoch(start, now)?;
+        let latest = parse_millis_since_epoch(end, now)?;
+        Ok(DatePattern::Between(earliest, latest))
+    }
+
     /// Determines whether a given timestamp is matched by the pattern.
     pub fn matches(&self, timestamp: &Timestamp) -> bool {
         match self {
             DatePattern::AtOrAfter(earliest) => *earliest <= timestamp.timestamp,
             DatePattern::Before(latest) => timestamp.timestamp < *latest,
+            DatePattern::Between(earliest, latest) => {
+                *earliest <= timestamp.timestamp && timestamp.timestamp < *latest
+            }
         }
     }
 }
 
+fn parse_millis_since_epoch<Tz: TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Result<MillisSinceEpoch, DatePatternParseError>
+where
+    Tz::Offset: Copy,
+{
+    let d = parse_date_string(s, now, Dialect::Us).map_err(|source| {
+        DatePatternParseError::ParseError {
+            input: s.to_owned(),
+            source,
+        }
+    })?;
+    Ok(MillisSinceEpoch(d.timestamp_millis()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,6 +277,17 @@ impl View {
         }
     }
 
+    /// Iterates tag `(name, target)`s matching the given pattern. Entries are
+    /// sorted by `name`.
+    pub fn tags_matching<'a: 'b, 'b>(
+        &'a self,
+        pattern: &'b StringPattern,
+    ) -> impl Iterator<Item = (&'a str, &'a RefTarget)> + 'b {
+        pattern
+            .filter_btree_map(&self.data.tags)
+            .map(|(name, target)| (name.as_ref(), target))
+    }
+
     pub fn get_tag(&self, name: &str) -> &RefTarget {
         self.data.tags.get(name).flatten()
     }
@@ -295,6 +306,17 @@ impl View {
         self.data.git_refs.get(name).flatten()
     }
 
+    /// Iterates Git refs `(full_name, target)`s matching the given pattern.
+    /// Entries are sorted by `full_name`.
+    pub fn git_refs_matching<'a: 'b, 'b>(
+        &'a self,
+        pattern: &'b StringPattern,
+    ) -> impl Iterator<Item = (&'a str, &'a RefTarget)> + 'b {
+        pattern
+            .filter_btree_map(&self.data.git_refs)
+            .map(|(name, target)| (name.as_ref(), target))
+    }
+
     /// Sets the last imported Git ref to point to the given target. If the
     /// target is absent, the reference will be removed.
     pub fn set_git_ref_target(&mut self, name: &str, target: RefTarget) {
@@ -18,6 +18,7 @@ use jj_lib::conflicts::extract_as_single_hunk;
 use jj_lib::conflicts::materialize_merge_result;
 use jj_lib::conflicts::parse_conflict;
 use jj_lib::conflicts::update_from_content;
+use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::merge::Merge;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
@@ -813,7 +814,7 @@ fn test_update_conflict_from_content() {
     // old conflict id back.
     let materialized = materialize_conflict_string(store, path, &conflict);
     let parse = |content| {
-        update_from_content(&conflict, store, path, content)
+        update_from_content(&conflict, store, path, content, ConflictMarkerStyle::Jj)
             .block_on()
             .unwrap()
     };
@@ -862,7 +863,7 @@ fn test_update_conflict_from_content_modify_delete() {
     // old conflict id back.
     let materialized = materialize_conflict_string(store, path, &conflict);
     let parse = |content| {
-        update_from_content(&conflict, store, path, content)
+        update_from_content(&conflict, store, path, content, ConflictMarkerStyle::Jj)
             .block_on()
             .unwrap()
     };
@@ -916,7 +917,7 @@ fn test_update_conflict_from_content_simplified_conflict() {
     let materialized = materialize_conflict_string(store, path, &conflict);
     let materialized_simplified = materialize_conflict_string(store, path, &simplified_conflict);
     let parse = |content| {
-        update_from_content(&conflict, store, path, content)
+        update_from_content(&conflict, store, path, content, ConflictMarkerStyle::Jj)
             .block_on()
             .unwrap()
     };
@@ -1036,6 +1037,149 @@ fn test_update_conflict_from_content_simplified_conflict() {
     );
 }
 
+#[test]
+fn test_conflict_marker_styles() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    let path = RepoPath::from_internal_string("file");
+    let base_file_id = testutils::write_file(store, path, "line 1\nline 2\nline 3\n");
+    let left_file_id = testutils::write_file(store, path, "left 1\nline 2\nleft 3\n");
+    let right_file_id = testutils::write_file(store, path, "right 1\nline 2\nright 3\n");
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_file_id.clone())],
+        vec![Some(left_file_id.clone()), Some(right_file_id.clone())],
+    );
+    let materialize = |conflict: &Merge<Option<FileId>>, marker_style| {
+        let mut result: Vec<u8> = vec![];
+        let contents = extract_as_single_hunk(conflict, store, path)
+            .block_on()
+            .unwrap();
+        materialize_merge_result(&contents, marker_style, &mut result).unwrap();
+        String::from_utf8(result).unwrap()
+    };
+    let parse = |content: &str, marker_style| {
+        update_from_content(&conflict, store, path, content.as_bytes(), marker_style)
+            .block_on()
+            .unwrap()
+    };
+
+    let diff3 = materialize(&conflict, ConflictMarkerStyle::Diff3);
+    insta::assert_snapshot!(diff3, @r###"
+    <<<<<<< Conflict 1 of 2
+    left 1
+    ||||||| base
+    line 1
+    =======
+    right 1
+    >>>>>>> Conflict 1 of 2 ends
+    line 2
+    <<<<<<< Conflict 2 of 2
+    left 3
+    ||||||| base
+    line 3
+    =======
+    right 3
+    >>>>>>> Conflict 2 of 2 ends
+    "###);
+    assert_eq!(parse(&diff3, ConflictMarkerStyle::Diff3), conflict);
+    // The diff3 markers aren't understood in content written in the jj style
+    assert_eq!(parse_conflict(diff3.as_bytes(), 2), None);
+
+    let two_way = materialize(&conflict, ConflictMarkerStyle::TwoWay);
+    insta::assert_snapshot!(two_way, @r###"
+    <<<<<<< Conflict 1 of 2
+    left 1
+    =======
+    right 1
+    >>>>>>> Conflict 1 of 2 ends
+    line 2
+    <<<<<<< Conflict 2 of 2
+    left 3
+    =======
+    right 3
+    >>>>>>> Conflict 2 of 2 ends
+    "###);
+    assert_eq!(parse(&two_way, ConflictMarkerStyle::TwoWay), conflict);
+    // The base of two-way markers isn't known without the original conflict
+    assert_eq!(parse_conflict(two_way.as_bytes(), 2), None);
+
+    // In the jj style, the two-way markers are plain text, so the file is
+    // resolved with the markers as its contents
+    let two_way_in_jj_file = indoc! {"
+        resolved 1
+        line 2
+        <<<<<<< Conflict 2 of 2
+        edited left 3
+        =======
+        edited right 3
+        >>>>>>> Conflict 2 of 2 ends
+    "};
+    let resolved_file_id = testutils::write_file(store, path, two_way_in_jj_file);
+    assert_eq!(
+        parse(two_way_in_jj_file, ConflictMarkerStyle::Jj),
+        Merge::normal(resolved_file_id)
+    );
+
+    // The base of a two-way conflict is found by its number even if the
+    // conflicts before it were resolved
+    let new_conflict = parse(two_way_in_jj_file, ConflictMarkerStyle::TwoWay);
+    let new_base_file_id = testutils::write_file(store, path, "resolved 1\nline 2\nline 3\n");
+    let new_left_file_id =
+        testutils::write_file(store, path, "resolved 1\nline 2\nedited left 3\n");
+    let new_right_file_id =
+        testutils::write_file(store, path, "resolved 1\nline 2\nedited right 3\n");
+    assert_eq!(
+        new_conflict,
+        Merge::from_removes_adds(
+            vec![Some(new_base_file_id.clone())],
+            vec![
+                Some(new_left_file_id.clone()),
+                Some(new_right_file_id.clone())
+            ]
+        )
+    );
+    // The diff3 markers are understood as well, since they only add the base
+    let new_conflict = parse(
+        indoc! {"
+            resolved 1
+            line 2
+            <<<<<<< Conflict 2 of 2
+            edited left 3
+            ||||||| base
+            line 3
+            =======
+            edited right 3
+            >>>>>>> Conflict 2 of 2 ends
+        "},
+        ConflictMarkerStyle::TwoWay,
+    );
+    assert_eq!(
+        new_conflict,
+        Merge::from_removes_adds(
+            vec![Some(new_base_file_id.clone())],
+            vec![
+                Some(new_left_file_id.clone()),
+                Some(new_right_file_id.clone())
+            ]
+        )
+    );
+
+    // Conflicts with more than two sides are written in the jj style
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_file_id.clone()), Some(base_file_id.clone())],
+        vec![
+            Some(left_file_id.clone()),
+            Some(right_file_id.clone()),
+            Some(base_file_id.clone()),
+        ],
+    );
+    assert_eq!(
+        materialize(&conflict, ConflictMarkerStyle::Diff3),
+        materialize(&conflict, ConflictMarkerStyle::Jj)
+    );
+}
+
 fn materialize_conflict_string(
     store: &Store,
     path: &RepoPath,
@@ -1045,6 +1189,6 @@ fn materialize_conflict_string(
     let contents = extract_as_single_hunk(conflict, store, path)
         .block_on()
         .unwrap();
-    materialize_merge_result(&contents, &mut result).unwrap();
+    materialize_merge_result(&contents, ConflictMarkerStyle::Jj, &mut result).unwrap();
     String::from_utf8(result).unwrap()
 }
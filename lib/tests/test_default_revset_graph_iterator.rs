@@ -37,7 +37,13 @@ fn revset_for_commits(
         .unwrap();
     let expression =
         ResolvedExpression::Commits(commits.iter().map(|commit| commit.id().clone()).collect());
-    evaluate(&expression, repo.store(), index.clone()).unwrap()
+    evaluate(
+        &expression,
+        repo.store(),
+        index.clone(),
+        &Default::default(),
+    )
+    .unwrap()
 }
 
 fn direct(commit: &Commit) -> GraphEdge<CommitId> {
@@ -2636,6 +2636,8 @@ fn test_push_branches_success() {
                 new_target: Some(setup.child_of_main_commit.id().clone()),
             },
         )],
+        force_with_lease: false,
+        forced_branches: HashSet::new(),
     };
     let result = git::push_branches(
         tx.mut_repo(),
@@ -2705,6 +2707,8 @@ fn test_push_branches_deletion() {
                 new_target: None,
             },
         )],
+        force_with_lease: false,
+        forced_branches: HashSet::new(),
     };
     let result = git::push_branches(
         tx.mut_repo(),
@@ -2762,6 +2766,8 @@ fn test_push_branches_mixed_deletion_and_addition() {
                 },
             ),
         ],
+        force_with_lease: false,
+        forced_branches: HashSet::new(),
     };
     let result = git::push_branches(
         tx.mut_repo(),
@@ -2821,6 +2827,8 @@ fn test_push_branches_not_fast_forward() {
                 new_target: Some(setup.sideways_commit.id().clone()),
             },
         )],
+        force_with_lease: false,
+        forced_branches: HashSet::new(),
     };
     let result = git::push_branches(
         tx.mut_repo(),
@@ -2971,6 +2979,104 @@ fn test_push_updates_unexpectedly_moved_forward_on_remote() {
     );
 }
 
+#[test]
+fn test_push_branches_force_with_lease() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+
+    // The main branch is actually at `main_commit` on the remote, but we
+    // expect it to be at `parent_of_main_commit`. Moving it forward is allowed
+    // by default, but not if the lease is enforced.
+    let attempt_push = |force_with_lease: bool| {
+        let mut tx = setup.jj_repo.start_transaction(&settings);
+        let targets = GitBranchPushTargets {
+            branch_updates: vec![(
+                "main".to_owned(),
+                BranchPushUpdate {
+                    old_target: Some(setup.parent_of_main_commit.id().clone()),
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            )],
+            force_with_lease,
+            forced_branches: HashSet::new(),
+        };
+        git::push_branches(
+            tx.mut_repo(),
+            &clone_repo,
+            "origin",
+            &targets,
+            git::RemoteCallbacks::default(),
+        )
+    };
+
+    assert_eq!(
+        attempt_push(true),
+        Err(GitPushError::RefLeaseBroken(vec![
+            "refs/heads/main".to_owned()
+        ]))
+    );
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let target = source_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .target();
+    assert_eq!(target, Some(git_id(&setup.main_commit)));
+
+    assert_eq!(attempt_push(false), Ok(()));
+}
+
+#[test]
+fn test_push_branches_forced_branch() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+
+    // The main branch is actually at `main_commit` on the remote, but we
+    // expect it to be at `parent_of_main_commit`. Moving it sideways is only
+    // allowed if the branch is forced, even with the lease enforced.
+    let attempt_push = |forced_branches: &[&str]| {
+        let mut tx = setup.jj_repo.start_transaction(&settings);
+        let targets = GitBranchPushTargets {
+            branch_updates: vec![(
+                "main".to_owned(),
+                BranchPushUpdate {
+                    old_target: Some(setup.parent_of_main_commit.id().clone()),
+                    new_target: Some(setup.sideways_commit.id().clone()),
+                },
+            )],
+            force_with_lease: true,
+            forced_branches: forced_branches
+                .iter()
+                .map(|&name| name.to_owned())
+                .collect(),
+        };
+        git::push_branches(
+            tx.mut_repo(),
+            &clone_repo,
+            "origin",
+            &targets,
+            git::RemoteCallbacks::default(),
+        )
+    };
+
+    assert_eq!(
+        attempt_push(&["other"]),
+        Err(GitPushError::RefLeaseBroken(vec![
+            "refs/heads/main".to_owned()
+        ]))
+    );
+    assert_eq!(attempt_push(&["main"]), Ok(()));
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let target = source_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .target();
+    assert_eq!(target, Some(git_id(&setup.sideways_commit)));
+}
+
 #[test]
 fn test_push_updates_unexpectedly_exists_on_remote() {
     let settings = testutils::user_settings();
@@ -214,7 +214,9 @@ fn test_index_commits_criss_cross() {
             heads: ResolvedExpression::Commits(wanted.to_vec()).into(),
             generation,
         };
-        let revset = index.evaluate_revset(&expression, repo.store()).unwrap();
+        let revset = index
+            .evaluate_revset(&expression, repo.store(), &Default::default())
+            .unwrap();
         // Don't switch to more efficient .count() implementation. Here we're
         // testing the iterator behavior.
         revset.iter().count()
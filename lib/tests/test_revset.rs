@@ -14,10 +14,12 @@
 
 use std::iter;
 use std::path::Path;
+use std::rc::Rc;
 
 use assert_matches::assert_matches;
 use chrono::DateTime;
 use itertools::Itertools;
+use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
@@ -43,14 +45,19 @@ use jj_lib::revset::FailingSymbolResolver;
 use jj_lib::revset::ResolvedExpression;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetAliasesMap;
+use jj_lib::revset::RevsetEvaluationError;
+use jj_lib::revset::RevsetEvaluationOptions;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetExtensions;
 use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::RevsetResolutionError;
 use jj_lib::revset::RevsetWorkspaceContext;
+use jj_lib::revset::SortKey;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::settings::GitSettings;
+use jj_lib::settings::UserSettings;
+use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 use test_case::test_case;
 use testutils::create_random_commit;
@@ -68,8 +75,7 @@ fn resolve_symbol_with_extensions(
 ) -> Result<Vec<CommitId>, RevsetResolutionError> {
     let aliases_map = RevsetAliasesMap::default();
     let now = chrono::Local::now();
-    let context =
-        RevsetParseContext::new(&aliases_map, String::new(), now.into(), extensions, None);
+    let context = RevsetParseContext::new(&aliases_map, vec![], now.into(), None, extensions, None);
     let expression = parse(symbol, &context).unwrap();
     assert_matches!(*expression, RevsetExpression::CommitRef(_));
     let symbol_resolver = DefaultSymbolResolver::new(repo, extensions.symbol_resolvers());
@@ -205,8 +211,9 @@ fn test_resolve_symbol_commit_id() {
     let extensions = RevsetExtensions::default();
     let context = RevsetParseContext::new(
         &aliases_map,
-        settings.user_email(),
+        settings.user_emails(),
         chrono::Utc::now().fixed_offset().into(),
+        None,
         &extensions,
         None,
     );
@@ -433,7 +440,7 @@ fn test_resolve_working_copies() {
         .set_wc_commit(ws2.clone(), commit2.id().clone())
         .unwrap();
     let resolve = || -> Vec<CommitId> {
-        RevsetExpression::working_copies()
+        RevsetExpression::working_copies(StringPattern::everything())
             .evaluate_programmatic(mut_repo)
             .unwrap()
             .iter()
@@ -444,6 +451,62 @@ fn test_resolve_working_copies() {
     assert_eq!(resolve(), vec![commit2.id().clone(), commit1.id().clone()]);
 }
 
+#[test]
+fn test_evaluate_expression_working_copies() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = write_random_commit(mut_repo, &settings);
+    let commit3 = write_random_commit(mut_repo, &settings);
+
+    for (name, commit) in [
+        ("ws1", &commit1),
+        ("ws2", &commit2),
+        ("other", &commit3),
+        ("ws3", &commit3),
+    ] {
+        mut_repo
+            .set_wc_commit(WorkspaceId::new(name.to_string()), commit.id().clone())
+            .unwrap();
+    }
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "working_copies()"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    // Can select workspaces by pattern
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "working_copies(glob:'ws*')"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "working_copies(glob:'ws[12]')"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "working_copies(exact:other)"),
+        vec![commit3.id().clone()]
+    );
+    // Can silently resolve to an empty set if there's no matches
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "working_copies(exact:ws)"),
+        vec![]
+    );
+}
+
 #[test]
 fn test_resolve_symbol_branches() {
     let settings = testutils::user_settings();
@@ -725,6 +788,41 @@ fn test_resolve_symbol_tags() {
     );
 }
 
+#[test]
+fn test_shadowed_ref_names() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = write_random_commit(mut_repo, &settings);
+    assert!(mut_repo.shadowed_ref_names().is_empty());
+
+    mut_repo.set_tag_target("tag-branch", RefTarget::normal(commit1.id().clone()));
+    mut_repo.set_local_branch_target("tag-branch", RefTarget::normal(commit2.id().clone()));
+    mut_repo.set_tag_target("tag-only", RefTarget::normal(commit1.id().clone()));
+    mut_repo.set_local_branch_target("branch-only", RefTarget::normal(commit2.id().clone()));
+    // A Git ref can be referred to by its name without "refs/"
+    mut_repo.set_local_branch_target("heads/branch-git", RefTarget::normal(commit1.id().clone()));
+    mut_repo.set_git_ref_target(
+        "refs/heads/branch-git",
+        RefTarget::normal(commit2.id().clone()),
+    );
+    // Refs of different kinds that don't share the symbol aren't shadowed
+    mut_repo.set_git_ref_target(
+        "refs/heads/branch-only",
+        RefTarget::normal(commit2.id().clone()),
+    );
+
+    assert_eq!(
+        mut_repo.shadowed_ref_names(),
+        vec!["heads/branch-git".to_owned(), "tag-branch".to_owned()],
+    );
+}
+
 #[test]
 fn test_resolve_symbol_git_head() {
     let settings = testutils::user_settings();
@@ -866,8 +964,9 @@ fn resolve_commit_ids(repo: &dyn Repo, revset_str: &str) -> Vec<CommitId> {
     let revset_extensions = RevsetExtensions::default();
     let context = RevsetParseContext::new(
         &aliases_map,
-        settings.user_email(),
+        settings.user_emails(),
         chrono::Utc::now().fixed_offset().into(),
+        None,
         &revset_extensions,
         None,
     );
@@ -898,8 +997,9 @@ fn resolve_commit_ids_in_workspace(
     let extensions = RevsetExtensions::default();
     let context = RevsetParseContext::new(
         &aliases_map,
-        settings.user_email(),
+        settings.user_emails(),
         chrono::Utc::now().fixed_offset().into(),
+        None,
         &extensions,
         Some(workspace_ctx),
     );
@@ -1060,6 +1160,95 @@ fn test_evaluate_expression_roots() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_roots_within() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit3]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit6 = graph_builder.commit_with_parents(&[&commit4, &commit5]);
+
+    // Same as roots() if the domain includes everything
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "roots({} | {}, all())",
+                commit1.id().hex(),
+                commit3.id().hex()
+            )
+        ),
+        vec![commit1.id().clone()]
+    );
+
+    // The path between the commits leaves the domain
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "roots({} | {}, ~{})",
+                commit1.id().hex(),
+                commit3.id().hex(),
+                commit2.id().hex()
+            )
+        ),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+
+    // Commits outside the domain have no ancestors in the set
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "roots({} | {} | {}, {}::)",
+                commit1.id().hex(),
+                commit3.id().hex(),
+                commit4.id().hex(),
+                commit3.id().hex()
+            )
+        ),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+
+    // Another path through a merge stays within the domain
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "roots({} | {}, ~{})",
+                commit2.id().hex(),
+                commit6.id().hex(),
+                commit4.id().hex()
+            )
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "roots({} | {}, ~({} | {}))",
+                commit2.id().hex(),
+                commit6.id().hex(),
+                commit4.id().hex(),
+                commit5.id().hex()
+            )
+        ),
+        vec![commit6.id().clone(), commit2.id().clone()]
+    );
+
+    // Roots of an empty set is an empty set
+    assert_eq!(resolve_commit_ids(mut_repo, "roots(none(), all())"), vec![]);
+}
+
 #[test]
 fn test_evaluate_expression_parents() {
     let settings = testutils::user_settings();
@@ -1228,6 +1417,80 @@ fn test_evaluate_expression_children() {
     assert_eq!(resolve_commit_ids(mut_repo, "none()+"), vec![]);
 }
 
+#[test]
+fn test_evaluate_expression_siblings() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit1.id().clone()])
+        .write()
+        .unwrap();
+    let commit3 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit2.id().clone()])
+        .write()
+        .unwrap();
+    let commit4 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit1.id().clone()])
+        .write()
+        .unwrap();
+    let commit5 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit3.id().clone(), commit4.id().clone()])
+        .write()
+        .unwrap();
+    let commit6 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit5.id().clone()])
+        .write()
+        .unwrap();
+    let commit7 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit4.id().clone()])
+        .write()
+        .unwrap();
+
+    // The root commit has no parents, so it has no siblings
+    assert_eq!(resolve_commit_ids(mut_repo, "siblings(root())"), vec![]);
+    // The only child of the root commit has no siblings
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("siblings({})", commit1.id().hex())),
+        vec![]
+    );
+
+    // Other children of the parent are returned
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("siblings({})", commit2.id().hex())),
+        vec![commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("siblings({})", commit7.id().hex())),
+        vec![commit5.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("siblings({})", commit6.id().hex())),
+        vec![]
+    );
+
+    // Merge commits are siblings of the children of all of their parents
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("siblings({})", commit5.id().hex())),
+        vec![commit7.id().clone()]
+    );
+
+    // Commits in the input set are excluded even if they are siblings of each
+    // other
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("siblings({} | {})", commit3.id().hex(), commit4.id().hex())
+        ),
+        vec![commit2.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_ancestors() {
     let settings = testutils::user_settings();
@@ -1313,73 +1576,148 @@ fn test_evaluate_expression_ancestors() {
             commit1.id().clone(),
         ]
     );
-}
-
-#[test]
-fn test_evaluate_expression_range() {
-    let settings = testutils::user_settings();
-    let test_repo = TestRepo::init();
-    let repo = &test_repo.repo;
-
-    let mut tx = repo.start_transaction(&settings);
-    let mut_repo = tx.mut_repo();
-    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
-    let commit1 = graph_builder.initial_commit();
-    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
-    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
-    let commit4 = graph_builder.commit_with_parents(&[&commit1, &commit3]);
-
-    // The range from the root to the root is empty (because the left side of the
-    // range is exclusive)
-    assert_eq!(resolve_commit_ids(mut_repo, "root()..root()"), vec![]);
-
-    // Linear range
-    assert_eq!(
-        resolve_commit_ids(
-            mut_repo,
-            &format!("{}..{}", commit1.id().hex(), commit3.id().hex())
-        ),
-        vec![commit3.id().clone(), commit2.id().clone()]
-    );
 
-    // Empty range (descendant first)
+    // Can stop the traversal at a set of commits, excluding them and their
+    // ancestors
     assert_eq!(
         resolve_commit_ids(
             mut_repo,
-            &format!("{}..{}", commit3.id().hex(), commit1.id().hex())
+            &format!(
+                "ancestors({}, until={})",
+                commit4.id().hex(),
+                commit2.id().hex()
+            )
         ),
-        vec![]
+        vec![commit4.id().clone(), commit3.id().clone()]
     );
-
-    // Range including a merge
     assert_eq!(
         resolve_commit_ids(
             mut_repo,
-            &format!("{}..{}", commit1.id().hex(), commit4.id().hex())
+            &format!("ancestors({}, until=root())", commit3.id().hex())
         ),
         vec![
-            commit4.id().clone(),
             commit3.id().clone(),
-            commit2.id().clone()
+            commit2.id().clone(),
+            commit1.id().clone(),
         ]
     );
-
-    // Range including merge ancestors: commit4-- == root | commit2
+    // The heads are included even if they are in the stop set
     assert_eq!(
         resolve_commit_ids(
             mut_repo,
-            &format!("{}--..{}", commit4.id().hex(), commit3.id().hex())
+            &format!(
+                "ancestors({}, until={})",
+                commit3.id().hex(),
+                commit3.id().hex()
+            )
         ),
         vec![commit3.id().clone()]
     );
-
-    // Sibling commits
+    // Can combine the stop set with a depth limit
     assert_eq!(
         resolve_commit_ids(
             mut_repo,
-            &format!("{}..{}", commit2.id().hex(), commit3.id().hex())
+            &format!(
+                "ancestors({}, 3, until={})",
+                commit4.id().hex(),
+                commit1.id().hex()
+            )
         ),
-        vec![commit3.id().clone()]
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone()
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "ancestors({}, depth=2, until={})",
+                commit4.id().hex(),
+                commit1.id().hex()
+            )
+        ),
+        vec![commit4.id().clone(), commit3.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "ancestors({}, 0, until={})",
+                commit4.id().hex(),
+                commit1.id().hex()
+            )
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_range() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit1, &commit3]);
+
+    // The range from the root to the root is empty (because the left side of the
+    // range is exclusive)
+    assert_eq!(resolve_commit_ids(mut_repo, "root()..root()"), vec![]);
+
+    // Linear range
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("{}..{}", commit1.id().hex(), commit3.id().hex())
+        ),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+
+    // Empty range (descendant first)
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("{}..{}", commit3.id().hex(), commit1.id().hex())
+        ),
+        vec![]
+    );
+
+    // Range including a merge
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("{}..{}", commit1.id().hex(), commit4.id().hex())
+        ),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone()
+        ]
+    );
+
+    // Range including merge ancestors: commit4-- == root | commit2
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("{}--..{}", commit4.id().hex(), commit3.id().hex())
+        ),
+        vec![commit3.id().clone()]
+    );
+
+    // Sibling commits
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("{}..{}", commit2.id().hex(), commit3.id().hex())
+        ),
+        vec![commit3.id().clone()]
     );
 
     // Left operand defaults to root()
@@ -1550,7 +1888,11 @@ fn test_evaluate_expression_connected() {
                 commit2.id().hex()
             )
         ),
-        vec![commit2.id().clone(), commit1.id().clone(), root_commit_id]
+        vec![
+            commit2.id().clone(),
+            commit1.id().clone(),
+            root_commit_id.clone()
+        ]
     );
 
     // Siblings don't get connected
@@ -1589,6 +1931,119 @@ fn test_evaluate_expression_connected() {
             commit2.id().clone(),
         ]
     );
+
+    // Paths leaving the domain aren't followed
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "connected({} | {}, ~{})",
+                commit1.id().hex(),
+                commit5.id().hex(),
+                commit3.id().hex()
+            )
+        ),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+
+    // Commits in the domain but only connected through commits outside of it
+    // aren't included
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "connected({} | {}, ~({} | {}))",
+                commit1.id().hex(),
+                commit5.id().hex(),
+                commit3.id().hex(),
+                commit4.id().hex()
+            )
+        ),
+        vec![commit5.id().clone(), commit1.id().clone()]
+    );
+
+    // Commits of the set outside the domain are excluded
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "connected({} | {}, {}::)",
+                root_commit_id.hex(),
+                commit2.id().hex(),
+                commit1.id().hex()
+            )
+        ),
+        vec![commit2.id().clone()]
+    );
+
+    // The domain doesn't add any commits by itself
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("connected({}, all())", commit2.id().hex())
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "connected(none(), all())"),
+        vec![]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_max_commits() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id().clone();
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+
+    let evaluate = |expression: &Rc<RevsetExpression>, max_commits| {
+        let options = RevsetEvaluationOptions { max_commits };
+        optimize(expression.clone())
+            .resolve_programmatic(mut_repo)
+            .evaluate_with_options(mut_repo, &options)
+            .map(|revset| revset.iter().collect_vec())
+    };
+
+    // The result is unaffected if it fits within the limit
+    let connected = RevsetExpression::all().connected();
+    assert_eq!(
+        evaluate(&connected, Some(4)).unwrap(),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+            root_commit_id.clone(),
+        ]
+    );
+    assert_eq!(evaluate(&connected, None).unwrap().len(), 4);
+
+    // Collecting more commits than the limit fails
+    assert_matches!(
+        evaluate(&connected, Some(3)),
+        Err(RevsetEvaluationError::LimitExceeded(3))
+    );
+    assert_matches!(
+        evaluate(&RevsetExpression::all().heads(), Some(2)),
+        Err(RevsetEvaluationError::LimitExceeded(2))
+    );
+
+    // Lazily evaluated sets aren't bounded
+    assert_eq!(
+        evaluate(&RevsetExpression::all(), Some(1)).unwrap().len(),
+        4
+    );
 }
 
 #[test]
@@ -1869,59 +2324,431 @@ fn test_evaluate_expression_descendants() {
 }
 
 #[test]
-fn test_evaluate_expression_none() {
-    let test_repo = TestRepo::init();
-    let repo = &test_repo.repo;
-
-    // none() is empty (doesn't include the checkout, for example)
-    assert_eq!(resolve_commit_ids(repo.as_ref(), "none()"), vec![]);
-}
-
-#[test]
-fn test_evaluate_expression_all() {
+fn test_evaluate_expression_descendants_first_parent() {
     let settings = testutils::user_settings();
     let test_repo = TestRepo::init();
     let repo = &test_repo.repo;
 
     let mut tx = repo.start_transaction(&settings);
     let mut_repo = tx.mut_repo();
-    let root_commit_id = repo.store().root_commit_id().clone();
-    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
-    let commit1 = graph_builder.initial_commit();
-    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
-    let commit3 = graph_builder.commit_with_parents(&[&commit1]);
-    let commit4 = graph_builder.commit_with_parents(&[&commit2, &commit3]);
 
+    // 6
+    // 5
+    // |\
+    // 3 4
+    // 2 |
+    // |/
+    // 1
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit1.id().clone()])
+        .write()
+        .unwrap();
+    let commit3 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit2.id().clone()])
+        .write()
+        .unwrap();
+    let commit4 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit1.id().clone()])
+        .write()
+        .unwrap();
+    let commit5 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit3.id().clone(), commit4.id().clone()])
+        .write()
+        .unwrap();
+    let commit6 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit5.id().clone()])
+        .write()
+        .unwrap();
+
+    // Through commit4, commit5 is 2 generations away from commit1, but it's 3
+    // generations away along the first parents
     assert_eq!(
-        resolve_commit_ids(mut_repo, "all()"),
+        resolve_commit_ids(mut_repo, &format!("descendants({}, 3)", commit1.id().hex())),
         vec![
+            commit5.id().clone(),
             commit4.id().clone(),
             commit3.id().clone(),
             commit2.id().clone(),
             commit1.id().clone(),
-            root_commit_id,
         ]
     );
-}
-
-#[test]
-fn test_evaluate_expression_visible_heads() {
-    let settings = testutils::user_settings();
-    let test_repo = TestRepo::init();
-    let repo = &test_repo.repo;
-
-    let mut tx = repo.start_transaction(&settings);
-    let mut_repo = tx.mut_repo();
-    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
-    let commit1 = graph_builder.initial_commit();
-    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
-    let commit3 = graph_builder.commit_with_parents(&[&commit1]);
-
     assert_eq!(
-        resolve_commit_ids(mut_repo, "visible_heads()"),
-        vec![commit3.id().clone(), commit2.id().clone()]
+        resolve_commit_ids(
+            mut_repo,
+            &format!("descendants({}, 3, first_parent=true)", commit1.id().hex())
+        ),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("descendants({}, 4, true)", commit1.id().hex())
+        ),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
     );
-}
+
+    // Commits merging in the roots through a second parent aren't included
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("descendants({}, first_parent=true)", commit4.id().hex())
+        ),
+        vec![commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("descendants({}, first_parent=true)", commit2.id().hex())
+        ),
+        vec![
+            commit6.id().clone(),
+            commit5.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+        ]
+    );
+
+    // The default is to follow all parents
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("descendants({}, first_parent=false)", commit4.id().hex())
+        ),
+        vec![
+            commit6.id().clone(),
+            commit5.id().clone(),
+            commit4.id().clone(),
+        ]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_descendants_until() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    // 5
+    // 4 (branch)
+    // 3 6
+    // 2/
+    // 1
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit3]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit4]);
+    let commit6 = graph_builder.commit_with_parents(&[&commit2]);
+
+    // Without branches, all descendants are included
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("descendants_until({})", commit2.id().hex())
+        ),
+        vec![
+            commit6.id().clone(),
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+        ]
+    );
+
+    // The traversal stops before the intermediate branch
+    mut_repo.set_local_branch_target("branch", RefTarget::normal(commit4.id().clone()));
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("descendants_until({})", commit2.id().hex())
+        ),
+        vec![
+            commit6.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+        ]
+    );
+
+    // The roots are included even if they are in the stop set
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "descendants_until(branch)"),
+        vec![commit5.id().clone(), commit4.id().clone()]
+    );
+
+    // Explicit stop set
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "descendants_until({}, {})",
+                commit1.id().hex(),
+                commit3.id().hex()
+            )
+        ),
+        vec![
+            commit6.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_strict_ancestors_descendants() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let root_commit_id = repo.store().root_commit_id().clone();
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit1.id().clone()])
+        .write()
+        .unwrap();
+    let commit3 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit2.id().clone()])
+        .write()
+        .unwrap();
+    let commit4 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit1.id().clone()])
+        .write()
+        .unwrap();
+    let commit5 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit3.id().clone(), commit4.id().clone()])
+        .write()
+        .unwrap();
+
+    // The input commit is excluded
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("strict_descendants({})", commit2.id().hex())
+        ),
+        vec![commit5.id().clone(), commit3.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("strict_ancestors({})", commit3.id().hex())
+        ),
+        vec![
+            commit2.id().clone(),
+            commit1.id().clone(),
+            root_commit_id.clone(),
+        ]
+    );
+
+    // All input commits are excluded, even if they are descendants or
+    // ancestors of other input commits
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "strict_descendants({} | {})",
+                commit1.id().hex(),
+                commit3.id().hex()
+            )
+        ),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit2.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "strict_ancestors({} | {})",
+                commit5.id().hex(),
+                commit2.id().hex()
+            )
+        ),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit1.id().clone(),
+            root_commit_id.clone(),
+        ]
+    );
+
+    // Unlike the ancestors of the parents, the input commits that are
+    // ancestors of other input commits are excluded
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("::({} | {})-", commit5.id().hex(), commit2.id().hex())
+        ),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+            root_commit_id.clone(),
+        ]
+    );
+    // Likewise for the descendants of the children
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("({} | {})+::", commit1.id().hex(), commit3.id().hex())
+        ),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "strict_ancestors(root())"),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "strict_descendants(none())"),
+        vec![]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_none() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // none() is empty (doesn't include the checkout, for example)
+    assert_eq!(resolve_commit_ids(repo.as_ref(), "none()"), vec![]);
+}
+
+#[test]
+fn test_evaluate_expression_all() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let root_commit_id = repo.store().root_commit_id().clone();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit2, &commit3]);
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "all()"),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+            root_commit_id,
+        ]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_visible_heads() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit1]);
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "visible_heads()"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_visible_heads_within() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit3]);
+    mut_repo.remove_head(commit5.id());
+    mut_repo.add_head(&commit3).unwrap();
+
+    // Heads of the domain, not of the repo
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "visible_heads({}|{})",
+                commit1.id().hex(),
+                commit2.id().hex()
+            )
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "visible_heads({}|{})",
+                commit2.id().hex(),
+                commit3.id().hex()
+            )
+        ),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+    // Only some of the repo heads are in the domain
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("visible_heads(~{})", commit4.id().hex())),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "visible_heads(all())"),
+        resolve_commit_ids(mut_repo, "visible_heads()")
+    );
+    // Hidden commits in the domain are ignored
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "visible_heads({}|{})",
+                commit1.id().hex(),
+                commit5.id().hex()
+            )
+        ),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "visible_heads(none())"),
+        vec![]
+    );
+}
 
 #[test]
 fn test_evaluate_expression_git_refs() {
@@ -1980,6 +2807,38 @@ fn test_evaluate_expression_git_refs() {
             commit2.id().clone()
         ]
     );
+
+    // Can get git refs with matching names
+    mut_repo.set_git_ref_target(
+        "refs/heads/branch1",
+        RefTarget::normal(commit1.id().clone()),
+    );
+    mut_repo.set_git_ref_target("refs/tags/v1", RefTarget::normal(commit2.id().clone()));
+    mut_repo.set_git_ref_target("refs/tags/tag1", RefTarget::absent());
+    mut_repo.set_git_ref_target(
+        "refs/remotes/origin/branch1",
+        RefTarget::normal(commit3.id().clone()),
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(glob:'refs/tags/*')"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(glob:'refs/tags/v*')"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(branch1)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(exact:refs/heads/branch1)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(glob:'refs/tags/w*')"),
+        vec![]
+    );
 }
 
 #[test]
@@ -1997,13 +2856,154 @@ fn test_evaluate_expression_git_head() {
     assert_eq!(resolve_commit_ids(mut_repo, "git_head()"), vec![]);
     mut_repo.set_git_head_target(RefTarget::normal(commit1.id().clone()));
     assert_eq!(
-        resolve_commit_ids(mut_repo, "git_head()"),
+        resolve_commit_ids(mut_repo, "git_head()"),
+        vec![commit1.id().clone()]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_branches() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let commit1 = write_random_commit(mut_repo, &settings);
+    let commit2 = write_random_commit(mut_repo, &settings);
+    let commit3 = write_random_commit(mut_repo, &settings);
+    let commit4 = write_random_commit(mut_repo, &settings);
+
+    // Can get branches when there are none
+    assert_eq!(resolve_commit_ids(mut_repo, "branches()"), vec![]);
+    // Can get a few branches
+    mut_repo.set_local_branch_target("branch1", RefTarget::normal(commit1.id().clone()));
+    mut_repo.set_local_branch_target("branch2", RefTarget::normal(commit2.id().clone()));
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches()"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // Can get branches with matching names
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches(branch1)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches(branch)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches(exact:branch1)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, r#"branches(glob:"Branch?")"#),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, r#"branches(glob-i:"Branch?")"#),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches(regex:'ranch')"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches(regex:'^[Bb]ranch1$')"),
+        vec![commit1.id().clone()]
+    );
+    // Can silently resolve to an empty set if there's no matches
+    assert_eq!(resolve_commit_ids(mut_repo, "branches(branch3)"), vec![]);
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches(exact:ranch1)"),
+        vec![]
+    );
+    // Two branches pointing to the same commit does not result in a duplicate in
+    // the revset
+    mut_repo.set_local_branch_target("branch3", RefTarget::normal(commit2.id().clone()));
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches()"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // Can get branches when there are conflicted refs
+    mut_repo.set_local_branch_target(
+        "branch1",
+        RefTarget::from_legacy_form(
+            [commit1.id().clone()],
+            [commit2.id().clone(), commit3.id().clone()],
+        ),
+    );
+    mut_repo.set_local_branch_target(
+        "branch2",
+        RefTarget::from_legacy_form(
+            [commit2.id().clone()],
+            [commit3.id().clone(), commit4.id().clone()],
+        ),
+    );
+    mut_repo.set_local_branch_target("branch3", RefTarget::absent());
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches()"),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone()
+        ]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_branches_behind() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit1]);
+
+    // Nothing is behind when there are no branches
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("branches_behind({})", commit3.id().hex())
+        ),
+        vec![]
+    );
+
+    mut_repo.set_local_branch_target("merged", RefTarget::normal(commit1.id().clone()));
+    mut_repo.set_local_branch_target("main", RefTarget::normal(commit3.id().clone()));
+    mut_repo.set_local_branch_target("unmerged", RefTarget::normal(commit4.id().clone()));
+    // Branches pointing to ancestors of the target, including the target
+    // itself, are behind
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches_behind(main)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("branches_behind({})", commit2.id().hex())
+        ),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches_behind(unmerged)"),
+        vec![commit4.id().clone(), commit1.id().clone()]
+    );
+    // Composes with other revsets
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "branches_behind(main) ~ main"),
         vec![commit1.id().clone()]
     );
 }
 
 #[test]
-fn test_evaluate_expression_branches() {
+fn test_evaluate_expression_tags() {
     let settings = testutils::user_settings();
     let test_repo = TestRepo::init();
     let repo = &test_repo.repo;
@@ -2016,81 +3016,69 @@ fn test_evaluate_expression_branches() {
     let commit3 = write_random_commit(mut_repo, &settings);
     let commit4 = write_random_commit(mut_repo, &settings);
 
-    // Can get branches when there are none
-    assert_eq!(resolve_commit_ids(mut_repo, "branches()"), vec![]);
-    // Can get a few branches
-    mut_repo.set_local_branch_target("branch1", RefTarget::normal(commit1.id().clone()));
-    mut_repo.set_local_branch_target("branch2", RefTarget::normal(commit2.id().clone()));
+    // Can get tags when there are none
+    assert_eq!(resolve_commit_ids(mut_repo, "tags()"), vec![]);
+    // Can get a few tags
+    mut_repo.set_tag_target("v1.0", RefTarget::normal(commit1.id().clone()));
+    mut_repo.set_tag_target("v2.0", RefTarget::normal(commit2.id().clone()));
     assert_eq!(
-        resolve_commit_ids(mut_repo, "branches()"),
+        resolve_commit_ids(mut_repo, "tags()"),
         vec![commit2.id().clone(), commit1.id().clone()]
     );
-    // Can get branches with matching names
+    // Can get tags with matching names
     assert_eq!(
-        resolve_commit_ids(mut_repo, "branches(branch1)"),
+        resolve_commit_ids(mut_repo, "tags(v1)"),
         vec![commit1.id().clone()]
     );
     assert_eq!(
-        resolve_commit_ids(mut_repo, "branches(branch)"),
-        vec![commit2.id().clone(), commit1.id().clone()]
+        resolve_commit_ids(mut_repo, "tags(exact:'v2.0')"),
+        vec![commit2.id().clone()]
     );
     assert_eq!(
-        resolve_commit_ids(mut_repo, "branches(exact:branch1)"),
+        resolve_commit_ids(mut_repo, "tags(glob:'v1.*')"),
         vec![commit1.id().clone()]
     );
     assert_eq!(
-        resolve_commit_ids(mut_repo, r#"branches(glob:"Branch?")"#),
-        vec![]
-    );
-    assert_eq!(
-        resolve_commit_ids(mut_repo, r#"branches(glob-i:"Branch?")"#),
-        vec![commit2.id().clone(), commit1.id().clone()]
-    );
-    assert_eq!(
-        resolve_commit_ids(mut_repo, "branches(regex:'ranch')"),
+        resolve_commit_ids(mut_repo, "tags(regex:'^v[0-9]')"),
         vec![commit2.id().clone(), commit1.id().clone()]
     );
-    assert_eq!(
-        resolve_commit_ids(mut_repo, "branches(regex:'^[Bb]ranch1$')"),
-        vec![commit1.id().clone()]
-    );
     // Can silently resolve to an empty set if there's no matches
-    assert_eq!(resolve_commit_ids(mut_repo, "branches(branch3)"), vec![]);
-    assert_eq!(
-        resolve_commit_ids(mut_repo, "branches(exact:ranch1)"),
-        vec![]
-    );
-    // Two branches pointing to the same commit does not result in a duplicate in
+    assert_eq!(resolve_commit_ids(mut_repo, "tags(v3)"), vec![]);
+    // Two tags pointing to the same commit does not result in a duplicate in
     // the revset
-    mut_repo.set_local_branch_target("branch3", RefTarget::normal(commit2.id().clone()));
+    mut_repo.set_tag_target("v2.0-rc", RefTarget::normal(commit2.id().clone()));
     assert_eq!(
-        resolve_commit_ids(mut_repo, "branches()"),
+        resolve_commit_ids(mut_repo, "tags()"),
         vec![commit2.id().clone(), commit1.id().clone()]
     );
-    // Can get branches when there are conflicted refs
-    mut_repo.set_local_branch_target(
-        "branch1",
+    // Can get tags when there are conflicted refs
+    mut_repo.set_tag_target(
+        "v1.0",
         RefTarget::from_legacy_form(
             [commit1.id().clone()],
             [commit2.id().clone(), commit3.id().clone()],
         ),
     );
-    mut_repo.set_local_branch_target(
-        "branch2",
+    mut_repo.set_tag_target(
+        "v2.0",
         RefTarget::from_legacy_form(
             [commit2.id().clone()],
             [commit3.id().clone(), commit4.id().clone()],
         ),
     );
-    mut_repo.set_local_branch_target("branch3", RefTarget::absent());
+    mut_repo.set_tag_target("v2.0-rc", RefTarget::absent());
     assert_eq!(
-        resolve_commit_ids(mut_repo, "branches()"),
+        resolve_commit_ids(mut_repo, "tags()"),
         vec![
             commit4.id().clone(),
             commit3.id().clone(),
             commit2.id().clone()
         ]
     );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "tags(exact:'v1.0')"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
 }
 
 #[test]
@@ -2355,6 +3343,213 @@ fn test_evaluate_expression_latest() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_latest_by_author_date() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    // Author timestamps are in the reverse order of committer timestamps, as if
+    // the older commits had been rebased more recently.
+    let mut write_commit_with_timestamps = |author_sec: i64, committer_sec: i64| {
+        let builder = create_random_commit(mut_repo, &settings);
+        let mut author = builder.author().clone();
+        author.timestamp.timestamp = MillisSinceEpoch(author_sec * 1000);
+        let mut committer = builder.committer().clone();
+        committer.timestamp.timestamp = MillisSinceEpoch(committer_sec * 1000);
+        builder
+            .set_author(author)
+            .set_committer(committer)
+            .write()
+            .unwrap()
+    };
+    let commit1_a1 = write_commit_with_timestamps(1, 4);
+    let commit2_a2 = write_commit_with_timestamps(2, 3);
+    let commit3_a2 = write_commit_with_timestamps(2, 2);
+    let commit4_a3 = write_commit_with_timestamps(3, 1);
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(all(), 1, committer)"),
+        vec![commit1_a1.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(all(), 1, author)"),
+        vec![commit4_a3.id().clone()],
+    );
+
+    // Tie-breaking: pick the later entry in position
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(all(), 2, author)"),
+        vec![commit4_a3.id().clone(), commit3_a2.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(~root(), 4, author_date)"),
+        vec![
+            commit4_a3.id().clone(),
+            commit3_a2.id().clone(),
+            commit2_a2.id().clone(),
+            commit1_a1.id().clone(),
+        ],
+    );
+
+    // Should not panic with count = 0 or empty set
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(all(), 0, author)"),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(none(), 1, author)"),
+        vec![]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_distinct_change_ids() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let mut write_commit = |change_id: Option<&ChangeId>, committer_sec: i64| {
+        let mut builder = create_random_commit(mut_repo, &settings);
+        if let Some(change_id) = change_id {
+            builder = builder.set_change_id(change_id.clone());
+        }
+        let mut committer = builder.committer().clone();
+        committer.timestamp.timestamp = MillisSinceEpoch(committer_sec * 1000);
+        builder.set_committer(committer).write().unwrap()
+    };
+    let commit1 = write_commit(None, 1);
+    // commit2 and commit3 are divergent, and commit3 is the latest
+    let commit2 = write_commit(None, 2);
+    let commit3 = write_commit(Some(commit2.change_id()), 3);
+    // commit4 and commit5 are divergent with the same timestamp
+    let commit4 = write_commit(None, 4);
+    let commit5 = write_commit(Some(commit4.change_id()), 4);
+    // commit6 is divergent with commit1, and is the latest
+    let commit6 = write_commit(Some(commit1.change_id()), 5);
+
+    // Tie-breaking: pick the later entry in position
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "distinct_change_ids(all() ~ root())"),
+        vec![
+            commit6.id().clone(),
+            commit5.id().clone(),
+            commit3.id().clone(),
+        ],
+    );
+
+    // Only the commits in the set are considered
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "distinct_change_ids({} | {})",
+                commit1.id().hex(),
+                commit2.id().hex()
+            ),
+        ),
+        vec![commit2.id().clone(), commit1.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "distinct_change_ids(none())"),
+        vec![]
+    );
+
+    // Commits without divergent commits are kept
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("distinct_change_ids({})", commit4.id().hex())
+        ),
+        vec![commit4.id().clone()],
+    );
+}
+
+#[test]
+fn test_evaluate_expression_sorted() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let mut write_commit_with_timestamps = |author_sec: i64, committer_sec: i64| {
+        let builder = create_random_commit(mut_repo, &settings);
+        let mut author = builder.author().clone();
+        author.timestamp.timestamp = MillisSinceEpoch(author_sec * 1000);
+        let mut committer = builder.committer().clone();
+        committer.timestamp.timestamp = MillisSinceEpoch(committer_sec * 1000);
+        builder
+            .set_author(author)
+            .set_committer(committer)
+            .write()
+            .unwrap()
+    };
+    let commit1_a1_c3 = write_commit_with_timestamps(1, 3);
+    let commit2_a4_c1 = write_commit_with_timestamps(4, 1);
+    let commit3_a2_c4 = write_commit_with_timestamps(2, 4);
+    let commit4_a2_c2 = write_commit_with_timestamps(2, 2);
+
+    // Sorted by committer timestamp by default, newest first
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sorted(~root())"),
+        vec![
+            commit3_a2_c4.id().clone(),
+            commit1_a1_c3.id().clone(),
+            commit4_a2_c2.id().clone(),
+            commit2_a4_c1.id().clone(),
+        ],
+    );
+
+    // Tie-breaking: the later entry in position comes first
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sorted(~root(), author_date)"),
+        vec![
+            commit2_a4_c1.id().clone(),
+            commit4_a2_c2.id().clone(),
+            commit3_a2_c4.id().clone(),
+            commit1_a1_c3.id().clone(),
+        ],
+    );
+
+    // The order can't be preserved when nested, so it's rejected even if the
+    // expression was built without going through the parser
+    let expression = RevsetExpression::root()
+        .negated()
+        .sorted_by(SortKey::default())
+        .intersection(&RevsetExpression::visible_heads());
+    let resolved = expression
+        .resolve_user_expression(mut_repo, &FailingSymbolResolver)
+        .unwrap();
+    assert_matches!(
+        resolved.evaluate(mut_repo).map(|_| ()),
+        Err(RevsetEvaluationError::Other(message))
+            if message == "sorted() can only be used as the outermost function"
+    );
+
+    // Sorted by commit ID regardless of the index positions
+    let mut expected = vec![
+        commit1_a1_c3.id().clone(),
+        commit2_a4_c1.id().clone(),
+        commit3_a2_c4.id().clone(),
+        commit4_a2_c2.id().clone(),
+    ];
+    expected.sort();
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sorted(heads(all()), commit_id)"),
+        expected
+    );
+
+    assert_eq!(resolve_commit_ids(mut_repo, "sorted(none())"), vec![]);
+}
+
 #[test]
 fn test_evaluate_expression_merges() {
     let settings = testutils::user_settings();
@@ -2380,6 +3575,51 @@ fn test_evaluate_expression_merges() {
         resolve_commit_ids(mut_repo, &format!("::{} & merges()", commit5.id().hex())),
         vec![commit5.id().clone()]
     );
+    // Finds merges with the exact number of parents
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "merges(2)"),
+        vec![commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "merges(3)"),
+        vec![commit5.id().clone()]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "merges(4)"), vec![]);
+    // Finds merges with at least the given number of parents
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "merges(atleast:2)"),
+        vec![commit5.id().clone(), commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "merges(atleast:3)"),
+        vec![commit5.id().clone()]
+    );
+
+    // Finds parents of the merges among the given commits
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("merge_parents({})", commit5.id().hex())),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "merge_parents({}|{})",
+                commit4.id().hex(),
+                commit3.id().hex()
+            )
+        ),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // Non-merge commits have no merge parents
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("merge_parents({})", commit1.id().hex())),
+        vec![]
+    );
 }
 
 #[test]
@@ -2509,6 +3749,79 @@ fn test_evaluate_expression_author() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_author_and_committer_fields() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let timestamp = Timestamp {
+        timestamp: MillisSinceEpoch(0),
+        tz_offset: 0,
+    };
+    // The name of one commit's author appears in the other's email, and the
+    // committers are swapped so they can't be mistaken for the authors.
+    let signature1 = Signature {
+        name: "Example User".to_string(),
+        email: "user@foo.org".to_string(),
+        timestamp: timestamp.clone(),
+    };
+    let signature2 = Signature {
+        name: "Foo User".to_string(),
+        email: "user@example.com".to_string(),
+        timestamp,
+    };
+    let commit1 = create_random_commit(mut_repo, &settings)
+        .set_author(signature1.clone())
+        .set_committer(signature2.clone())
+        .write()
+        .unwrap();
+    let commit2 = create_random_commit(mut_repo, &settings)
+        .set_parents(vec![commit1.id().clone()])
+        .set_author(signature2)
+        .set_committer(signature1)
+        .write()
+        .unwrap();
+
+    // The combined predicates match either field
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author(substring-i:example)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "committer(substring-i:example)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // The scoped predicates match only their own field
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_name(substring-i:example)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_email(substring-i:example)"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_email(glob:'*@example.com')"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "committer_name(substring-i:example)"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "committer_email(substring-i:example)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_name(exact:'user@foo.org')"),
+        vec![]
+    );
+}
+
 fn parse_timestamp(s: &str) -> Timestamp {
     Timestamp::from_datetime(s.parse::<DateTime<chrono::FixedOffset>>().unwrap())
 }
@@ -2571,12 +3884,47 @@ fn test_evaluate_expression_author_date() {
 
     // Can find multiple matches
     assert_eq!(
-        resolve_commit_ids(mut_repo, "author_date(after:'2023-03-25 12:00')"),
-        vec![commit3.id().clone(), commit2.id().clone()]
+        resolve_commit_ids(mut_repo, "author_date(after:'2023-03-25 12:00')"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date(before:'2023-03-25 12:00')"),
+        vec![commit1.id().clone(), root_commit.id().clone()]
+    );
+
+    // Range includes the start like `after:`, but not the end like `before:`
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            "author_date('2023-03-25 12:30'..'2023-03-25 13:30')"
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            "author_date(after:'2023-03-25 12:30') & author_date(before:'2023-03-25 13:30')"
+        ),
+        vec![commit2.id().clone()]
     );
     assert_eq!(
-        resolve_commit_ids(mut_repo, "author_date(before:'2023-03-25 12:00')"),
-        vec![commit1.id().clone(), root_commit.id().clone()]
+        resolve_commit_ids(
+            mut_repo,
+            "author_date('2023-03-25 11:30'..'2023-03-25 13:31')"
+        ),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    // Empty range
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            "author_date('2023-03-25 12:30'..'2023-03-25 12:30')"
+        ),
+        vec![]
     );
 }
 
@@ -2645,6 +3993,13 @@ fn test_evaluate_expression_committer_date() {
         resolve_commit_ids(mut_repo, "committer_date(before:'2023-03-25 12:00')"),
         vec![commit1.id().clone(), root_commit.id().clone()]
     );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            "committer_date('2023-03-25 11:30'..'2023-03-25 12:30')"
+        ),
+        vec![commit1.id().clone()]
+    );
 }
 
 #[test]
@@ -2716,6 +4071,62 @@ fn test_evaluate_expression_mine() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_mine_multiple_emails() {
+    let config = testutils::base_config()
+        .set_override(
+            "user.emails",
+            vec!["work@example.com", "personal@example.com"],
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    let settings = UserSettings::from_config(config);
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let timestamp = Timestamp {
+        timestamp: MillisSinceEpoch(0),
+        tz_offset: 0,
+    };
+    let mut create_commit_with_email = |email: &str| {
+        create_random_commit(mut_repo, &settings)
+            .set_author(Signature {
+                name: "name".to_string(),
+                email: email.to_string(),
+                timestamp: timestamp.clone(),
+            })
+            .write()
+            .unwrap()
+    };
+    let commit1 = create_commit_with_email("work@example.com");
+    // Test that matches are case‐insensitive
+    let commit2 = create_commit_with_email("Personal@Example.com");
+    // `user.email` is ignored if `user.emails` is set
+    let _commit3 = create_commit_with_email(&settings.user_email());
+
+    let aliases_map = RevsetAliasesMap::default();
+    let extensions = RevsetExtensions::default();
+    let context = RevsetParseContext::new(
+        &aliases_map,
+        settings.user_emails(),
+        chrono::Utc::now().fixed_offset().into(),
+        None,
+        &extensions,
+        None,
+    );
+    let expression = optimize(parse("mine()", &context).unwrap());
+    let symbol_resolver = DefaultSymbolResolver::new(mut_repo, extensions.symbol_resolvers());
+    let expression = expression
+        .resolve_user_expression(mut_repo, &symbol_resolver)
+        .unwrap();
+    let commit_ids: Vec<_> = expression.evaluate(mut_repo).unwrap().iter().collect();
+    assert_eq!(commit_ids, vec![commit2.id().clone(), commit1.id().clone()]);
+}
+
 #[test]
 fn test_evaluate_expression_committer() {
     let settings = testutils::user_settings();
@@ -3008,6 +4419,26 @@ fn test_evaluate_expression_difference() {
         ),
         vec![commit4.id().clone()]
     );
+
+    // only_in() is the same as the difference from the ancestors
+    for (x, y) in [
+        (format!("::{}", commit4.id().hex()), commit5.id().hex()),
+        (format!("::{}", commit5.id().hex()), commit4.id().hex()),
+        (commit4.id().hex(), commit2.id().hex()),
+        (commit2.id().hex(), commit4.id().hex()),
+    ] {
+        assert_eq!(
+            resolve_commit_ids(mut_repo, &format!("only_in({x}, {y})")),
+            resolve_commit_ids(mut_repo, &format!("{x} ~ ::{y}")),
+        );
+    }
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("only_in(::{}, {})", commit4.id().hex(), commit5.id().hex())
+        ),
+        vec![commit4.id().clone(), commit3.id().clone()]
+    );
 }
 
 #[test]
@@ -3181,11 +4612,278 @@ fn test_evaluate_expression_file() {
         ),
         vec![commit2.id().clone()]
     );
-
-    // empty() revset, which is identical to ~file(".")
+
+    // empty() revset, which is identical to ~file(".")
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("{}:: & empty()", commit1.id().hex())),
+        vec![commit4.id().clone()]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_changed_since() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let file_a = RepoPath::from_internal_string("a");
+    let file_b = RepoPath::from_internal_string("b");
+    let mut write_commit = |parent: &CommitId, a: &str, b: &str| {
+        let tree = create_tree(repo, &[(file_a, a), (file_b, b)]);
+        mut_repo
+            .new_commit(&settings, vec![parent.clone()], tree.id())
+            .write()
+            .unwrap()
+    };
+    // f3: changes a
+    // f2: changes b
+    // f1: changes a
+    // |
+    // | main: changes a
+    // |/
+    // base
+    let base = write_commit(repo.store().root_commit_id(), "base", "base");
+    let main = write_commit(base.id(), "main", "base");
+    let f1 = write_commit(base.id(), "f1", "base");
+    let f2 = write_commit(f1.id(), "f1", "f2");
+    let f3 = write_commit(f2.id(), "f3", "f2");
+
+    // Commits changing "a" overlap with main, but "b" doesn't differ from main
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "{}..{} & changed_since({})",
+                base.id().hex(),
+                f3.id().hex(),
+                main.id().hex()
+            )
+        ),
+        vec![f3.id().clone(), f1.id().clone()]
+    );
+
+    // Only f3 changes a file its parent already changed since base
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "{}..{} & changed_since({})",
+                base.id().hex(),
+                f3.id().hex(),
+                base.id().hex()
+            )
+        ),
+        vec![f3.id().clone()]
+    );
+
+    // The reference must be a single commit
+    assert_matches!(
+        RevsetExpression::changed_since(&RevsetExpression::none())
+            .evaluate_programmatic(mut_repo)
+            .map(|revset| revset.iter().count()),
+        Err(RevsetEvaluationError::Other(_))
+    );
+}
+
+#[test]
+fn test_evaluate_expression_file_change_kind() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let added_modified_removed = RepoPath::from_internal_string("added_modified_removed");
+    let side = RepoPath::from_internal_string("side");
+    let tree1 = create_tree(repo, &[(added_modified_removed, "1")]);
+    let tree2 = create_tree(repo, &[(added_modified_removed, "2")]);
+    let tree3 = create_tree(repo, &[]);
+    let tree4 = create_tree(repo, &[(side, "1")]);
+    let commit1 = mut_repo
+        .new_commit(
+            &settings,
+            vec![repo.store().root_commit_id().clone()],
+            tree1.id(),
+        )
+        .write()
+        .unwrap();
+    let commit2 = mut_repo
+        .new_commit(&settings, vec![commit1.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+    let commit3 = mut_repo
+        .new_commit(&settings, vec![commit2.id().clone()], tree3.id())
+        .write()
+        .unwrap();
+    let commit4 = mut_repo
+        .new_commit(&settings, vec![commit3.id().clone()], tree4.id())
+        .write()
+        .unwrap();
+    // The merge takes "side" from one of the parents, which isn't an addition
+    let commit5 = mut_repo
+        .new_commit(
+            &settings,
+            vec![commit3.id().clone(), commit4.id().clone()],
+            tree4.id(),
+        )
+        .write()
+        .unwrap();
+
+    let resolve = |revset_str: &str| -> Vec<CommitId> {
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            revset_str,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root()),
+        )
+    };
+
+    assert_eq!(
+        resolve(r#"file("added_modified_removed", kind=added)"#),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve(r#"file("added_modified_removed", kind=modified)"#),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve(r#"file("added_modified_removed", kind=removed)"#),
+        vec![commit3.id().clone()]
+    );
+    assert_eq!(
+        resolve(r#"file(".", kind=added)"#),
+        vec![commit4.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(resolve(r#"file("side", kind=removed)"#), vec![]);
+    assert_eq!(
+        resolve(&format!(
+            r#"{}:: & file("side", kind=added)"#,
+            commit4.id().hex()
+        )),
+        vec![commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve(&format!(r#"{}:: & file(".")"#, commit5.id().hex())),
+        vec![]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_empty_wrt_parent() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let file_path = RepoPath::from_internal_string("file");
+    let tree1 = create_tree(repo, &[(file_path, "1")]);
+    let tree2 = create_tree(repo, &[(file_path, "2")]);
+    let root_commit_id = repo.store().root_commit_id().clone();
+    let commit1 = mut_repo
+        .new_commit(&settings, vec![root_commit_id.clone()], tree1.id())
+        .write()
+        .unwrap();
+    let commit2 = mut_repo
+        .new_commit(&settings, vec![root_commit_id.clone()], tree2.id())
+        .write()
+        .unwrap();
+    // Merge commits taking the tree of one of the parents as is
+    let commit3 = mut_repo
+        .new_commit(
+            &settings,
+            vec![commit1.id().clone(), commit2.id().clone()],
+            tree2.id(),
+        )
+        .write()
+        .unwrap();
+    let commit4 = mut_repo
+        .new_commit(
+            &settings,
+            vec![commit1.id().clone(), commit2.id().clone()],
+            tree1.id(),
+        )
+        .write()
+        .unwrap();
+    let commit5 = mut_repo
+        .new_commit(&settings, vec![commit1.id().clone()], tree1.id())
+        .write()
+        .unwrap();
+
+    // The merge commits aren't empty relative to the merge of their parents
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "empty() ~ root()"),
+        vec![commit5.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "empty(parent=1)"),
+        vec![commit5.id().clone(), commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "empty(parent=2)"),
+        vec![commit3.id().clone()]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "empty(3)"), vec![]);
+}
+
+#[test]
+fn test_evaluate_expression_file_negation() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let lock_path = RepoPath::from_internal_string("Cargo.lock");
+    let src_path = RepoPath::from_internal_string("src/main.rs");
+    let tree1 = create_tree(repo, &[(lock_path, "1")]);
+    let tree2 = create_tree(repo, &[(lock_path, "1"), (src_path, "1")]);
+    let commit1 = mut_repo
+        .new_commit(
+            &settings,
+            vec![repo.store().root_commit_id().clone()],
+            tree1.id(),
+        )
+        .write()
+        .unwrap();
+    let commit2 = mut_repo
+        .new_commit(&settings, vec![commit1.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+
+    let resolve = |revset_str: &str| -> Vec<CommitId> {
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            revset_str,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root()),
+        )
+    };
+
+    // Negated fileset matches the commits touching any other file
+    assert_eq!(
+        resolve(r#"file(~glob:"*.lock")"#),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve(r#"file(~"Cargo.lock")"#),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(resolve(r#"file(~(glob:"*.lock" | src))"#), vec![]);
+    assert_eq!(
+        resolve(r#"file(glob:"*.lock" | src)"#),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // A bare string is a path, not a glob pattern
     assert_eq!(
-        resolve_commit_ids(mut_repo, &format!("{}:: & empty()", commit1.id().hex())),
-        vec![commit4.id().clone()]
+        resolve(r#"file(~"*.lock")"#),
+        vec![commit2.id().clone(), commit1.id().clone()]
     );
 }
 
@@ -3335,6 +5033,69 @@ fn test_evaluate_expression_diff_contains() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_diff_contains_max_size() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let small_path = RepoPath::from_internal_string("small");
+    let large_path = RepoPath::from_internal_string("large");
+    let large_content = format!("TODO\n{}", "x".repeat(100));
+    let tree1 = create_tree(repo, &[(small_path, "TODO\n")]);
+    let tree2 = create_tree(
+        repo,
+        &[(small_path, "TODO\n"), (large_path, &large_content)],
+    );
+    let commit1 = mut_repo
+        .new_commit(
+            &settings,
+            vec![repo.store().root_commit_id().clone()],
+            tree1.id(),
+        )
+        .write()
+        .unwrap();
+    let commit2 = mut_repo
+        .new_commit(&settings, vec![commit1.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+
+    let query = |revset_str: &str| {
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            revset_str,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root()),
+        )
+    };
+
+    // Unlimited by default
+    assert_eq!(
+        query("diff_contains('TODO')"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // Match in the oversized file should be skipped
+    assert_eq!(
+        query("diff_contains('TODO', max_size=100)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        query("diff_contains('TODO', all(), 100)"),
+        vec![commit1.id().clone()]
+    );
+    // The limit is inclusive
+    assert_eq!(
+        query(&format!(
+            "diff_contains('TODO', max_size={})",
+            large_content.len()
+        )),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_file_merged_parents() {
     let settings = testutils::user_settings();
@@ -3409,6 +5170,102 @@ fn test_evaluate_expression_file_merged_parents() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_changed_files() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let file_path1 = RepoPath::from_internal_string("file1");
+    let file_path2 = RepoPath::from_internal_string("file2");
+    let file_path3 = RepoPath::from_internal_string("dir/file3");
+    let file_path4 = RepoPath::from_internal_string("dir/file4");
+    let tree1 = create_tree(
+        repo,
+        &[(file_path1, "1"), (file_path2, "1"), (file_path3, "1")],
+    );
+    let tree2 = create_tree(
+        repo,
+        &[(file_path1, "2"), (file_path2, "1"), (file_path3, "1")],
+    );
+    let tree3 = create_tree(
+        repo,
+        &[
+            (file_path1, "1"),
+            (file_path2, "3"),
+            (file_path3, "1"),
+            (file_path4, "3"),
+        ],
+    );
+    // Merge of tree2 and tree3
+    let tree5 = create_tree(
+        repo,
+        &[
+            (file_path1, "2"),
+            (file_path2, "3"),
+            (file_path3, "1"),
+            (file_path4, "3"),
+        ],
+    );
+    // Merge of tree2 and tree3, plus a change to file3
+    let tree4 = create_tree(
+        repo,
+        &[
+            (file_path1, "2"),
+            (file_path2, "3"),
+            (file_path3, "4"),
+            (file_path4, "3"),
+        ],
+    );
+
+    let mut create_commit = |parent_ids, tree_id| {
+        mut_repo
+            .new_commit(&settings, parent_ids, tree_id)
+            .write()
+            .unwrap()
+    };
+    let commit1 = create_commit(vec![repo.store().root_commit_id().clone()], tree1.id());
+    let commit2 = create_commit(vec![commit1.id().clone()], tree2.id());
+    let commit3 = create_commit(vec![commit1.id().clone()], tree3.id());
+    let commit4 = create_commit(vec![commit2.id().clone(), commit3.id().clone()], tree4.id());
+    let commit5 = create_commit(vec![commit2.id().clone(), commit3.id().clone()], tree5.id());
+    let root_commit_id = repo.store().root_commit_id().clone();
+
+    // Merges are compared against the merge of their parents
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "changed_files(0)"),
+        vec![commit5.id().clone(), root_commit_id.clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "changed_files(1)"),
+        vec![commit4.id().clone(), commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "changed_files(exact:3)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "changed_files(atleast:2)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "changed_files(atmost:1)"),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit2.id().clone(),
+            root_commit_id,
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "changed_files(atleast:4)"),
+        vec![]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_conflict() {
     let settings = testutils::user_settings();
@@ -3444,6 +5301,150 @@ fn test_evaluate_expression_conflict() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_conflict_in_files() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    // Create a few trees, including one with a conflict in `dir/file1` but not
+    // in `file2`
+    let file_path1 = RepoPath::from_internal_string("dir/file1");
+    let file_path2 = RepoPath::from_internal_string("file2");
+    let tree1 = create_tree(repo, &[(file_path1, "1"), (file_path2, "1")]);
+    let tree2 = create_tree(repo, &[(file_path1, "2"), (file_path2, "2")]);
+    let tree3 = create_tree(repo, &[(file_path1, "3"), (file_path2, "1")]);
+    let tree4 = tree2.merge(&tree1, &tree3).unwrap();
+
+    let mut create_commit = |parent_ids, tree_id| {
+        mut_repo
+            .new_commit(&settings, parent_ids, tree_id)
+            .write()
+            .unwrap()
+    };
+    let commit1 = create_commit(vec![repo.store().root_commit_id().clone()], tree1.id());
+    let commit2 = create_commit(vec![commit1.id().clone()], tree2.id());
+    let commit3 = create_commit(vec![commit2.id().clone()], tree3.id());
+    let commit4 = create_commit(vec![commit3.id().clone()], tree4.id());
+
+    // Only commit4 has a conflict, and it's only in `dir/file1`
+    assert_eq!(
+        resolve_commit_ids_in_workspace(mut_repo, "conflict(dir)", &test_workspace.workspace, None),
+        vec![commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            "conflict(dir/file1)",
+            &test_workspace.workspace,
+            None
+        ),
+        vec![commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            "conflict(file2)",
+            &test_workspace.workspace,
+            None
+        ),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            "conflict(~dir)",
+            &test_workspace.workspace,
+            None
+        ),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            "conflict(all())",
+            &test_workspace.workspace,
+            None
+        ),
+        vec![commit4.id().clone()]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_tree_size() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    let small_path = RepoPath::from_internal_string("small");
+    let large_path = RepoPath::from_internal_string("dir/large");
+    let large_content = "x".repeat(1000);
+    let tree1 = create_tree(repo, &[(small_path, "small")]);
+    let other_path = RepoPath::from_internal_string("dir/other");
+    let tree2 = create_tree(repo, &[(small_path, "small"), (large_path, &large_content)]);
+    let tree3 = create_tree(
+        repo,
+        &[
+            (small_path, "small"),
+            (large_path, &large_content),
+            (other_path, "ab"),
+        ],
+    );
+
+    let mut create_commit = |parent_ids, tree_id| {
+        mut_repo
+            .new_commit(&settings, parent_ids, tree_id)
+            .write()
+            .unwrap()
+    };
+    let commit1 = create_commit(vec![repo.store().root_commit_id().clone()], tree1.id());
+    let commit2 = create_commit(vec![commit1.id().clone()], tree2.id());
+    let commit3 = create_commit(vec![commit2.id().clone()], tree3.id());
+
+    // Sizes of files in subdirectories are included
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "tree_size(1000)"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+    // The size must be larger than the threshold
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "tree_size(1004)"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "tree_size(1005)"),
+        vec![commit3.id().clone()]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "tree_size(1007)"), vec![]);
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "tree_size(1)"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    // The root commit has an empty tree
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "tree_size(0)"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("tree_size({})", u64::MAX)),
+        vec![]
+    );
+}
+
 #[test]
 fn test_reverse_graph_iterator() {
     let settings = testutils::user_settings();
@@ -3547,3 +5548,60 @@ fn test_revset_containing_fn() {
     assert!(!revset_has_commit(commit_c.id()));
     assert!(revset_has_commit(commit_d.id()));
 }
+
+#[test]
+fn test_optimize_preserves_semantics() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.mut_repo();
+
+    // 6
+    // |\
+    // 4 5
+    // | |
+    // 2 3
+    // |/
+    // 1
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit3]);
+    let commit6 = graph_builder.commit_with_parents(&[&commit4, &commit5]);
+    mut_repo.set_local_branch_target("b4", RefTarget::normal(commit4.id().clone()));
+    mut_repo.set_local_branch_target("b5", RefTarget::normal(commit5.id().clone()));
+
+    let aliases_map = RevsetAliasesMap::default();
+    let revset_extensions = RevsetExtensions::default();
+    let context = RevsetParseContext::new(
+        &aliases_map,
+        settings.user_emails(),
+        chrono::Utc::now().fixed_offset().into(),
+        None,
+        &revset_extensions,
+        None,
+    );
+    let [id1, id2, id3, id6] = [&commit1, &commit2, &commit3, &commit6].map(|c| c.id().hex());
+    let revset_strs = [
+        format!("heads(::{id6} ~ {id6})"),
+        format!("heads(::branches() & ~::{id2})"),
+        format!("roots({id2}:: | {id3}::)"),
+        format!("roots(all() ~ ::{id1})"),
+        format!("connected({id2} | {id6})"),
+        format!("connected(branches()) ~ {id1}::{id2}"),
+        format!("(({id1}+)::) & ::branches()"),
+        format!("~({id3}::) & ~::{id2}"),
+        format!("{id1}.. & ~::(b4 | b5)"),
+        format!("heads(({id1}|{id3})..{id6}) | roots({id6}-)"),
+        "::b4 & description(regex:'.') & ~empty()".to_owned(),
+        "(b4 | b5)- & ~(b4- ~ b5-)".to_owned(),
+    ];
+    for revset_str in &revset_strs {
+        let expression = parse(revset_str, &context).unwrap();
+        testutils::assert_optimize_preserves_semantics(&expression, mut_repo);
+    }
+}
@@ -20,6 +20,7 @@ use std::io::Read;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::Once;
 
@@ -48,6 +49,10 @@ use jj_lib::repo::RepoLoader;
 use jj_lib::repo::StoreFactories;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::revset;
+use jj_lib::revset::DefaultSymbolResolver;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::secret_backend::SecretBackend;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::Signer;
@@ -530,6 +535,33 @@ pub fn assert_abandoned_with_parent(
     new_parent_commit
 }
 
+/// Evaluates `expression` as is and after `revset::optimize()`, and asserts
+/// that both produce the same commits in the same order.
+///
+/// Symbols are resolved in the `repo` with the default symbol resolver. Use
+/// this to check that new optimization rules don't change the semantics.
+pub fn assert_optimize_preserves_semantics(expression: &Rc<RevsetExpression>, repo: &dyn Repo) {
+    let no_extensions: &[Box<dyn SymbolResolverExtension>] = &[];
+    let symbol_resolver = DefaultSymbolResolver::new(repo, no_extensions);
+    let evaluate = |expression: &Rc<RevsetExpression>| -> Vec<CommitId> {
+        expression
+            .clone()
+            .resolve_user_expression(repo, &symbol_resolver)
+            .unwrap()
+            .evaluate(repo)
+            .unwrap()
+            .iter()
+            .collect()
+    };
+    let optimized = revset::optimize(expression.clone());
+    assert_eq!(
+        evaluate(&optimized),
+        evaluate(expression),
+        "optimized expression evaluated differently\noriginal: {expression:#?}\noptimized: \
+         {optimized:#?}",
+    );
+}
+
 pub fn assert_no_forgotten_test_files(test_dir: &Path) {
     let runner_path = test_dir.join("runner.rs");
     let runner = fs::read_to_string(&runner_path).unwrap();